@@ -1,4 +1,5 @@
 use crate::ctx::Ctx;
+use crate::model::migration::{self, split_sql};
 use crate::model::user::{User, UserBmc};
 use crate::model::ModelManager;
 use sqlx::postgres::PgPoolOptions;
@@ -43,7 +44,11 @@ pub async fn init_dev_db() -> Result<(), Box<dyn std::error::Error>> {
 		pexec(&root_db, &sql_recreate_db_file).await?;
 	}
 
-	// -- Get sql files.
+	// -- Apply the migrations (i.e., the schema).
+	let mm = ModelManager::new().await?;
+	migration::migrate(&mm).await?;
+
+	// -- Get the dev seed sql files.
 	let mut paths: Vec<PathBuf> = fs::read_dir(sql_dir)?
 		.filter_map(|entry| entry.ok().map(|e| e.path()))
 		.collect();
@@ -63,7 +68,6 @@ pub async fn init_dev_db() -> Result<(), Box<dyn std::error::Error>> {
 	}
 
 	// -- Init model layer.
-	let mm = ModelManager::new().await?;
 	let ctx = Ctx::root_ctx();
 
 	// -- Set demo1 pwd
@@ -82,10 +86,7 @@ async fn pexec(db: &Db, file: &Path) -> Result<(), sqlx::Error> {
 	// -- Read the file.
 	let content = fs::read_to_string(file)?;
 
-	// FIXME: Make the split more sql proof.
-	let sqls: Vec<&str> = content.split(';').collect();
-
-	for sql in sqls {
+	for sql in split_sql(&content) {
 		sqlx::query(sql).execute(db).await?;
 	}
 
@@ -5,7 +5,10 @@ mod dev_db;
 use crate::ctx::Ctx;
 use crate::model::project::{ProjectBmc, ProjectForCreate};
 use crate::model::task::{Task, TaskBmc, TaskForCreate};
+use crate::model::user::{User, UserBmc};
+use crate::model::workspace::{WorkspaceBmc, WorkspaceFilter};
 use crate::model::{self, ModelManager};
+use serde_json::json;
 use tokio::sync::OnceCell;
 use tracing::info;
 
@@ -38,13 +41,35 @@ pub async fn init_test() -> ModelManager {
 	mm.clone()
 }
 
+/// Returns the `Ctx` of a dev seeded user (e.g., "demo1", "demo2"),
+/// with its default workspace as active workspace.
+pub async fn ctx_for_username(mm: &ModelManager, username: &str) -> Ctx {
+	let user: User = UserBmc::first_by_username(&Ctx::root_ctx(), mm, username)
+		.await
+		.unwrap()
+		.unwrap_or_else(|| panic!("dev user '{username}' not found"));
+
+	let ctx = Ctx::new(user.id).unwrap();
+	match WorkspaceBmc::default_id(&ctx, mm).await.unwrap() {
+		Some(workspace_id) => ctx.add_workspace_id(workspace_id),
+		None => ctx,
+	}
+}
+
+/// Create the project in the ctx active workspace,
+/// or in the dev seeded "Demo" workspace for a ctx without one (e.g., root ctx).
 pub async fn seed_project(
 	ctx: &Ctx,
 	mm: &ModelManager,
 	name: &str,
 ) -> model::Result<i64> {
+	let ctx = match ctx.workspace_id() {
+		Some(_) => ctx.clone(),
+		None => ctx.add_workspace_id(demo_workspace_id(mm).await?),
+	};
+
 	ProjectBmc::create(
-		ctx,
+		&ctx,
 		mm,
 		ProjectForCreate {
 			name: name.to_string(),
@@ -59,22 +84,30 @@ pub async fn seed_tasks(
 	project_id: i64,
 	titles: &[&str],
 ) -> model::Result<Vec<Task>> {
-	let mut tasks = Vec::new();
-
-	for title in titles {
-		let id = TaskBmc::create(
-			ctx,
-			mm,
-			TaskForCreate {
-				project_id,
-				title: title.to_string(),
-			},
-		)
-		.await?;
-		let task = TaskBmc::get(ctx, mm, id).await?;
-
-		tasks.push(task);
-	}
+	let tasks_c = titles
+		.iter()
+		.map(|title| TaskForCreate {
+			project_id,
+			title: title.to_string(),
+			..Default::default()
+		})
+		.collect();
+
+	let ids = TaskBmc::create_many(ctx, mm, tasks_c).await?;
+	let tasks = TaskBmc::get_many(ctx, mm, &ids).await?;
 
 	Ok(tasks)
 }
+
+/// Returns the id of the dev seeded "Demo" workspace.
+async fn demo_workspace_id(mm: &ModelManager) -> model::Result<i64> {
+	let filter: WorkspaceFilter =
+		serde_json::from_value(json!({"name": "Demo"})).unwrap();
+	let workspace =
+		WorkspaceBmc::list(&Ctx::root_ctx(), mm, Some(vec![filter]), None)
+			.await?
+			.pop()
+			.expect("dev workspace 'Demo' not found");
+
+	Ok(workspace.id)
+}
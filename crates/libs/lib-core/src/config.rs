@@ -1,4 +1,4 @@
-use lib_utils::envs::get_env;
+use lib_utils::envs::{get_env, get_env_parse};
 use std::sync::OnceLock;
 
 pub fn core_config() -> &'static CoreConfig {
@@ -15,6 +15,15 @@ pub fn core_config() -> &'static CoreConfig {
 pub struct CoreConfig {
 	// -- Db
 	pub DB_URL: String,
+	/// The read replicas db urls (possibly none).
+	pub DB_REPLICA_URLS: Vec<String>,
+	pub MIGRATIONS_DIR: String,
+	/// If the queries run with the ctx row-level security session
+	/// (see `model::store::rls`).
+	pub DB_RLS: bool,
+
+	// -- Blob
+	pub BLOB_STORE_DIR: String,
 
 	// -- Web
 	pub WEB_FOLDER: String,
@@ -25,6 +34,16 @@ impl CoreConfig {
 		Ok(CoreConfig {
 			// -- Db
 			DB_URL: get_env("SERVICE_DB_URL")?,
+			DB_REPLICA_URLS: get_env("SERVICE_DB_REPLICA_URLS")?
+				.split(',')
+				.map(|url| url.trim().to_string())
+				.filter(|url| !url.is_empty())
+				.collect(),
+			MIGRATIONS_DIR: get_env("SERVICE_MIGRATIONS_DIR")?,
+			DB_RLS: get_env_parse("SERVICE_DB_RLS")?,
+
+			// -- Blob
+			BLOB_STORE_DIR: get_env("SERVICE_BLOB_STORE_DIR")?,
 
 			// -- Web
 			WEB_FOLDER: get_env("SERVICE_WEB_FOLDER")?,
@@ -9,26 +9,54 @@ pub use self::error::{Error, Result};
 #[derive(Clone, Debug)]
 pub struct Ctx {
 	user_id: i64,
+
+	/// The active workspace, to which the projects (and their entities) are scoped.
+	workspace_id: Option<i64>,
 }
 
 // Constructors.
 impl Ctx {
 	pub fn root_ctx() -> Self {
-		Ctx { user_id: 0 }
+		Ctx {
+			user_id: 0,
+			workspace_id: None,
+		}
 	}
 
 	pub fn new(user_id: i64) -> Result<Self> {
 		if user_id == 0 {
 			Err(Error::CtxCannotNewRootCtx)
 		} else {
-			Ok(Self { user_id })
+			Ok(Self {
+				user_id,
+				workspace_id: None,
+			})
 		}
 	}
 }
 
+// Property Adders.
+impl Ctx {
+	/// Returns this ctx with the `workspace_id` active workspace.
+	pub fn add_workspace_id(&self, workspace_id: i64) -> Ctx {
+		let mut ctx = self.clone();
+		ctx.workspace_id = Some(workspace_id);
+		ctx
+	}
+}
+
 // Property Accessors.
 impl Ctx {
 	pub fn user_id(&self) -> i64 {
 		self.user_id
 	}
+
+	pub fn workspace_id(&self) -> Option<i64> {
+		self.workspace_id
+	}
+
+	/// Returns true if this is the root ctx (which bypasses the model access control).
+	pub fn is_root(&self) -> bool {
+		self.user_id == 0
+	}
 }
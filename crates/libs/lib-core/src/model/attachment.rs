@@ -0,0 +1,271 @@
+//! The task attachments (i.e., the files attached to the tasks).
+//!
+//! - The attachment row holds the file metadata, and its content
+//!   is in the `ModelManager` blob store (by the `blob_key`).
+//! - The attachments have the access of their task.
+//!
+//! Note: The blob writes are not transactional, so a create rolled back
+//!       by an outer transaction can leave an orphan blob
+//!       (harmless, but not reclaimed for now).
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc};
+use crate::model::blob::{BlobReader, BlobStore};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::ProjectBmc;
+use crate::model::project_member::ProjectRole;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Iden, Query};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+use tokio::io::AsyncRead;
+use uuid::Uuid;
+
+// region:    --- Attachment Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Attachment {
+	pub id: i64,
+	pub task_id: i64,
+
+	pub file_name: String,
+	pub content_type: String,
+	/// The content size in bytes.
+	pub size: i64,
+	/// The blob store key of the content (internal).
+	#[serde(skip)]
+	pub blob_key: String,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Deserialize)]
+pub struct AttachmentForCreate {
+	pub task_id: i64,
+	pub file_name: String,
+	pub content_type: String,
+}
+
+/// The `AttachmentForCreate` with its stored content `size` and `blob_key`.
+#[derive(Fields)]
+struct AttachmentForInsert {
+	task_id: i64,
+	file_name: String,
+	content_type: String,
+	size: i64,
+	blob_key: String,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct AttachmentFilter {
+	id: Option<OpValsInt64>,
+	task_id: Option<OpValsInt64>,
+	file_name: Option<OpValsString>,
+	content_type: Option<OpValsString>,
+	size: Option<OpValsInt64>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum AttachmentIden {
+	TaskId,
+}
+// endregion: --- Attachment Types
+
+// region:    --- AttachmentBmc
+pub struct AttachmentBmc;
+
+impl DbBmc for AttachmentBmc {
+	const TABLE: &'static str = "attachment";
+
+	/// The attachments have the access of their task.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let mut task_ids = Query::select();
+		task_ids.column(CommonIden::Id).from(TaskBmc::table_ref());
+		if let Some(access_cond) = base::ctx_access_cond::<TaskBmc>(ctx, access) {
+			task_ids.cond_where(access_cond);
+		}
+
+		Some(
+			Condition::all()
+				.add(Expr::col(AttachmentIden::TaskId).in_subquery(task_ids)),
+		)
+	}
+}
+
+impl AttachmentBmc {
+	/// Attach the file `content` to the task (streamed to the blob store,
+	/// before the attachment row insert, which deletes the blob when failing).
+	///
+	/// Note: Not to be called in a transaction (e.g., `ModelManager::in_txn`),
+	///       as it would be held for the whole content streaming.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		attachment_c: AttachmentForCreate,
+		content: impl AsyncRead + Unpin + Send,
+	) -> Result<i64> {
+		// -- Check the task access
+		let task = TaskBmc::get(ctx, mm, attachment_c.task_id).await?;
+		ProjectBmc::check_role(ctx, mm, task.project_id, ProjectRole::Editor)
+			.await?;
+
+		// -- Store the content
+		let blob_key = Uuid::new_v4().to_string();
+		let size = mm.blob_store().put(&blob_key, content).await?;
+
+		let AttachmentForCreate {
+			task_id,
+			file_name,
+			content_type,
+		} = attachment_c;
+		let attachment_i = AttachmentForInsert {
+			task_id,
+			file_name,
+			content_type,
+			size: size as i64,
+			blob_key: blob_key.clone(),
+		};
+
+		match base::create::<Self, _>(ctx, mm, attachment_i).await {
+			Ok(id) => Ok(id),
+			Err(ex) => {
+				let _ = mm.blob_store().delete(&blob_key).await;
+				Err(ex)
+			}
+		}
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Attachment> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	/// Returns the attachment with its content reader.
+	pub async fn open(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<(Attachment, BlobReader)> {
+		let attachment = Self::get(ctx, mm, id).await?;
+		let content = mm.blob_store().open(&attachment.blob_key).await?;
+
+		Ok((attachment, content))
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<AttachmentFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Attachment>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Delete the attachment and its content.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		let attachment = Self::get(ctx, mm, id).await?;
+		base::delete::<Self>(ctx, mm, id).await?;
+		mm.blob_store().delete(&attachment.blob_key).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- AttachmentBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::blob;
+	use crate::model::Error;
+	use anyhow::Result;
+	use serial_test::serial;
+	use std::io::Cursor;
+	use tokio::io::AsyncReadExt;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_open_delete_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_content = b"test_create_open_delete_ok content".to_vec();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_create_open_delete_ok project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_create_open_delete_ok task"],
+		)
+		.await?[0]
+			.id;
+
+		// -- Exec
+		let id = AttachmentBmc::create(
+			&ctx,
+			&mm,
+			AttachmentForCreate {
+				task_id: fx_task_id,
+				file_name: "notes.txt".to_string(),
+				content_type: "text/plain".to_string(),
+			},
+			Cursor::new(fx_content.clone()),
+		)
+		.await?;
+		let (attachment, mut reader) = AttachmentBmc::open(&ctx, &mm, id).await?;
+		let mut content = Vec::new();
+		reader.read_to_end(&mut content).await?;
+		let other_res = AttachmentBmc::open(&ctx_other, &mm, id).await;
+		AttachmentBmc::delete(&ctx, &mm, id).await?;
+		let blob_res = mm.blob_store().open(&attachment.blob_key).await;
+
+		// -- Check
+		assert_eq!(attachment.file_name, "notes.txt");
+		assert_eq!(attachment.size, fx_content.len() as i64);
+		assert_eq!(content, fx_content);
+		assert!(
+			matches!(other_res, Err(Error::AccessDenied { .. })),
+			"other should not open the attachment"
+		);
+		assert!(
+			matches!(blob_res, Err(blob::Error::BlobNotFound { .. })),
+			"blob should be deleted"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,238 @@
+//! Audit of the model writes.
+//!
+//! Each `base::create`, `update`, `delete` (and trash `restore`, `purge`) records
+//! an `entity_history` row with the entity table and id, the acting ctx user and time
+//! (`cid`, `ctime`), and the json diff of the changed columns
+//! (e.g., `{"title": {"old": "task A", "new": "task B"}}`).
+//!
+//! The `base::*_many` batch functions record one row per entity,
+//! with one multi-row insert.
+//!
+//! Each recorded write also records its `EntityChanged` outbox event
+//! (see `model::outbox`).
+//!
+//! The base write functions run in a transaction (nested in the caller one, if any,
+//! see `ModelManager::in_txn`), so the history rows and the outbox events
+//! are committed, or rolled back, with their write.
+//!
+//! See `model::entity_history` for reading them.
+
+use crate::ctx::Ctx;
+use crate::model::base::search::SEARCH_TSV_COLUMN;
+use crate::model::base::{CommonIden, DbBmc, TimestampIden};
+use crate::model::entity_history::{EntityAction, EntityHistoryBmc};
+use crate::model::outbox::{self, DomainEvent};
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::now_utc;
+use sea_query::{Alias, Expr, Iden, IntoIden, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// The credential columns (e.g., of the `user`), never recorded in the history.
+const CREDENTIAL_COLUMNS: &[&str] = &["pwd", "pwd_salt", "token_salt"];
+
+#[derive(Iden)]
+enum EntityHistoryIden {
+	Entity,
+	EntityId,
+	Action,
+	Diff,
+}
+
+/// Returns the json snapshot of the row `id` of the `MC` entity
+/// (regardless of the ctx access and trash state), or `None` if there is no such row.
+pub(super) async fn row_snapshot<MC>(
+	mm: &ModelManager,
+	id: i64,
+) -> Result<Option<Value>>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.expr(Expr::cust("row_to_json(r)"))
+		.from_as(MC::table_ref(), Alias::new("r"))
+		.and_where(Expr::col(CommonIden::Id).eq(id));
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (Value,), _>(&sql, values);
+	let snapshot = dbx
+		.fetch_optional(sqlx_query)
+		.await?
+		.map(|(snapshot,)| snapshot);
+
+	Ok(snapshot)
+}
+
+/// Returns the json snapshots of the rows `ids` of the `MC` entity by id
+/// (regardless of the ctx access and trash state).
+pub(super) async fn row_snapshots<MC>(
+	mm: &ModelManager,
+	ids: &[i64],
+) -> Result<HashMap<i64, Value>>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.column(CommonIden::Id)
+		.expr(Expr::cust("row_to_json(r)"))
+		.from_as(MC::table_ref(), Alias::new("r"))
+		.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()));
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64, Value), _>(&sql, values);
+	let snapshots = dbx.fetch_all(sqlx_query).await?;
+
+	Ok(snapshots.into_iter().collect())
+}
+
+/// The `before` and `after` row snapshots of the entity `id` for a model write.
+pub(super) struct RowChange {
+	pub id: i64,
+	pub before: Option<Value>,
+	pub after: Option<Value>,
+}
+
+/// Records the `action` on the entity `id` in the `entity_history`,
+/// with the diff between the `before` and `after` row snapshots.
+pub(super) async fn record<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	action: EntityAction,
+	before: Option<Value>,
+	after: Option<Value>,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
+	let change = RowChange { id, before, after };
+	record_many::<MC>(ctx, mm, action, vec![change]).await
+}
+
+/// Records the `action` on each of the entity `changes` in the `entity_history`,
+/// in one multi-row insert (and their outbox events).
+pub(super) async fn record_many<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	action: EntityAction,
+	changes: Vec<RowChange>,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
+	if changes.is_empty() {
+		return Ok(());
+	}
+
+	let dbx = mm.dbx_for(ctx);
+	let now = now_utc();
+	let events = changes
+		.iter()
+		.map(|change| DomainEvent::EntityChanged {
+			entity: MC::TABLE.to_string(),
+			entity_id: change.id,
+			action,
+		})
+		.collect();
+
+	// -- Build query
+	let mut query = Query::insert();
+	query.into_table(EntityHistoryBmc::table_ref()).columns([
+		EntityHistoryIden::Entity.into_iden(),
+		EntityHistoryIden::EntityId.into_iden(),
+		EntityHistoryIden::Action.into_iden(),
+		EntityHistoryIden::Diff.into_iden(),
+		TimestampIden::Cid.into_iden(),
+		TimestampIden::Ctime.into_iden(),
+	]);
+	// Note: The history rows are immutable, so they only have the creation timestamps.
+	for RowChange { id, before, after } in changes {
+		let diff = json_diff(before.as_ref(), after.as_ref());
+		query.values([
+			MC::TABLE.into(),
+			id.into(),
+			action.into(),
+			diff.into(),
+			ctx.user_id().into(),
+			now.into(),
+		])?;
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_with(&sql, values);
+	dbx.execute(sqlx_query).await?;
+
+	// -- Record the outbox events
+	outbox::record(ctx, mm, events).await?;
+
+	Ok(())
+}
+
+// region:    --- Utils
+
+/// Returns the json object of the columns that differ between the `before` and `after`
+/// row snapshots, as `{"column": {"old": .., "new": ..}}`
+/// (`old` is absent for a created row, and `new` for a deleted one).
+///
+/// Note: The timestamp columns are skipped, since they are the history row `cid`/`ctime`,
+///       and so are the generated `search_tsv` column, and the credential columns.
+fn json_diff(before: Option<&Value>, after: Option<&Value>) -> Value {
+	let empty = Map::new();
+	let before = before.and_then(Value::as_object).unwrap_or(&empty);
+	let after = after.and_then(Value::as_object).unwrap_or(&empty);
+
+	let mut diff = Map::new();
+	for name in before.keys().chain(after.keys()) {
+		if diff.contains_key(name)
+			|| is_timestamp_column(name)
+			|| name == SEARCH_TSV_COLUMN
+			|| CREDENTIAL_COLUMNS.contains(&name.as_str())
+		{
+			continue;
+		}
+
+		let (old, new) = (before.get(name), after.get(name));
+		if old == new {
+			continue;
+		}
+
+		let mut change = Map::new();
+		if let Some(old) = old {
+			change.insert("old".to_string(), old.clone());
+		}
+		if let Some(new) = new {
+			change.insert("new".to_string(), new.clone());
+		}
+		diff.insert(name.to_string(), Value::Object(change));
+	}
+
+	Value::Object(diff)
+}
+
+fn is_timestamp_column(name: &str) -> bool {
+	[
+		TimestampIden::Cid,
+		TimestampIden::Ctime,
+		TimestampIden::Mid,
+		TimestampIden::Mtime,
+		TimestampIden::Did,
+		TimestampIden::Dtime,
+	]
+	.iter()
+	.any(|iden| iden.to_string() == name)
+}
+
+// endregion: --- Utils
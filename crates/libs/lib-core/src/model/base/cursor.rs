@@ -0,0 +1,279 @@
+//! Keyset (cursor) pagination for the base list.
+//!
+//! - The list is ordered by the requested `order_bys`, plus the `id` as the
+//!   tie-breaker, so that the order is total and stable.
+//! - The `next_cursor` is the opaque (base64url json) encoding of the `order_bys`,
+//!   the hash of the filters, and the values of the `order_bys` columns for the last
+//!   row of the page (i.e., a cursor is only valid for the same filters and order).
+//! - The next page is the rows strictly after these values (in the `order_bys` order),
+//!   so that concurrent inserts or deletes do not skip or duplicate rows,
+//!   and deep pages are as fast as the first one.
+//!
+//! Note: The cursor values are the column text representations, compared as
+//!       SQL literals (coerced to the column types by Postgres).
+
+use crate::ctx::Ctx;
+use crate::model::base::{
+	and_where_row_scope, compute_list_options, ctx_access_cond, AccessKind, DbBmc,
+	RowScope,
+};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::b64::{b64u_decode_to_string, b64u_encode};
+use modql::field::HasFields;
+use modql::filter::{FilterGroups, ListOptions, OrderBy};
+use modql::StringIden;
+use sea_query::{
+	Alias, Condition, Expr, Func, PostgresQueryBuilder, Query, SimpleExpr, Value,
+};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use sqlx::postgres::PgRow;
+use sqlx::{FromRow, Row};
+
+/// The alias of the selected cursor values column.
+const CURSOR_COLUMN: &str = "_cursor";
+
+/// A page of a keyset paginated list.
+#[derive(Debug, Serialize)]
+pub struct ListPage<E> {
+	pub items: Vec<E>,
+	/// The cursor of the next page, or `None` for the last page.
+	pub next_cursor: Option<String>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct Cursor {
+	/// The effective order bys (e.g., `["!ctime", "id"]`).
+	order_bys: Vec<String>,
+	/// The hash of the filters condition (see `filter_hash`).
+	filter_hash: String,
+	/// The last row values of the `order_bys` columns.
+	values: Vec<Option<String>>,
+}
+
+/// List a page of entities after the `cursor` (or the first page if `None`).
+///
+/// Note: The `list_options.offset` is ignored (the cursor is the position).
+pub async fn list_page<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	list_options: Option<ListOptions>,
+	cursor: Option<String>,
+) -> Result<ListPage<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	// Note: Reads from a read replica, when possible (see `Dbx`).
+	let dbx = mm.dbx_for(ctx).read_replica();
+
+	let list_options = compute_list_options(list_options)?;
+	// Note: `compute_list_options` always set the limit.
+	let limit = list_options.limit.unwrap_or_default().max(0);
+	let order_bys = keyset_order_bys(list_options);
+
+	// -- Build the query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.columns(E::field_column_refs())
+		.expr_as(cursor_values_expr(&order_bys), Alias::new(CURSOR_COLUMN));
+	and_where_row_scope::<MC>(&mut query, RowScope::Active);
+
+	// condition from filter
+	let filter_cond: Option<Condition> = match filter {
+		Some(filter) => {
+			let filters: FilterGroups = filter.into();
+			Some(filters.try_into()?)
+		}
+		None => None,
+	};
+	let filter_hash = filter_hash(filter_cond.as_ref());
+	if let Some(filter_cond) = filter_cond {
+		query.cond_where(filter_cond);
+	}
+	// condition from ctx access
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		query.cond_where(access_cond);
+	}
+	// condition from cursor
+	if let Some(cursor) = cursor {
+		let values = decode_cursor(&cursor, &order_bys, &filter_hash)?;
+		query.cond_where(after_cursor_cond(&order_bys, values));
+	}
+	// order and limit (one more to know if there is a next page)
+	for order_by in order_bys.iter().cloned() {
+		let (col, order) = order_by.into_sea_col_order();
+		query.order_by(col, order);
+	}
+	query.limit(limit as u64 + 1);
+
+	// -- Execute the query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_with(&sql, values);
+	let mut rows = dbx.fetch_all_rows(sqlx_query).await?;
+
+	// -- Build the page
+	let next_cursor = if rows.len() > limit as usize {
+		rows.truncate(limit as usize);
+		match rows.last() {
+			Some(last_row) => {
+				let values: Vec<Option<String>> =
+					serde_json::from_value(last_row.try_get(CURSOR_COLUMN)?)
+						.map_err(|_| Error::ListCursorInvalid)?;
+				Some(encode_cursor(&order_bys, filter_hash, values))
+			}
+			None => None,
+		}
+	} else {
+		None
+	};
+	let items = rows
+		.iter()
+		.map(E::from_row)
+		.collect::<core::result::Result<Vec<E>, _>>()?;
+
+	Ok(ListPage { items, next_cursor })
+}
+
+// region:    --- Utils
+
+/// Returns the list options order bys (default `id`), plus the `id` tie-breaker.
+fn keyset_order_bys(list_options: ListOptions) -> Vec<OrderBy> {
+	let mut order_bys: Vec<OrderBy> = list_options
+		.order_bys
+		.map(|order_bys| order_bys.order_bys())
+		.unwrap_or_default();
+
+	let has_id = order_bys
+		.iter()
+		.any(|order_by| order_by_col(order_by) == "id");
+	if !has_id {
+		order_bys.push(OrderBy::Asc("id".to_string()));
+	}
+
+	order_bys
+}
+
+fn order_by_col(order_by: &OrderBy) -> &str {
+	match order_by {
+		OrderBy::Asc(col) | OrderBy::Desc(col) => col,
+	}
+}
+
+/// Returns the order by as in the list options (e.g., `"title"` or `"!ctime"`).
+fn order_by_to_string(order_by: &OrderBy) -> String {
+	match order_by {
+		OrderBy::Asc(col) => col.to_string(),
+		OrderBy::Desc(col) => format!("!{col}"),
+	}
+}
+
+/// Returns the `json_build_array(col_1::text, ...)` of the order bys columns.
+fn cursor_values_expr(order_bys: &[OrderBy]) -> SimpleExpr {
+	let args = order_bys.iter().map(|order_by| {
+		Expr::col(StringIden(order_by_col(order_by).to_string()))
+			.cast_as(Alias::new("text"))
+	});
+
+	Func::cust(Alias::new("json_build_array")).args(args).into()
+}
+
+/// Returns the (base64url) hash of the filters condition SQL
+/// (with its values inlined, so that other values are other filters).
+fn filter_hash(filter_cond: Option<&Condition>) -> String {
+	let mut query = Query::select();
+	query.expr(Expr::val(1));
+	if let Some(filter_cond) = filter_cond {
+		query.cond_where(filter_cond.clone());
+	}
+	let sql = query.to_string(PostgresQueryBuilder);
+
+	b64u_encode(Sha256::digest(sql.as_bytes()))
+}
+
+fn encode_cursor(
+	order_bys: &[OrderBy],
+	filter_hash: String,
+	values: Vec<Option<String>>,
+) -> String {
+	let cursor = Cursor {
+		order_bys: order_bys.iter().map(order_by_to_string).collect(),
+		filter_hash,
+		values,
+	};
+	// Note: Serializing this struct to json cannot fail.
+	let json = serde_json::to_string(&cursor).unwrap_or_default();
+
+	b64u_encode(json)
+}
+
+/// Returns the cursor values, if the cursor was built for these `order_bys`
+/// and filters (i.e., their `filter_hash`).
+fn decode_cursor(
+	cursor: &str,
+	order_bys: &[OrderBy],
+	filter_hash: &str,
+) -> Result<Vec<Option<String>>> {
+	let cursor: Cursor = b64u_decode_to_string(cursor)
+		.ok()
+		.and_then(|json| serde_json::from_str(&json).ok())
+		.ok_or(Error::ListCursorInvalid)?;
+
+	let order_bys: Vec<String> = order_bys.iter().map(order_by_to_string).collect();
+	if cursor.order_bys != order_bys
+		|| cursor.filter_hash != filter_hash
+		|| cursor.values.len() != order_bys.len()
+	{
+		return Err(Error::ListCursorInvalid);
+	}
+
+	Ok(cursor.values)
+}
+
+/// Returns the condition matching the rows after the cursor `values`
+/// in the `order_bys` order, i.e., for `(a ASC, b DESC, id ASC)`:
+///
+/// `a > va OR (a = va AND b < vb) OR (a = va AND b = vb AND id > vid)`
+///
+/// Note: With the Postgres default, the NULLs are last for `ASC`, and first for `DESC`.
+fn after_cursor_cond(
+	order_bys: &[OrderBy],
+	values: Vec<Option<String>>,
+) -> Condition {
+	let mut cond = Condition::any();
+	let mut equal_cond = Condition::all();
+
+	for (order_by, value) in order_bys.iter().zip(values) {
+		let col = || Expr::col(StringIden(order_by_col(order_by).to_string()));
+		// Note: The literal (vs. bound) string value is coerced to the column type.
+		let value =
+			value.map(|v| SimpleExpr::Constant(Value::String(Some(Box::new(v)))));
+
+		let after: Option<SimpleExpr> = match (order_by, &value) {
+			(OrderBy::Asc(_), Some(value)) => {
+				Some(col().gt(value.clone()).or(col().is_null()))
+			}
+			(OrderBy::Asc(_), None) => None,
+			(OrderBy::Desc(_), Some(value)) => Some(col().lt(value.clone())),
+			(OrderBy::Desc(_), None) => Some(col().is_not_null()),
+		};
+		if let Some(after) = after {
+			cond = cond.add(equal_cond.clone().add(after));
+		}
+
+		equal_cond = match value {
+			Some(value) => equal_cond.add(col().eq(value)),
+			None => equal_cond.add(col().is_null()),
+		};
+	}
+
+	cond
+}
+
+// endregion: --- Utils
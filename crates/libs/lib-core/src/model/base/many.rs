@@ -0,0 +1,517 @@
+//! Batch (multi-row) variants of the base CRUD functions.
+//!
+//! - `create_many` inserts the rows with multi-row inserts (by chunks of `MANY_CHUNK_SIZE`),
+//!   with their ids allocated beforehand, in the data order.
+//! - `update_many` and `delete_many` target the entities by id list or by filter
+//!   (see `ManyTarget`), with one statement for all of them.
+//! - `update_each` updates each entity with its own data, with one statement
+//!   (by chunks) for all of them.
+//!
+//! Note: Each of them runs in a transaction (see `ModelManager::in_txn`),
+//!       so the batch is all-or-nothing (e.g., all the `create_many` chunks).
+
+use crate::ctx::Ctx;
+use crate::model::base::audit::{self, RowChange};
+use crate::model::base::{
+	add_timestamps_for_create, add_timestamps_for_delete, add_timestamps_for_update,
+	and_where_row_scope, ctx_access_cond, not_found_or_access_denied, AccessKind,
+	CommonIden, DbBmc, RowScope,
+};
+use crate::model::entity_history::EntityAction;
+use crate::model::ModelManager;
+use crate::model::Result;
+use modql::field::{Field, Fields, HasFields};
+use modql::filter::FilterGroups;
+use sea_query::{
+	CaseStatement, Condition, DynIden, Expr, IntoIden, Order, PostgresQueryBuilder,
+	Query, SimpleExpr,
+};
+use sea_query_binder::SqlxBinder;
+use serde::Deserialize;
+use sqlx::postgres::PgRow;
+use sqlx::FromRow;
+use std::collections::{HashMap, HashSet};
+
+/// The max number of rows per multi-row insert.
+/// (well under the 65535 bind parameters limit of Postgres)
+const MANY_CHUNK_SIZE: usize = 1000;
+
+/// The entities targeted by `update_many` and `delete_many`.
+///
+/// In json (e.g., rpc params), `{"ids": [123, 124]}` or `{"filters": [..]}`.
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ManyTarget<F> {
+	/// All these entities, or an `EntityNotFound`/`AccessDenied` error
+	/// for the first one not writable by the ctx user.
+	Ids(Vec<i64>),
+	/// The entities matching this filter and writable by the ctx user.
+	Filters(F),
+}
+
+/// Create the entities, returning their ids in the `data` order.
+pub async fn create_many<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	data: Vec<E>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+
+		// -- Allocate the ids, in the `data` order
+		//    (the `RETURNING` order is not guaranteed to be the `VALUES` one)
+		let ids = next_ids::<MC>(mm, data.len()).await?;
+
+		// -- Extract the fields of each row
+		let rows: Vec<Vec<Field>> = data
+			.into_iter()
+			.zip(ids.iter())
+			.map(|(data, &id)| {
+				let mut fields = data.not_none_fields();
+				fields.push(Field::new(CommonIden::Id.into_iden(), id.into()));
+				add_timestamps_for_create(&mut fields, ctx.user_id());
+				fields.into_vec()
+			})
+			.collect();
+
+		// -- Insert by chunks
+		for chunk in rows.chunks(MANY_CHUNK_SIZE) {
+			// The columns are the union of the rows columns
+			// (the rows missing some get their column DEFAULT).
+			let mut columns: Vec<DynIden> = Vec::new();
+			for field in chunk.iter().flatten() {
+				if !columns
+					.iter()
+					.any(|col| col.to_string() == field.iden.to_string())
+				{
+					columns.push(field.iden.clone());
+				}
+			}
+
+			// -- Build query
+			let mut query = Query::insert();
+			query.into_table(MC::table_ref()).columns(columns.clone());
+			for row in chunk {
+				let mut value_by_col: HashMap<String, SimpleExpr> = row
+					.iter()
+					.map(|field| (field.iden.to_string(), field.value.clone()))
+					.collect();
+				let sea_values: Vec<SimpleExpr> = columns
+					.iter()
+					.map(|col| {
+						value_by_col
+							.remove(&col.to_string())
+							.unwrap_or_else(|| Expr::cust("DEFAULT"))
+					})
+					.collect();
+				query.values(sea_values)?;
+			}
+
+			// -- Exec query
+			let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+			let sqlx_query = sqlx::query_with(&sql, values);
+			dbx.execute(sqlx_query).await?;
+		}
+
+		// -- Record history
+		let mut afters = audit::row_snapshots::<MC>(mm, &ids).await?;
+		let changes = ids
+			.iter()
+			.map(|&id| RowChange {
+				id,
+				before: None,
+				after: afters.remove(&id),
+			})
+			.collect();
+		audit::record_many::<MC>(ctx, mm, EntityAction::Create, changes).await?;
+
+		Ok(ids)
+	})
+	.await
+}
+
+/// Get the entities `ids` readable by the ctx user, ordered by id.
+///
+/// Note: Unlike `get`, the missing or not readable ids are just skipped.
+pub async fn get_many<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	ids: &[i64],
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	get_many_in_scope::<MC, E>(ctx, mm, ids, RowScope::Active).await
+}
+
+/// Get the entities `ids` from the trash (see `DbBmc::SOFT_DELETE`).
+pub async fn get_many_trashed<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	ids: &[i64],
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	get_many_in_scope::<MC, E>(ctx, mm, ids, RowScope::Trashed).await
+}
+
+async fn get_many_in_scope<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	ids: &[i64],
+	scope: RowScope,
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	let dbx = mm.dbx_for(ctx);
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.columns(E::field_column_refs())
+		.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()))
+		.order_by(CommonIden::Id, Order::Asc);
+	and_where_row_scope::<MC>(&mut query, scope);
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		query.cond_where(access_cond);
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+	let entities = dbx.fetch_all(sqlx_query).await?;
+
+	Ok(entities)
+}
+
+/// Update the `target` entities with the same `data`, returning their ids.
+pub async fn update_many<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: ManyTarget<F>,
+	data: E,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+	F: Into<FilterGroups>,
+{
+	let mut fields = data.not_none_fields();
+	add_timestamps_for_update(&mut fields, ctx.user_id());
+
+	update_target_fields::<MC, F>(ctx, mm, target, fields, EntityAction::Update)
+		.await
+}
+
+/// Delete the `target` entities, returning their ids.
+/// (moved to the trash for the `DbBmc::SOFT_DELETE` entities)
+pub async fn delete_many<MC, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: ManyTarget<F>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+{
+	if MC::SOFT_DELETE {
+		let mut fields = Fields::new(Vec::new());
+		add_timestamps_for_delete(&mut fields, ctx.user_id());
+		return update_target_fields::<MC, F>(
+			ctx,
+			mm,
+			target,
+			fields,
+			EntityAction::Delete,
+		)
+		.await;
+	}
+
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+		let ids = target_ids_for_write::<MC, F>(ctx, mm, target).await?;
+		if ids.is_empty() {
+			return Ok(ids);
+		}
+
+		// -- Snapshot for history
+		let mut befores = audit::row_snapshots::<MC>(mm, &ids).await?;
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		dbx.execute(sqlx_query).await?;
+
+		// -- Record history
+		let changes = ids
+			.iter()
+			.map(|&id| RowChange {
+				id,
+				before: befores.remove(&id),
+				after: None,
+			})
+			.collect();
+		audit::record_many::<MC>(ctx, mm, EntityAction::Delete, changes).await?;
+
+		Ok(ids)
+	})
+	.await
+}
+
+/// Update each of the entities `(id, data)` with its own `data`
+/// (e.g., a renumbering), with one statement for all of them (by chunks),
+/// returning their ids.
+///
+/// Returns the `EntityNotFound`/`AccessDenied` error of the first id
+/// which is not writable by the ctx user (like `ManyTarget::Ids`).
+pub async fn update_each<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	data: Vec<(i64, E)>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+		let target = ManyTarget::<FilterGroups>::Ids(
+			data.iter().map(|(id, _)| *id).collect(),
+		);
+		let ids = target_ids_for_write::<MC, _>(ctx, mm, target).await?;
+		if ids.is_empty() {
+			return Ok(ids);
+		}
+
+		// -- Snapshot for history
+		let mut befores = audit::row_snapshots::<MC>(mm, &ids).await?;
+
+		// -- Update by chunks
+		let rows: Vec<(i64, Vec<Field>)> = data
+			.into_iter()
+			.map(|(id, data)| (id, data.not_none_fields().into_vec()))
+			.collect();
+		for chunk in rows.chunks(MANY_CHUNK_SIZE) {
+			// Each column is set to its value for the row id
+			// (or kept for the rows without it).
+			let mut case_by_col: Vec<(DynIden, CaseStatement)> = Vec::new();
+			for (id, fields) in chunk {
+				for field in fields {
+					let when = Expr::col(CommonIden::Id).eq(*id);
+					let col = field.iden.to_string();
+					match case_by_col.iter_mut().find(|(c, _)| c.to_string() == col)
+					{
+						Some((_, case)) => {
+							*case = case.clone().case(when, field.value.clone());
+						}
+						None => case_by_col.push((
+							field.iden.clone(),
+							Expr::case(when, field.value.clone()),
+						)),
+					}
+				}
+			}
+			let mut fields = Fields::new(Vec::new());
+			add_timestamps_for_update(&mut fields, ctx.user_id());
+			let mut values: Vec<(DynIden, SimpleExpr)> = case_by_col
+				.into_iter()
+				.map(|(col, case)| {
+					let value = case.finally(Expr::col(col.clone()));
+					(col, value.into())
+				})
+				.collect();
+			values.extend(fields.for_sea_update());
+
+			// -- Build query
+			let mut query = Query::update();
+			query.table(MC::table_ref()).values(values).and_where(
+				Expr::col(CommonIden::Id).is_in(chunk.iter().map(|(id, _)| *id)),
+			);
+
+			// -- Exec query
+			let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+			let sqlx_query = sqlx::query_with(&sql, values);
+			dbx.execute(sqlx_query).await?;
+		}
+
+		// -- Record history
+		let mut afters = audit::row_snapshots::<MC>(mm, &ids).await?;
+		let changes = ids
+			.iter()
+			.map(|&id| RowChange {
+				id,
+				before: befores.remove(&id),
+				after: afters.remove(&id),
+			})
+			.collect();
+		audit::record_many::<MC>(ctx, mm, EntityAction::Update, changes).await?;
+
+		Ok(ids)
+	})
+	.await
+}
+
+// region:    --- Utils
+
+/// Update the `fields` of the `target` entities, recording the `action`
+/// in their history, and returning their ids.
+async fn update_target_fields<MC, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: ManyTarget<F>,
+	fields: Fields,
+	action: EntityAction,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+		let ids = target_ids_for_write::<MC, F>(ctx, mm, target).await?;
+		if ids.is_empty() {
+			return Ok(ids);
+		}
+
+		// -- Snapshot for history
+		let mut befores = audit::row_snapshots::<MC>(mm, &ids).await?;
+
+		// -- Build query
+		let mut query = Query::update();
+		query
+			.table(MC::table_ref())
+			.values(fields.for_sea_update())
+			.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		dbx.execute(sqlx_query).await?;
+
+		// -- Record history
+		let mut afters = audit::row_snapshots::<MC>(mm, &ids).await?;
+		let changes = ids
+			.iter()
+			.map(|&id| RowChange {
+				id,
+				before: befores.remove(&id),
+				after: afters.remove(&id),
+			})
+			.collect();
+		audit::record_many::<MC>(ctx, mm, action, changes).await?;
+
+		Ok(ids)
+	})
+	.await
+}
+
+/// Returns the ids of the active `target` entities writable by the ctx user,
+/// ordered by id.
+///
+/// For `ManyTarget::Ids`, returns the `EntityNotFound`/`AccessDenied` error
+/// of the first id which is not.
+pub(in crate::model) async fn target_ids_for_write<MC, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	target: ManyTarget<F>,
+) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+{
+	let dbx = mm.dbx_for(ctx);
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.order_by(CommonIden::Id, Order::Asc);
+	and_where_row_scope::<MC>(&mut query, RowScope::Active);
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+		query.cond_where(access_cond);
+	}
+	let target_ids = match target {
+		ManyTarget::Ids(ids) => {
+			query.and_where(Expr::col(CommonIden::Id).is_in(ids.iter().copied()));
+			Some(ids)
+		}
+		ManyTarget::Filters(filter) => {
+			let filters: FilterGroups = filter.into();
+			let cond: Condition = filters.try_into()?;
+			query.cond_where(cond);
+			None
+		}
+	};
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let ids: Vec<i64> = dbx
+		.fetch_all(sqlx_query)
+		.await?
+		.into_iter()
+		.map(|(id,)| id)
+		.collect();
+
+	// -- Check that all the target ids are writable
+	if let Some(target_ids) = target_ids {
+		let writable_ids: HashSet<i64> = ids.iter().copied().collect();
+		if let Some(&id) = target_ids.iter().find(|id| !writable_ids.contains(id)) {
+			return Err(
+				not_found_or_access_denied::<MC>(mm, id, RowScope::Active).await?
+			);
+		}
+	}
+
+	Ok(ids)
+}
+
+/// Returns `count` new ids of the `MC` table, from its id sequence.
+///
+/// Note: With the table owner role (the rls roles are not granted the sequences).
+async fn next_ids<MC>(mm: &ModelManager, count: usize) -> Result<Vec<i64>>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx();
+
+	let sqlx_query = sqlx::query_as::<_, (i64,)>(
+		"SELECT nextval(pg_get_serial_sequence($1, 'id')) FROM generate_series(1, $2)",
+	)
+	.bind(MC::TABLE)
+	.bind(count as i64);
+	let ids = dbx
+		.fetch_all(sqlx_query)
+		.await?
+		.into_iter()
+		.map(|(id,)| id)
+		.collect();
+
+	Ok(ids)
+}
+
+// endregion: --- Utils
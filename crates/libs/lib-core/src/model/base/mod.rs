@@ -0,0 +1,836 @@
+// region:    --- Modules
+
+mod audit;
+mod cursor;
+mod many;
+mod search;
+
+pub use self::cursor::{list_page, ListPage};
+pub(in crate::model) use self::many::target_ids_for_write;
+pub use self::many::{
+	create_many, delete_many, get_many, get_many_trashed, update_each, update_many,
+	ManyTarget,
+};
+pub use self::search::search_select;
+
+use crate::ctx::Ctx;
+use crate::model::entity_history::EntityAction;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::now_utc;
+use modql::field::{Field, Fields, HasFields};
+use modql::filter::{FilterGroups, ListOptions};
+use modql::SIden;
+use sea_query::{
+	Asterisk, Condition, ConditionalStatement, Expr, Iden, IntoIden,
+	PostgresQueryBuilder, Query, TableRef, Value,
+};
+use sea_query_binder::SqlxBinder;
+use serde::Serialize;
+use sqlx::postgres::PgRow;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// endregion: --- Modules
+
+/// A list of entities with their pagination meta
+/// (e.g., for the "Showing 51-100 of 3,214" UIs).
+#[derive(Debug, Serialize)]
+pub struct ListWithMeta<E> {
+	pub items: Vec<E>,
+	/// The total number of entities matching the filter (regardless of the limit/offset).
+	pub total: i64,
+	pub limit: i64,
+	pub offset: i64,
+}
+
+const LIST_LIMIT_DEFAULT: i64 = 1000;
+const LIST_LIMIT_MAX: i64 = 5000;
+
+#[derive(Iden)]
+pub enum CommonIden {
+	Id,
+}
+
+#[derive(Iden)]
+pub enum TimestampIden {
+	Cid,
+	Ctime,
+	Mid,
+	Mtime,
+	Did,
+	Dtime,
+}
+
+/// The kind of access a model operation requires on the entity rows.
+/// (e.g., `get` and `list` are `Read`, `update` and `delete` are `Write`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AccessKind {
+	Read,
+	Write,
+}
+
+pub trait DbBmc {
+	const TABLE: &'static str;
+
+	/// When `true`, the entity rows are soft deleted,
+	/// i.e., `delete` sets their `did`/`dtime` and moves them to the trash,
+	/// from where they can be restored or purged.
+	///
+	/// Note: The table must have the nullable `did` and `dtime` columns.
+	const SOFT_DELETE: bool = false;
+
+	fn table_ref() -> TableRef {
+		TableRef::Table(SIden(Self::TABLE).into_iden())
+	}
+
+	/// Returns the condition restricting the rows of this entity
+	/// to the ones accessible by the `ctx` user for this `access` kind.
+	///
+	/// Default to `None` (i.e., no access restriction).
+	///
+	/// Note: This is never called for the root ctx, which bypasses the
+	///       access control (see `ctx_access_cond`).
+	fn access_cond(_ctx: &Ctx, _access: AccessKind) -> Option<Condition> {
+		None
+	}
+}
+
+/// The rows a query applies to for the soft delete entities
+/// (i.e., the ones not in the trash, or the ones in the trash).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum RowScope {
+	Active,
+	Trashed,
+}
+
+/// Returns the access condition of the `MC` entity for this `ctx` and `access` kind,
+/// or `None` for the root ctx.
+pub fn ctx_access_cond<MC>(ctx: &Ctx, access: AccessKind) -> Option<Condition>
+where
+	MC: DbBmc,
+{
+	if ctx.is_root() {
+		None
+	} else {
+		MC::access_cond(ctx, access)
+	}
+}
+
+pub fn compute_list_options(
+	list_options: Option<ListOptions>,
+) -> Result<ListOptions> {
+	if let Some(mut list_options) = list_options {
+		// Validate the limit.
+		if let Some(limit) = list_options.limit {
+			if limit > LIST_LIMIT_MAX {
+				return Err(Error::ListLimitOverMax {
+					max: LIST_LIMIT_MAX,
+					actual: limit,
+				});
+			}
+		}
+		// Set the default limit if no limit
+		else {
+			list_options.limit = Some(LIST_LIMIT_DEFAULT);
+		}
+		Ok(list_options)
+	}
+	// When None, return default
+	else {
+		Ok(ListOptions {
+			limit: Some(LIST_LIMIT_DEFAULT),
+			offset: None,
+			order_bys: Some("id".into()),
+		})
+	}
+}
+
+pub async fn create<MC, E>(ctx: &Ctx, mm: &ModelManager, data: E) -> Result<i64>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+
+		// -- Extract fields (name / sea-query value expression)
+		let mut fields = data.not_none_fields();
+		add_timestamps_for_create(&mut fields, ctx.user_id());
+		let (columns, sea_values) = fields.for_sea_insert();
+
+		// -- Build query
+		let mut query = Query::insert();
+		query
+			.into_table(MC::table_ref())
+			.columns(columns)
+			.values(sea_values)?
+			.returning(Query::returning().columns([CommonIden::Id]));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let (id,) = dbx.fetch_one(sqlx_query).await?;
+
+		// -- Record history
+		let after = audit::row_snapshot::<MC>(mm, id).await?;
+		audit::record::<MC>(ctx, mm, id, EntityAction::Create, None, after).await?;
+
+		Ok(id)
+	})
+	.await
+}
+
+pub async fn get<MC, E>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	get_in_scope::<MC, E>(ctx, mm, id, RowScope::Active).await
+}
+
+/// Get an entity from the trash (see `DbBmc::SOFT_DELETE`).
+pub async fn get_trashed<MC, E>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	get_in_scope::<MC, E>(ctx, mm, id, RowScope::Trashed).await
+}
+
+async fn get_in_scope<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	scope: RowScope,
+) -> Result<E>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	// Note: Reads from a read replica, when possible (see `Dbx`).
+	let dbx = mm.dbx_for(ctx).read_replica();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.columns(E::field_column_refs())
+		.and_where(Expr::col(CommonIden::Id).eq(id));
+	and_where_row_scope::<MC>(&mut query, scope);
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		query.cond_where(access_cond);
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+	let entity = dbx.fetch_optional(sqlx_query).await?;
+
+	match entity {
+		Some(entity) => Ok(entity),
+		None => Err(not_found_or_access_denied::<MC>(mm, id, scope).await?),
+	}
+}
+
+pub async fn list<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	list_options: Option<ListOptions>,
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	let filter_cond = filter_to_cond(filter)?;
+	list_in_scope::<MC, E>(ctx, mm, filter_cond, list_options, RowScope::Active)
+		.await
+}
+
+/// List the entities matching the `filter` and the `cond`
+/// (i.e., a Bmc condition not expressible as a filter, e.g., with a subquery).
+pub async fn list_with_cond<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	cond: Condition,
+	list_options: Option<ListOptions>,
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	let cond = match filter_to_cond(filter)? {
+		Some(filter_cond) => Condition::all().add(filter_cond).add(cond),
+		None => cond,
+	};
+	list_in_scope::<MC, E>(ctx, mm, Some(cond), list_options, RowScope::Active).await
+}
+
+/// List the entities in the trash (see `DbBmc::SOFT_DELETE`).
+pub async fn list_trashed<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	list_options: Option<ListOptions>,
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	let filter_cond = filter_to_cond(filter)?;
+	list_in_scope::<MC, E>(ctx, mm, filter_cond, list_options, RowScope::Trashed)
+		.await
+}
+
+/// List the entities with their `total` count (ignoring the list options
+/// limit and offset), and the effective `limit` and `offset`.
+pub async fn list_with_meta<MC, E, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+	list_options: Option<ListOptions>,
+) -> Result<ListWithMeta<E>>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	let filter_cond = filter_to_cond(filter)?;
+	let list_options = compute_list_options(list_options)?;
+	// Note: `compute_list_options` always set the limit.
+	let limit = list_options.limit.unwrap_or_default();
+	let offset = list_options.offset.unwrap_or_default();
+
+	let total =
+		count_in_scope::<MC>(ctx, mm, filter_cond.clone(), RowScope::Active).await?;
+	let items = list_in_scope::<MC, E>(
+		ctx,
+		mm,
+		filter_cond,
+		Some(list_options),
+		RowScope::Active,
+	)
+	.await?;
+
+	Ok(ListWithMeta {
+		items,
+		total,
+		limit,
+		offset,
+	})
+}
+
+/// Count the entities matching the `filter` (as `list` would return them
+/// without limit).
+pub async fn count<MC, F>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter: Option<F>,
+) -> Result<i64>
+where
+	MC: DbBmc,
+	F: Into<FilterGroups>,
+{
+	let filter_cond = filter_to_cond(filter)?;
+	count_in_scope::<MC>(ctx, mm, filter_cond, RowScope::Active).await
+}
+
+async fn list_in_scope<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter_cond: Option<Condition>,
+	list_options: Option<ListOptions>,
+	scope: RowScope,
+) -> Result<Vec<E>>
+where
+	MC: DbBmc,
+	E: for<'r> FromRow<'r, PgRow> + Unpin + Send,
+	E: HasFields,
+{
+	// Note: Reads from a read replica, when possible (see `Dbx`).
+	let dbx = mm.dbx_for(ctx).read_replica();
+
+	// -- Build the query
+	let mut query = Query::select();
+	query.from(MC::table_ref()).columns(E::field_column_refs());
+	and_where_row_scope::<MC>(&mut query, scope);
+
+	// condition from filter
+	if let Some(filter_cond) = filter_cond {
+		query.cond_where(filter_cond);
+	}
+	// condition from ctx access
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		query.cond_where(access_cond);
+	}
+	// list options
+	let list_options = compute_list_options(list_options)?;
+	list_options.apply_to_sea_query(&mut query);
+
+	// -- Execute the query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+	let entities = dbx.fetch_all(sqlx_query).await?;
+
+	Ok(entities)
+}
+
+async fn count_in_scope<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	filter_cond: Option<Condition>,
+	scope: RowScope,
+) -> Result<i64>
+where
+	MC: DbBmc,
+{
+	// Note: Reads from a read replica, when possible (see `Dbx`).
+	let dbx = mm.dbx_for(ctx).read_replica();
+
+	// -- Build the query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.expr(Expr::col(Asterisk).count());
+	and_where_row_scope::<MC>(&mut query, scope);
+
+	// condition from filter
+	if let Some(filter_cond) = filter_cond {
+		query.cond_where(filter_cond);
+	}
+	// condition from ctx access
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		query.cond_where(access_cond);
+	}
+
+	// -- Execute the query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let (count,) = dbx.fetch_one(sqlx_query).await?;
+
+	Ok(count)
+}
+
+pub async fn update<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	data: E,
+) -> Result<()>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	update_versioned::<MC, E>(ctx, mm, id, data, None).await
+}
+
+/// Update an entity, only if its `mtime` is still the `expected_mtime` (when given).
+///
+/// Returns `Error::ConcurrentModification` with the current `mtime`
+/// if the entity was modified since (optimistic concurrency control).
+pub async fn update_versioned<MC, E>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	data: E,
+	expected_mtime: Option<OffsetDateTime>,
+) -> Result<()>
+where
+	MC: DbBmc,
+	E: HasFields,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+
+		let mut fields = data.not_none_fields();
+		add_timestamps_for_update(&mut fields, ctx.user_id());
+		let fields = fields.for_sea_update();
+
+		// -- Snapshot for history
+		let before = audit::row_snapshot::<MC>(mm, id).await?;
+
+		// -- Build query
+		let mut query = Query::update();
+		query
+			.table(MC::table_ref())
+			.values(fields)
+			.and_where(Expr::col(CommonIden::Id).eq(id));
+		and_where_row_scope::<MC>(&mut query, RowScope::Active);
+		if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+			query.cond_where(access_cond);
+		}
+		if let Some(expected_mtime) = expected_mtime {
+			query.and_where(Expr::col(TimestampIden::Mtime).eq(expected_mtime));
+		}
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = dbx.execute(sqlx_query).await?;
+
+		// -- Check result
+		if count == 0 {
+			if expected_mtime.is_some() {
+				if let Some(current_mtime) = current_mtime::<MC>(ctx, mm, id).await?
+				{
+					return Err(Error::ConcurrentModification {
+						entity: MC::TABLE,
+						id,
+						current_mtime,
+					});
+				}
+			}
+			return Err(
+				not_found_or_access_denied::<MC>(mm, id, RowScope::Active).await?
+			);
+		}
+
+		// -- Record history
+		let after = audit::row_snapshot::<MC>(mm, id).await?;
+		audit::record::<MC>(ctx, mm, id, EntityAction::Update, before, after)
+			.await?;
+
+		Ok(())
+	})
+	.await
+}
+
+/// Delete an entity.
+///
+/// Note: For the `DbBmc::SOFT_DELETE` entities, the row is moved to the trash
+///       (see `restore` and `purge`).
+pub async fn delete<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+	MC: DbBmc,
+{
+	if MC::SOFT_DELETE {
+		let mut fields = Fields::new(Vec::new());
+		add_timestamps_for_delete(&mut fields, ctx.user_id());
+		return update_trash_state::<MC>(ctx, mm, id, fields, EntityAction::Delete)
+			.await;
+	}
+
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+
+		// -- Snapshot for history
+		let before = audit::row_snapshot::<MC>(mm, id).await?;
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.and_where(Expr::col(CommonIden::Id).eq(id));
+		if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+			query.cond_where(access_cond);
+		}
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = dbx.execute(sqlx_query).await?;
+
+		// -- Check result
+		if count == 0 {
+			return Err(
+				not_found_or_access_denied::<MC>(mm, id, RowScope::Active).await?
+			);
+		}
+
+		// -- Record history
+		audit::record::<MC>(ctx, mm, id, EntityAction::Delete, before, None).await?;
+
+		Ok(())
+	})
+	.await
+}
+
+/// Restore an entity from the trash (see `DbBmc::SOFT_DELETE`).
+pub async fn restore<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+	MC: DbBmc,
+{
+	let mut fields = Fields::new(vec![
+		Field::new(TimestampIden::Did.into_iden(), Value::BigInt(None).into()),
+		Field::new(
+			TimestampIden::Dtime.into_iden(),
+			Value::TimeDateTimeWithTimeZone(None).into(),
+		),
+	]);
+	add_timestamps_for_update(&mut fields, ctx.user_id());
+
+	update_trash_state::<MC>(ctx, mm, id, fields, EntityAction::Restore).await
+}
+
+/// Permanently delete an entity from the trash (see `DbBmc::SOFT_DELETE`).
+pub async fn purge<MC>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()>
+where
+	MC: DbBmc,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+
+		// -- Snapshot for history
+		let before = audit::row_snapshot::<MC>(mm, id).await?;
+
+		// -- Build query
+		let mut query = Query::delete();
+		query
+			.from_table(MC::table_ref())
+			.and_where(Expr::col(CommonIden::Id).eq(id));
+		and_where_row_scope::<MC>(&mut query, RowScope::Trashed);
+		if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+			query.cond_where(access_cond);
+		}
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = dbx.execute(sqlx_query).await?;
+
+		// -- Check result
+		if count == 0 {
+			return Err(
+				not_found_or_access_denied::<MC>(mm, id, RowScope::Trashed).await?
+			);
+		}
+
+		// -- Record history
+		audit::record::<MC>(ctx, mm, id, EntityAction::Purge, before, None).await?;
+
+		Ok(())
+	})
+	.await
+}
+
+/// Update the `did`/`dtime` (and other timestamp) `fields` of an entity `id`
+/// for the trash `action` (i.e., `Delete` to move it to the trash, `Restore` from it).
+async fn update_trash_state<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+	fields: Fields,
+	action: EntityAction,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
+	mm.in_txn(|mm| async move {
+		let mm = &mm;
+		let dbx = mm.dbx_for(ctx);
+		let scope = match action {
+			EntityAction::Restore => RowScope::Trashed,
+			_ => RowScope::Active,
+		};
+
+		// -- Snapshot for history
+		let before = audit::row_snapshot::<MC>(mm, id).await?;
+
+		// -- Build query
+		let mut query = Query::update();
+		query
+			.table(MC::table_ref())
+			.values(fields.for_sea_update())
+			.and_where(Expr::col(CommonIden::Id).eq(id));
+		and_where_row_scope::<MC>(&mut query, scope);
+		if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+			query.cond_where(access_cond);
+		}
+
+		// -- Execute query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let count = dbx.execute(sqlx_query).await?;
+
+		// -- Check result
+		if count == 0 {
+			return Err(not_found_or_access_denied::<MC>(mm, id, scope).await?);
+		}
+
+		// -- Record history
+		let after = audit::row_snapshot::<MC>(mm, id).await?;
+		audit::record::<MC>(ctx, mm, id, action, before, after).await?;
+
+		Ok(())
+	})
+	.await
+}
+
+/// Checks that the entity `id` exists and matches the `access_cond`.
+/// (i.e., `EntityNotFound` or `AccessDenied` otherwise)
+///
+/// Note: For access checks that are not expressed by `DbBmc::access_cond`
+///       (e.g., the project role required to create a task).
+pub async fn check_access_cond<MC>(
+	mm: &ModelManager,
+	id: i64,
+	access_cond: Option<Condition>,
+) -> Result<()>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.and_where(Expr::col(CommonIden::Id).eq(id));
+	and_where_row_scope::<MC>(&mut query, RowScope::Active);
+	if let Some(access_cond) = access_cond {
+		query.cond_where(access_cond);
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let found = dbx.fetch_optional(sqlx_query).await?;
+
+	match found {
+		Some(_) => Ok(()),
+		None => {
+			Err(not_found_or_access_denied::<MC>(mm, id, RowScope::Active).await?)
+		}
+	}
+}
+
+// region:    --- Utils
+
+/// Returns the sea-query condition of the modql `filter`.
+fn filter_to_cond<F>(filter: Option<F>) -> Result<Option<Condition>>
+where
+	F: Into<FilterGroups>,
+{
+	let Some(filter) = filter else {
+		return Ok(None);
+	};
+	let filters: FilterGroups = filter.into();
+	let cond: Condition = filters.try_into()?;
+
+	Ok(Some(cond))
+}
+
+/// Update the timestamps info for create
+/// (e.g., cid, ctime, and mid, mtime will be updated with the same values)
+pub fn add_timestamps_for_create(fields: &mut Fields, user_id: i64) {
+	let now = now_utc();
+	fields.push(Field::new(TimestampIden::Cid.into_iden(), user_id.into()));
+	fields.push(Field::new(TimestampIden::Ctime.into_iden(), now.into()));
+
+	fields.push(Field::new(TimestampIden::Mid.into_iden(), user_id.into()));
+	fields.push(Field::new(TimestampIden::Mtime.into_iden(), now.into()));
+}
+
+/// Update the timestamps info only for update.
+/// (.e.g., only mid, mtime will be udpated)
+pub fn add_timestamps_for_update(fields: &mut Fields, user_id: i64) {
+	let now = now_utc();
+	fields.push(Field::new(TimestampIden::Mid.into_iden(), user_id.into()));
+	fields.push(Field::new(TimestampIden::Mtime.into_iden(), now.into()));
+}
+
+/// Update the timestamps info for a soft delete.
+/// (i.e., did, dtime)
+pub fn add_timestamps_for_delete(fields: &mut Fields, user_id: i64) {
+	let now = now_utc();
+	fields.push(Field::new(TimestampIden::Did.into_iden(), user_id.into()));
+	fields.push(Field::new(TimestampIden::Dtime.into_iden(), now.into()));
+}
+
+/// Restrict the query to the rows of the `scope` for the `DbBmc::SOFT_DELETE` entities.
+/// (no-op for the other entities)
+fn and_where_row_scope<MC>(query: &mut impl ConditionalStatement, scope: RowScope)
+where
+	MC: DbBmc,
+{
+	if MC::SOFT_DELETE {
+		let dtime = Expr::col(TimestampIden::Dtime);
+		query.and_where(match scope {
+			RowScope::Active => dtime.is_null(),
+			RowScope::Trashed => dtime.is_not_null(),
+		});
+	}
+}
+
+/// Returns the `mtime` of the active entity `id` if writable by the ctx user,
+/// or `None` otherwise.
+async fn current_mtime<MC>(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	id: i64,
+) -> Result<Option<OffsetDateTime>>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx_for(ctx);
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.column(TimestampIden::Mtime)
+		.and_where(Expr::col(CommonIden::Id).eq(id));
+	and_where_row_scope::<MC>(&mut query, RowScope::Active);
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Write) {
+		query.cond_where(access_cond);
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (OffsetDateTime,), _>(&sql, values);
+	let mtime = dbx.fetch_optional(sqlx_query).await?.map(|(mtime,)| mtime);
+
+	Ok(mtime)
+}
+
+/// Returns the error for an entity `id` that was not matched by a ctx scoped query.
+/// (i.e., `EntityNotFound` if the row does not exist in the `scope`, `AccessDenied` otherwise)
+async fn not_found_or_access_denied<MC>(
+	mm: &ModelManager,
+	id: i64,
+	scope: RowScope,
+) -> Result<Error>
+where
+	MC: DbBmc,
+{
+	let dbx = mm.dbx();
+
+	// -- Build query
+	let mut query = Query::select();
+	query
+		.from(MC::table_ref())
+		.column(CommonIden::Id)
+		.and_where(Expr::col(CommonIden::Id).eq(id));
+	and_where_row_scope::<MC>(&mut query, scope);
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+	let found = dbx.fetch_optional(sqlx_query).await?;
+
+	let entity = MC::TABLE;
+	if found.is_some() {
+		Ok(Error::AccessDenied { entity, id })
+	} else {
+		Ok(Error::EntityNotFound { entity, id })
+	}
+}
+
+// endregion: --- Utils
@@ -0,0 +1,70 @@
+//! Full-text search constructs for the base entities.
+//!
+//! - The searchable tables have the generated (and GIN indexed) `search_tsv`
+//!   tsvector column of their text column(s) (e.g., `task.title`).
+//! - The search query is parsed with `websearch_to_tsquery`
+//!   (e.g., `"design review" -draft`).
+//!
+//! See `model::search` for the cross entities search.
+
+use crate::ctx::Ctx;
+use crate::model::base::{
+	and_where_row_scope, ctx_access_cond, AccessKind, CommonIden, DbBmc, RowScope,
+};
+use modql::StringIden;
+use sea_query::{Alias, BinOper, Expr, Func, Query, SelectStatement, SimpleExpr};
+
+/// The generated tsvector column of the searchable tables.
+pub(super) const SEARCH_TSV_COLUMN: &str = "search_tsv";
+
+/// The text search configuration of the `search_tsv` columns.
+const TS_CONFIG: &str = "'english'::regconfig";
+
+/// The `ts_headline` options (i.e., the highlight markers).
+const HEADLINE_OPTIONS: &str = "StartSel=<b>, StopSel=</b>";
+
+/// Returns the select of the `MC` entities matching the search `query`,
+/// readable by the ctx user, as the search hit columns:
+/// `entity`, `id`, `text`, `headline`, `rank`.
+///
+/// - `text_col` is the column the headline is built from (e.g., `title`).
+pub fn search_select<MC>(ctx: &Ctx, text_col: &str, query: &str) -> SelectStatement
+where
+	MC: DbBmc,
+{
+	let ts_query = || -> SimpleExpr {
+		Func::cust(Alias::new("websearch_to_tsquery"))
+			.args([Expr::cust(TS_CONFIG), Expr::val(query).into()])
+			.into()
+	};
+	let text_col = || Expr::col(StringIden(text_col.to_string()));
+	let search_tsv = || Expr::col(StringIden(SEARCH_TSV_COLUMN.to_string()));
+
+	let mut select = Query::select();
+	select
+		.from(MC::table_ref())
+		.expr_as(Expr::val(MC::TABLE), Alias::new("entity"))
+		.column(CommonIden::Id)
+		.expr_as(text_col(), Alias::new("text"))
+		.expr_as(
+			Func::cust(Alias::new("ts_headline")).args([
+				Expr::cust(TS_CONFIG),
+				text_col().into(),
+				ts_query(),
+				Expr::val(HEADLINE_OPTIONS).into(),
+			]),
+			Alias::new("headline"),
+		)
+		.expr_as(
+			Func::cust(Alias::new("ts_rank"))
+				.args([search_tsv().into(), ts_query()]),
+			Alias::new("rank"),
+		)
+		.and_where(search_tsv().binary(BinOper::Custom("@@"), ts_query()));
+	and_where_row_scope::<MC>(&mut select, RowScope::Active);
+	if let Some(access_cond) = ctx_access_cond::<MC>(ctx, AccessKind::Read) {
+		select.cond_where(access_cond);
+	}
+
+	select
+}
@@ -0,0 +1,40 @@
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub enum Error {
+	/// The blob key is empty, or has other than `[A-Za-z0-9_-]` characters.
+	BlobKeyInvalid {
+		key: String,
+	},
+	BlobNotFound {
+		key: String,
+	},
+
+	// -- Externals
+	Io(#[serde_as(as = "DisplayFromStr")] std::io::Error),
+}
+
+// region:    --- Froms
+impl From<std::io::Error> for Error {
+	fn from(val: std::io::Error) -> Self {
+		Self::Io(val)
+	}
+}
+// endregion: --- Froms
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
@@ -0,0 +1,84 @@
+use crate::model::blob::{check_key, BlobReader, BlobStore, Error, Result};
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::PathBuf;
+use tokio::fs::{self, File};
+use tokio::io::{AsyncRead, AsyncWriteExt};
+
+/// The local filesystem blob store, one file per blob in its `root_dir`.
+#[derive(Clone)]
+pub struct LocalBlobStore {
+	root_dir: PathBuf,
+}
+
+impl LocalBlobStore {
+	pub fn new(root_dir: impl Into<PathBuf>) -> Self {
+		LocalBlobStore {
+			root_dir: root_dir.into(),
+		}
+	}
+
+	fn path(&self, key: &str) -> Result<PathBuf> {
+		check_key(key)?;
+
+		Ok(self.root_dir.join(key))
+	}
+}
+
+#[async_trait]
+impl BlobStore for LocalBlobStore {
+	async fn put<R>(&self, key: &str, mut content: R) -> Result<u64>
+	where
+		R: AsyncRead + Unpin + Send,
+	{
+		let path = self.path(key)?;
+		fs::create_dir_all(&self.root_dir).await?;
+
+		// Write to a part file first, so that a failed write
+		// does not leave a partial blob.
+		let part_path = path.with_extension("part");
+		let write_res = async {
+			let mut file = File::create(&part_path).await?;
+			let size = tokio::io::copy(&mut content, &mut file).await?;
+			file.flush().await?;
+			file.sync_all().await?;
+			Ok::<_, std::io::Error>(size)
+		}
+		.await;
+
+		match write_res {
+			Ok(size) => {
+				fs::rename(&part_path, &path).await?;
+				Ok(size)
+			}
+			Err(ex) => {
+				let _ = fs::remove_file(&part_path).await;
+				Err(ex.into())
+			}
+		}
+	}
+
+	async fn open(&self, key: &str) -> Result<BlobReader> {
+		let path = self.path(key)?;
+
+		match File::open(&path).await {
+			Ok(file) => Ok(Box::pin(file)),
+			Err(ex) if ex.kind() == ErrorKind::NotFound => {
+				Err(Error::BlobNotFound {
+					key: key.to_string(),
+				})
+			}
+			Err(ex) => Err(ex.into()),
+		}
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		let path = self.path(key)?;
+
+		match fs::remove_file(&path).await {
+			Ok(()) => Ok(()),
+			Err(ex) if ex.kind() == ErrorKind::NotFound => Ok(()),
+			Err(ex) => Err(ex.into()),
+		}
+	}
+}
@@ -0,0 +1,95 @@
+//! Blob Store
+//!
+//! Design:
+//!
+//! - The blob store holds the binary content (e.g., the task attachments)
+//!   by key, out of the database.
+//! - The `ModelManager` owns the `BlobStoreDispatcher` (built from the config),
+//!   only accessed by the Model Controllers (e.g., `AttachmentBmc`).
+//! - The backends implement the `BlobStore` trait, and are dispatched
+//!   by the `BlobStoreDispatcher` (i.e., local filesystem for now, S3 later).
+//! - The blob writes are not part of the db transactions.
+//!
+
+// region:    --- Modules
+
+mod error;
+mod local;
+
+pub use self::error::{Error, Result};
+pub use self::local::LocalBlobStore;
+
+use crate::core_config;
+use async_trait::async_trait;
+use std::pin::Pin;
+use tokio::io::AsyncRead;
+
+// endregion: --- Modules
+
+/// The blob content reader (i.e., to stream the content out).
+pub type BlobReader = Pin<Box<dyn AsyncRead + Send>>;
+
+#[async_trait]
+pub trait BlobStore {
+	/// Write the `content` as the `key` blob (replacing it if any),
+	/// and returns its size in bytes.
+	async fn put<R>(&self, key: &str, content: R) -> Result<u64>
+	where
+		R: AsyncRead + Unpin + Send;
+
+	/// Returns the reader of the `key` blob content.
+	async fn open(&self, key: &str) -> Result<BlobReader>;
+
+	/// Delete the `key` blob (no-op if it does not exist).
+	async fn delete(&self, key: &str) -> Result<()>;
+}
+
+#[derive(Clone)]
+pub enum BlobStoreDispatcher {
+	Local(LocalBlobStore),
+}
+
+#[async_trait]
+impl BlobStore for BlobStoreDispatcher {
+	async fn put<R>(&self, key: &str, content: R) -> Result<u64>
+	where
+		R: AsyncRead + Unpin + Send,
+	{
+		match self {
+			Self::Local(store) => store.put(key, content).await,
+		}
+	}
+
+	async fn open(&self, key: &str) -> Result<BlobReader> {
+		match self {
+			Self::Local(store) => store.open(key).await,
+		}
+	}
+
+	async fn delete(&self, key: &str) -> Result<()> {
+		match self {
+			Self::Local(store) => store.delete(key).await,
+		}
+	}
+}
+
+pub fn new_blob_store() -> BlobStoreDispatcher {
+	BlobStoreDispatcher::Local(LocalBlobStore::new(&core_config().BLOB_STORE_DIR))
+}
+
+/// Returns an error if the `key` is not a safe blob key
+/// (i.e., `[A-Za-z0-9_-]+`, so that it can be used as a file or object name).
+fn check_key(key: &str) -> Result<()> {
+	let is_valid = !key.is_empty()
+		&& key
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+
+	if is_valid {
+		Ok(())
+	} else {
+		Err(Error::BlobKeyInvalid {
+			key: key.to_string(),
+		})
+	}
+}
@@ -0,0 +1,202 @@
+//! Live change feed of the project and task writes
+//!
+//! Design:
+//!
+//! - The `project` and `task` row writes notify the `entity_change` postgres channel
+//!   (db triggers, see the `0005_change_feed` migration), on their transaction commit.
+//! - Each service instance runs one `ChangeFeed`, listening to this channel,
+//!   and broadcasting the `EntityChange`s to its subscribers
+//!   (e.g., the web-server SSE connections).
+//! - So, the changes of all the service instances reach all the subscribers,
+//!   which filter them by their ctx (see `EntityChange::is_visible`).
+//!
+//! Note: The feed is best effort (e.g., the notifications are lost while the listener
+//!       reconnects, or dropped for a lagging subscriber), so the clients should
+//!       refetch their data after a gap.
+//!
+
+use crate::core_config;
+use crate::ctx::Ctx;
+use crate::model::entity_history::EntityAction;
+use crate::model::project::ProjectBmc;
+use crate::model::{ModelManager, Result};
+use serde::{Deserialize, Serialize};
+use sqlx::postgres::PgListener;
+use std::time::Duration;
+use tokio::sync::broadcast;
+use tracing::error;
+
+/// The postgres channel of the change notifications (see the db triggers).
+const CHANGE_CHANNEL: &str = "entity_change";
+/// The changes buffered per subscriber (i.e., before it lags).
+const CHANGE_BUFFER_SIZE: usize = 1024;
+/// The wait before listening again, after a listener failure.
+const LISTEN_RETRY_WAIT: Duration = Duration::from_secs(1);
+
+// region:    --- ChangeFeed Types
+
+/// A project or task row change, as notified by the db.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EntityChange {
+	/// The entity table (i.e., `project` or `task`).
+	pub entity: String,
+	pub id: i64,
+	/// The project of the entity (i.e., its `id` for a project).
+	pub project_id: i64,
+	pub action: EntityAction,
+}
+
+impl EntityChange {
+	/// Returns true if the change is on a project readable by the ctx user
+	/// (see `ProjectBmc::is_readable`).
+	///
+	/// Note: Checked when delivered, so the changes of a purged project
+	///       (i.e., with its tasks) are not visible anymore.
+	pub async fn is_visible(&self, ctx: &Ctx, mm: &ModelManager) -> Result<bool> {
+		ProjectBmc::is_readable(ctx, mm, self.project_id).await
+	}
+}
+
+// endregion: --- ChangeFeed Types
+
+/// The service instance change feed, to subscribe to (e.g., as app state).
+#[derive(Clone)]
+pub struct ChangeFeed {
+	change_tx: broadcast::Sender<EntityChange>,
+}
+
+impl ChangeFeed {
+	/// Starts listening to the db change notifications, for the runtime lifetime.
+	///
+	/// Note: On its own primary db connection (i.e., not one of the pool),
+	///       as the notifications are not replicated.
+	pub async fn start() -> Result<ChangeFeed> {
+		let mut listener = PgListener::connect(&core_config().DB_URL).await?;
+		listener.listen(CHANGE_CHANNEL).await?;
+
+		let (change_tx, _) = broadcast::channel(CHANGE_BUFFER_SIZE);
+		tokio::spawn(Self::broadcast_changes(listener, change_tx.clone()));
+
+		Ok(ChangeFeed { change_tx })
+	}
+
+	/// Returns a receiver of the changes notified from now on
+	/// (of all the users, to be filtered by `EntityChange::is_visible`).
+	pub fn subscribe(&self) -> broadcast::Receiver<EntityChange> {
+		self.change_tx.subscribe()
+	}
+
+	async fn broadcast_changes(
+		mut listener: PgListener,
+		change_tx: broadcast::Sender<EntityChange>,
+	) {
+		loop {
+			// Note: On a connection loss, `recv` reconnects (and listens again).
+			let notification = match listener.recv().await {
+				Ok(notification) => notification,
+				Err(ex) => {
+					error!("{:<12} - listen fail - {ex:?}", "CHANGE_FEED");
+					tokio::time::sleep(LISTEN_RETRY_WAIT).await;
+					continue;
+				}
+			};
+
+			match serde_json::from_str::<EntityChange>(notification.payload()) {
+				// Note: Fails only without subscribers, which is fine.
+				Ok(change) => {
+					let _ = change_tx.send(change);
+				}
+				Err(ex) => {
+					error!("{:<12} - payload invalid - {ex:?}", "CHANGE_FEED");
+				}
+			}
+		}
+	}
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_change_feed_project_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let change_feed = ChangeFeed::start().await?;
+		let mut change_rx = change_feed.subscribe();
+
+		// -- Exec
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_change_feed_project_ok")
+				.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_change_feed_project_ok task"],
+		)
+		.await?[0]
+			.id;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		// -- Check
+		let changes = recv_project_changes(&mut change_rx, fx_project_id).await?;
+		let change = |entity: &str, id: i64, action| EntityChange {
+			entity: entity.to_string(),
+			id,
+			project_id: fx_project_id,
+			action,
+		};
+		assert_eq!(
+			changes,
+			vec![
+				change("project", fx_project_id, EntityAction::Create),
+				change("task", fx_task_id, EntityAction::Create),
+				change("project", fx_project_id, EntityAction::Delete),
+			]
+		);
+		for change in changes.iter() {
+			assert!(change.is_visible(&ctx, &mm).await?);
+			assert!(change.is_visible(&Ctx::root_ctx(), &mm).await?);
+			assert!(
+				!change.is_visible(&ctx_other, &mm).await?,
+				"not a project member, should not be visible"
+			);
+		}
+
+		// -- Clean
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	/// Receives the changes of the project `project_id`, up to its move to the trash.
+	async fn recv_project_changes(
+		change_rx: &mut broadcast::Receiver<EntityChange>,
+		project_id: i64,
+	) -> Result<Vec<EntityChange>> {
+		let mut changes = Vec::new();
+		loop {
+			let change =
+				tokio::time::timeout(Duration::from_secs(5), change_rx.recv())
+					.await??;
+			if change.project_id != project_id {
+				continue;
+			}
+			let is_project_delete =
+				change.entity == "project" && change.action == EntityAction::Delete;
+			changes.push(change);
+			if is_project_delete {
+				return Ok(changes);
+			}
+		}
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,257 @@
+//! The entity history (audit trail) of the model writes.
+//!
+//! The history rows are recorded by the `base` model functions
+//! (see `model::base::audit`), and are read-only from this module.
+
+use crate::ctx::Ctx;
+use crate::model::attachment::AttachmentBmc;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc};
+use crate::model::label::{LabelBmc, TaskLabelBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::ProjectBmc;
+use crate::model::project_member::ProjectMemberBmc;
+use crate::model::task::TaskBmc;
+use crate::model::task_comment::TaskCommentBmc;
+use crate::model::task_dependency::TaskDependencyBmc;
+use crate::model::workspace::WorkspaceBmc;
+use crate::model::workspace_member::WorkspaceMemberBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use crate::model::{ListPage, ListWithMeta};
+use lib_utils::time::Rfc3339;
+use modql::field::{FieldValue, Fields};
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Iden, Query};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- EntityHistory Types
+
+/// The model write action recorded in the entity history.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, FieldValue, sqlx::Type, Serialize, Deserialize,
+)]
+#[sqlx(type_name = "varchar")]
+pub enum EntityAction {
+	Create,
+	Update,
+	Delete,
+	Restore,
+	Purge,
+}
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct EntityHistory {
+	pub id: i64,
+
+	/// The entity table name (e.g., "task").
+	pub entity: String,
+	pub entity_id: i64,
+	pub action: EntityAction,
+	/// The changed columns, as `{"column": {"old": .., "new": ..}}`.
+	pub diff: Value,
+
+	// -- Timestamps
+	//    (acting user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct EntityHistoryFilter {
+	id: Option<OpValsInt64>,
+	entity: Option<OpValsString>,
+	entity_id: Option<OpValsInt64>,
+	action: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum EntityHistoryIden {
+	Entity,
+	EntityId,
+}
+// endregion: --- EntityHistory Types
+
+// region:    --- EntityHistoryBmc
+pub struct EntityHistoryBmc;
+
+impl DbBmc for EntityHistoryBmc {
+	const TABLE: &'static str = "entity_history";
+
+	/// The history of an entity is visible to the users who can read the entity.
+	///
+	/// Note: The history of the entities not listed here (e.g., `user`)
+	///       is only visible to the root ctx.
+	fn access_cond(ctx: &Ctx, _access: AccessKind) -> Option<Condition> {
+		Some(
+			Condition::any()
+				.add(Self::entity_access_cond::<ProjectBmc>(ctx))
+				.add(Self::entity_access_cond::<TaskBmc>(ctx))
+				.add(Self::entity_access_cond::<ProjectMemberBmc>(ctx))
+				.add(Self::entity_access_cond::<TaskDependencyBmc>(ctx))
+				.add(Self::entity_access_cond::<LabelBmc>(ctx))
+				.add(Self::entity_access_cond::<TaskLabelBmc>(ctx))
+				.add(Self::entity_access_cond::<TaskCommentBmc>(ctx))
+				.add(Self::entity_access_cond::<AttachmentBmc>(ctx))
+				.add(Self::entity_access_cond::<WorkspaceBmc>(ctx))
+				.add(Self::entity_access_cond::<WorkspaceMemberBmc>(ctx)),
+		)
+	}
+}
+
+impl EntityHistoryBmc {
+	/// Returns the condition matching the history rows of the `MC` entities
+	/// readable by the ctx user.
+	fn entity_access_cond<MC>(ctx: &Ctx) -> Condition
+	where
+		MC: DbBmc,
+	{
+		let mut readable_ids = Query::select();
+		readable_ids.column(CommonIden::Id).from(MC::table_ref());
+		if let Some(access_cond) = base::ctx_access_cond::<MC>(ctx, AccessKind::Read)
+		{
+			readable_ids.cond_where(access_cond);
+		}
+
+		Condition::all()
+			.add(Expr::col(EntityHistoryIden::Entity).eq(MC::TABLE))
+			.add(Expr::col(EntityHistoryIden::EntityId).in_subquery(readable_ids))
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<EntityHistoryFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<EntityHistory>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// List a page of the entity history rows after the `cursor` (keyset pagination).
+	pub async fn list_page(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<EntityHistoryFilter>>,
+		list_options: Option<ListOptions>,
+		cursor: Option<String>,
+	) -> Result<ListPage<EntityHistory>> {
+		base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor).await
+	}
+
+	/// List the entity history rows with their total count and the effective limit/offset.
+	pub async fn list_with_meta(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<EntityHistoryFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<ListWithMeta<EntityHistory>> {
+		base::list_with_meta::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Count the entity history rows matching the filter.
+	pub async fn count(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<EntityHistoryFilter>>,
+	) -> Result<i64> {
+		base::count::<Self, _>(ctx, mm, filter).await
+	}
+}
+// endregion: --- EntityHistoryBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_task_history_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_title = "test_list_task_history_ok 01";
+		let fx_title_new = "test_list_task_history_ok 01 - new";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_task_history_ok project for task",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title])
+			.await?
+			.remove(0);
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_new.to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+		TaskBmc::restore(&ctx, &mm, fx_task.id).await?;
+
+		// -- Exec
+		let filter: EntityHistoryFilter = serde_json::from_value(json!({
+			"entity": "task",
+			"entity_id": fx_task.id,
+		}))?;
+		let history =
+			EntityHistoryBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let filter: EntityHistoryFilter = serde_json::from_value(json!({
+			"entity": "task",
+			"entity_id": fx_task.id,
+		}))?;
+		let history_other =
+			EntityHistoryBmc::list(&ctx_other, &mm, Some(vec![filter]), None)
+				.await?;
+
+		// -- Check
+		let actions: Vec<EntityAction> = history.iter().map(|h| h.action).collect();
+		assert_eq!(
+			actions,
+			&[
+				EntityAction::Create,
+				EntityAction::Update,
+				EntityAction::Delete,
+				EntityAction::Restore
+			]
+		);
+		assert!(history.iter().all(|h| h.cid == ctx.user_id()));
+		assert_eq!(history[0].diff["title"], json!({ "new": fx_title }));
+		assert_eq!(
+			history[1].diff,
+			json!({ "title": { "old": fx_title, "new": fx_title_new } })
+		);
+		assert!(
+			history_other.is_empty(),
+			"history should not be visible to other"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
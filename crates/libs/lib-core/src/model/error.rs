@@ -1,8 +1,10 @@
-use crate::model::store;
+use crate::model::{blob, migration, outbox, store};
 use derive_more::From;
 use lib_auth::pwd;
+use lib_utils::time::Rfc3339;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
+use sqlx::types::time::OffsetDateTime;
 
 pub type Result<T> = core::result::Result<T, Error>;
 
@@ -13,16 +15,119 @@ pub enum Error {
 		entity: &'static str,
 		id: i64,
 	},
+	AccessDenied {
+		entity: &'static str,
+		id: i64,
+	},
+	/// The entity was modified since the expected `mtime` (i.e., version).
+	ConcurrentModification {
+		entity: &'static str,
+		id: i64,
+		#[serde_as(as = "Rfc3339")]
+		current_mtime: OffsetDateTime,
+	},
 	ListLimitOverMax {
 		max: i64,
 		actual: i64,
 	},
+	/// The list cursor is malformed, or was not built for the same filters and `order_bys`.
+	ListCursorInvalid,
+
+	// -- User
+	UsernameEmpty,
+	UsernameTooLong {
+		max: usize,
+	},
+	UsernameAlreadyExists {
+		username: String,
+	},
+	UserPwdEmpty,
+	UserPwdNotMatching {
+		user_id: i64,
+	},
+
+	// -- Workspace
+	/// The ctx has no active workspace (e.g., to create a project in).
+	CtxNoWorkspace,
+	/// The workspace still has projects (in the trash or not), to purge first.
+	WorkspaceNotEmpty {
+		id: i64,
+	},
+	/// The workspace member change would leave the workspace without an owner.
+	WorkspaceLastOwner {
+		workspace_id: i64,
+	},
+
+	// -- Project
+	ProjectMemberAlreadyExists {
+		project_id: i64,
+		user_id: i64,
+	},
+	/// The project member user is not a member of the project workspace.
+	ProjectMemberNotInWorkspace {
+		project_id: i64,
+		user_id: i64,
+	},
+
+	// -- Task
+	/// The task move sibling is the task itself, or not in the same project.
+	TaskMoveSiblingInvalid {
+		id: i64,
+		sibling_id: i64,
+	},
+	TaskParentNotInProject {
+		parent_id: i64,
+		project_id: i64,
+	},
+	/// The task parent would be the task itself, or one of its subtasks.
+	TaskParentCycle {
+		id: i64,
+		parent_id: i64,
+	},
+	/// The `task_id` would (transitively) block its own `blocker_id`.
+	TaskDependencyCycle {
+		task_id: i64,
+		blocker_id: i64,
+	},
+	/// The task cannot be done while its `blocker_id` is not.
+	TaskBlocked {
+		id: i64,
+		blocker_id: i64,
+	},
+	/// The task cannot be done while its subtask `subtask_id` (recursively) is not.
+	TaskOpenSubtask {
+		id: i64,
+		subtask_id: i64,
+	},
+	TaskRecurrenceInvalid {
+		recurrence: String,
+		cause: &'static str,
+	},
+	/// The task recurrence requires a due date (i.e., the first occurrence).
+	TaskRecurrenceNoDueDate,
+
+	// -- Label
+	LabelNameAlreadyExists {
+		project_id: i64,
+		name: String,
+	},
+	/// The label is not a label of the task project.
+	LabelNotInProject {
+		label_id: i64,
+		project_id: i64,
+	},
 
 	// -- Modules
 	#[from]
 	Pwd(pwd::Error),
 	#[from]
 	Store(store::Error),
+	#[from]
+	Blob(blob::Error),
+	#[from]
+	Migration(migration::Error),
+	#[from]
+	Outbox(outbox::Error),
 
 	// -- Externals
 	#[from]
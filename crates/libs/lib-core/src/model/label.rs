@@ -0,0 +1,507 @@
+//! The project labels (e.g., `bug`, `ui`), attached to the tasks of their project.
+//!
+//! - A label name is unique in its project.
+//! - The labels are attached to the tasks through the `task_label` table
+//!   (see `LabelBmc::add_to_task`), and the tasks can be filtered on their labels
+//!   (see the `TaskFilter` `labels_any` and `labels_all`).
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::ProjectBmc;
+use crate::model::project_member::ProjectRole;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, IntoSeaError, ListOptions, OpValValue, OpValsInt64, OpValsString,
+	OpValsValue, SeaResult,
+};
+use sea_query::{
+	Alias, ColumnRef, Condition, ConditionExpression, Expr, Iden, JoinType,
+	PostgresQueryBuilder, Query, SelectStatement,
+};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Label Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Label {
+	pub id: i64,
+	pub project_id: i64,
+
+	pub name: String,
+	/// The display color (e.g., `#d73a4a`), free form for the clients.
+	pub color: Option<String>,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize, Default)]
+pub struct LabelForCreate {
+	pub project_id: i64,
+	pub name: String,
+	pub color: Option<String>,
+}
+
+#[derive(Fields, Deserialize, Default)]
+pub struct LabelForUpdate {
+	pub name: Option<String>,
+	pub color: Option<String>,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct LabelFilter {
+	id: Option<OpValsInt64>,
+	project_id: Option<OpValsInt64>,
+	name: Option<OpValsString>,
+	color: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+
+/// The task to label link (i.e., a `task_label` row).
+#[derive(Fields)]
+struct TaskLabelForCreate {
+	task_id: i64,
+	label_id: i64,
+}
+
+#[derive(Iden)]
+enum LabelIden {
+	ProjectId,
+	Name,
+}
+
+#[derive(Iden)]
+enum TaskLabelIden {
+	TaskId,
+	LabelId,
+}
+// endregion: --- Label Types
+
+// region:    --- LabelBmc
+pub struct LabelBmc;
+
+impl DbBmc for LabelBmc {
+	const TABLE: &'static str = "label";
+
+	/// Labels are readable by all the project members,
+	/// and written by the project editors and owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => ProjectRole::Viewer,
+			AccessKind::Write => ProjectRole::Editor,
+		};
+
+		Some(
+			Condition::all().add(
+				Expr::col(LabelIden::ProjectId)
+					.in_subquery(ProjectBmc::accessible_ids_query(ctx, min_role)),
+			),
+		)
+	}
+}
+
+impl LabelBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		label_c: LabelForCreate,
+	) -> Result<i64> {
+		// -- Check the project access
+		ProjectBmc::check_role(ctx, mm, label_c.project_id, ProjectRole::Editor)
+			.await?;
+
+		let project_id = label_c.project_id;
+		let name = label_c.name.clone();
+		base::create::<Self, _>(ctx, mm, label_c)
+			.await
+			.map_err(|err| name_conflict_err(err, project_id, name))
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Label> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<LabelFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Label>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// List the labels of the `task_id` task.
+	pub async fn list_for_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+	) -> Result<Vec<Label>> {
+		// -- Check the task access
+		TaskBmc::get(ctx, mm, task_id).await?;
+
+		let mut label_ids = Query::select();
+		label_ids
+			.column(TaskLabelIden::LabelId)
+			.from(TaskLabelBmc::table_ref())
+			.and_where(Expr::col(TaskLabelIden::TaskId).eq(task_id));
+		let cond =
+			Condition::all().add(Expr::col(CommonIden::Id).in_subquery(label_ids));
+
+		base::list_with_cond::<Self, _, Vec<LabelFilter>>(ctx, mm, None, cond, None)
+			.await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		label_u: LabelForUpdate,
+	) -> Result<()> {
+		Self::update_versioned(ctx, mm, id, label_u, None).await
+	}
+
+	/// Update the label, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		label_u: LabelForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		let label = Self::get(ctx, mm, id).await?;
+
+		let name = label_u.name.clone().unwrap_or(label.name);
+		base::update_versioned::<Self, _>(ctx, mm, id, label_u, expected_mtime)
+			.await
+			.map_err(|err| name_conflict_err(err, label.project_id, name))
+	}
+
+	/// Delete the label (and detach it from its tasks).
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::delete::<Self>(ctx, mm, id).await
+	}
+
+	/// Attach the `label_id` label to the `task_id` task
+	/// (no-op if it already is).
+	pub async fn add_to_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		label_id: i64,
+	) -> Result<()> {
+		let task = TaskBmc::get(ctx, mm, task_id).await?;
+		ProjectBmc::check_role(ctx, mm, task.project_id, ProjectRole::Editor)
+			.await?;
+		let label = Self::get(ctx, mm, label_id).await?;
+		if label.project_id != task.project_id {
+			return Err(Error::LabelNotInProject {
+				label_id,
+				project_id: task.project_id,
+			});
+		}
+
+		if TaskLabelBmc::find_id(mm, task_id, label_id)
+			.await?
+			.is_none()
+		{
+			let task_label_c = TaskLabelForCreate { task_id, label_id };
+			base::create::<TaskLabelBmc, _>(ctx, mm, task_label_c).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Detach the `label_id` label from the `task_id` task
+	/// (no-op if it is not attached).
+	pub async fn remove_from_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		label_id: i64,
+	) -> Result<()> {
+		if let Some(id) = TaskLabelBmc::find_id(mm, task_id, label_id).await? {
+			base::delete::<TaskLabelBmc>(ctx, mm, id).await?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Returns the `LabelNameAlreadyExists` error for the `(project_id, name)`
+/// unique violation, otherwise the `err` as is.
+fn name_conflict_err(err: Error, project_id: i64, name: String) -> Error {
+	match err {
+		Error::Sqlx(sqlx::Error::Database(db_err))
+			if db_err.is_unique_violation() =>
+		{
+			Error::LabelNameAlreadyExists { project_id, name }
+		}
+		err => err,
+	}
+}
+// endregion: --- LabelBmc
+
+// region:    --- TaskLabelBmc
+
+/// The `task_label` links, managed by the `LabelBmc` task methods.
+pub(in crate::model) struct TaskLabelBmc;
+
+impl DbBmc for TaskLabelBmc {
+	const TABLE: &'static str = "task_label";
+
+	/// The task labels have the access of their task.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let mut task_ids = Query::select();
+		task_ids.column(CommonIden::Id).from(TaskBmc::table_ref());
+		if let Some(access_cond) = base::ctx_access_cond::<TaskBmc>(ctx, access) {
+			task_ids.cond_where(access_cond);
+		}
+
+		Some(
+			Condition::all()
+				.add(Expr::col(TaskLabelIden::TaskId).in_subquery(task_ids)),
+		)
+	}
+}
+
+impl TaskLabelBmc {
+	/// Returns the id of the `task_id` to `label_id` link,
+	/// if any (regardless of the ctx access).
+	async fn find_id(
+		mm: &ModelManager,
+		task_id: i64,
+		label_id: i64,
+	) -> Result<Option<i64>> {
+		let dbx = mm.dbx();
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(TaskLabelIden::TaskId).eq(task_id))
+			.and_where(Expr::col(TaskLabelIden::LabelId).eq(label_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let id = dbx.fetch_optional(sqlx_query).await?.map(|(id,)| id);
+
+		Ok(id)
+	}
+}
+// endregion: --- TaskLabelBmc
+
+// region:    --- Task Filter Utils
+
+/// The `TaskFilter` `labels_any` condition, i.e., the tasks having
+/// at least one of the label names.
+///
+/// Note: The `col` is ignored, the condition is on the task `id`.
+pub fn labels_any_to_sea_condition(
+	_col: &ColumnRef,
+	op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+	let names = label_names(op_value)?;
+	let task_ids = labeled_task_ids_query(&names);
+
+	Ok(Expr::col(CommonIden::Id).in_subquery(task_ids).into())
+}
+
+/// The `TaskFilter` `labels_all` condition, i.e., the tasks having
+/// all the label names.
+///
+/// Note: The `col` is ignored, the condition is on the task `id`.
+pub fn labels_all_to_sea_condition(
+	_col: &ColumnRef,
+	op_value: OpValValue,
+) -> SeaResult<ConditionExpression> {
+	let names = label_names(op_value)?;
+	let mut task_ids = labeled_task_ids_query(&names);
+	task_ids
+		.group_by_col((Alias::new("tl"), TaskLabelIden::TaskId))
+		.and_having(
+			Expr::col((Alias::new("l"), LabelIden::Name))
+				.count_distinct()
+				.eq(names.len() as i64),
+		);
+
+	Ok(Expr::col(CommonIden::Id).in_subquery(task_ids).into())
+}
+
+/// Returns the label names of the filter value, either a name
+/// (e.g., `"bug"`) or an `$eq` array of names (e.g., `{"$eq": ["bug", "ui"]}`).
+fn label_names(op_value: OpValValue) -> SeaResult<Vec<String>> {
+	let OpValValue::Eq(value) = op_value else {
+		return Err(IntoSeaError::custom(
+			"label filter only supports a name, or an '$eq' array of names",
+		));
+	};
+
+	let names = match value {
+		serde_json::Value::Array(_) => serde_json::from_value(value)?,
+		value => vec![serde_json::from_value(value)?],
+	};
+
+	Ok(names)
+}
+
+/// Returns the select of the ids of the tasks having
+/// one of the label `names` (aliases `tl` for task_label, `l` for label).
+fn labeled_task_ids_query(names: &[String]) -> SelectStatement {
+	let tl = Alias::new("tl");
+	let l = Alias::new("l");
+
+	let mut query = Query::select();
+	query
+		.column((tl.clone(), TaskLabelIden::TaskId))
+		.from_as(TaskLabelBmc::table_ref(), tl.clone())
+		.join_as(
+			JoinType::InnerJoin,
+			LabelBmc::table_ref(),
+			l.clone(),
+			Expr::col((l.clone(), CommonIden::Id))
+				.equals((tl, TaskLabelIden::LabelId)),
+		)
+		.and_where(Expr::col((l, LabelIden::Name)).is_in(names.iter().cloned()));
+
+	query
+}
+
+// endregion: --- Task Filter Utils
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::task::{Task, TaskFilter};
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_task_labels_filter_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let fx_titles = &[
+			"test_task_labels_filter_ok 01",
+			"test_task_labels_filter_ok 02",
+			"test_task_labels_filter_ok 03",
+		];
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_task_labels_filter_ok")
+				.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		let mut fx_label_ids = Vec::new();
+		for name in ["bug", "ui"] {
+			let label_c = LabelForCreate {
+				project_id: fx_project_id,
+				name: name.to_string(),
+				color: None,
+			};
+			fx_label_ids.push(LabelBmc::create(&ctx, &mm, label_c).await?);
+		}
+		// 01 is bug and ui, 02 is bug only
+		LabelBmc::add_to_task(&ctx, &mm, fx_tasks[0].id, fx_label_ids[0]).await?;
+		LabelBmc::add_to_task(&ctx, &mm, fx_tasks[0].id, fx_label_ids[1]).await?;
+		LabelBmc::add_to_task(&ctx, &mm, fx_tasks[1].id, fx_label_ids[0]).await?;
+		// already attached, no-op
+		LabelBmc::add_to_task(&ctx, &mm, fx_tasks[1].id, fx_label_ids[0]).await?;
+		let list_titles = |tasks: Vec<Task>| -> Vec<String> {
+			tasks.into_iter().map(|t| t.title).collect()
+		};
+		let list_filtered = |mut filter: serde_json::Value| {
+			filter["project_id"] = json!(fx_project_id);
+			let filter: TaskFilter = serde_json::from_value(filter).unwrap();
+			TaskBmc::list(&ctx, &mm, Some(vec![filter]), None)
+		};
+
+		// -- Exec
+		let bug_tasks = list_filtered(json!({"labels_any": "bug"})).await?;
+		let any_tasks =
+			list_filtered(json!({"labels_any": {"$eq": ["bug", "ui"]}})).await?;
+		let all_tasks =
+			list_filtered(json!({"labels_all": {"$eq": ["bug", "ui"]}})).await?;
+		let task_labels = LabelBmc::list_for_task(&ctx, &mm, fx_tasks[0].id).await?;
+		LabelBmc::remove_from_task(&ctx, &mm, fx_tasks[0].id, fx_label_ids[0])
+			.await?;
+		let bug_tasks_after = list_filtered(json!({"labels_any": "bug"})).await?;
+
+		// -- Check
+		assert_eq!(list_titles(bug_tasks), &fx_titles[0..2]);
+		assert_eq!(list_titles(any_tasks), &fx_titles[0..2]);
+		assert_eq!(list_titles(all_tasks), &fx_titles[0..1]);
+		let label_names: Vec<String> =
+			task_labels.into_iter().map(|l| l.name).collect();
+		assert_eq!(label_names, &["bug", "ui"]);
+		assert_eq!(list_titles(bug_tasks_after), &fx_titles[1..2]);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_label_create_err_name_exists() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_label_create_err_name_exists")
+				.await?;
+		let new_label_c = || LabelForCreate {
+			project_id: fx_project_id,
+			name: "bug".to_string(),
+			color: Some("#d73a4a".to_string()),
+		};
+		LabelBmc::create(&ctx, &mm, new_label_c()).await?;
+
+		// -- Exec
+		let res = LabelBmc::create(&ctx, &mm, new_label_c()).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				&res,
+				Err(Error::LabelNameAlreadyExists { project_id, name })
+					if *project_id == fx_project_id && name == "bug"
+			),
+			"should be LabelNameAlreadyExists, but was {res:?}"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,67 @@
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub enum Error {
+	/// The migration file name is not `<version>_<description>.sql`.
+	MigrationFileNameInvalid {
+		file_name: String,
+	},
+	MigrationVersionDuplicate {
+		version: i64,
+	},
+	/// The applied migration file is not in the migrations dir anymore.
+	MigrationFileMissing {
+		version: i64,
+	},
+	/// The applied migration file was edited since it was applied.
+	MigrationChecksumMismatch {
+		version: i64,
+		file_name: String,
+	},
+	/// The pending migration is older than the last applied one.
+	MigrationOutOfOrder {
+		version: i64,
+		last_applied_version: i64,
+	},
+	MigrationFail {
+		version: i64,
+		file_name: String,
+		#[serde_as(as = "DisplayFromStr")]
+		cause: sqlx::Error,
+	},
+
+	// -- Externals
+	Io(#[serde_as(as = "DisplayFromStr")] std::io::Error),
+	Sqlx(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
+}
+
+// region:    --- Froms
+impl From<std::io::Error> for Error {
+	fn from(val: std::io::Error) -> Self {
+		Self::Io(val)
+	}
+}
+
+impl From<sqlx::Error> for Error {
+	fn from(val: sqlx::Error) -> Self {
+		Self::Sqlx(val)
+	}
+}
+// endregion: --- Froms
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
@@ -0,0 +1,333 @@
+//! Versioned Database Migrations
+//!
+//! Design:
+//!
+//! - The migrations are the `<version>_<description>.sql` files of the migrations dir
+//!   (`SERVICE_MIGRATIONS_DIR`), applied in their version order
+//!   (e.g., `0001_initial_schema.sql`, then `0002_...sql`).
+//! - The applied migrations are recorded in the `schema_migrations` table,
+//!   with the sha256 checksum of their file content.
+//! - `migrate(...)` applies only the pending migrations, each in its own transaction
+//!   (with its `schema_migrations` row).
+//! - It refuses to run (i.e., applies none) when an applied migration file was
+//!   edited or removed, or when a pending migration is older than the last applied one.
+//! - The runs are serialized by a postgres advisory lock
+//!   (e.g., for the concurrent starts of the service instances).
+//!
+//! Note: Once applied, a migration file must not be edited,
+//!       the schema changes go in a new migration file.
+//!
+
+// region:    --- Modules
+
+mod error;
+mod sql_split;
+
+pub use self::error::{Error, Result};
+pub(crate) use self::sql_split::split_sql;
+
+use crate::core_config;
+use crate::model::ModelManager;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use sqlx::{Connection, Executor, PgConnection};
+use std::fs;
+use std::path::Path;
+use tracing::info;
+
+// endregion: --- Modules
+
+const SQL_CREATE_MIGRATIONS_TABLE: &str = "
+CREATE TABLE IF NOT EXISTS schema_migrations (
+  version BIGINT PRIMARY KEY,
+  description varchar(256) NOT NULL,
+  checksum bytea NOT NULL,
+  applied_at timestamp with time zone NOT NULL DEFAULT now()
+)";
+
+/// The advisory lock key of the migration runs (arbitrary, but app wide).
+const MIGRATIONS_LOCK_KEY: i64 = 0x006d_6967_7261_7465;
+
+/// A migration file.
+struct Migration {
+	version: i64,
+	description: String,
+	file_name: String,
+	sql: String,
+	checksum: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct MigrationApplied {
+	pub version: i64,
+	pub description: String,
+}
+
+/// Apply the pending migrations of the `SERVICE_MIGRATIONS_DIR` dir,
+/// and returns them.
+pub async fn migrate(mm: &ModelManager) -> Result<Vec<MigrationApplied>> {
+	migrate_dir(mm, Path::new(&core_config().MIGRATIONS_DIR)).await
+}
+
+/// Apply the pending migrations of the `dir` migrations dir,
+/// and returns them.
+pub async fn migrate_dir(
+	mm: &ModelManager,
+	dir: &Path,
+) -> Result<Vec<MigrationApplied>> {
+	let migrations = load_migrations(dir)?;
+
+	let mut conn = mm.dbx().db_pool().acquire().await?;
+	sqlx::query("SELECT pg_advisory_lock($1)")
+		.bind(MIGRATIONS_LOCK_KEY)
+		.execute(&mut *conn)
+		.await?;
+
+	let applied_res = apply_pending(&mut conn, &migrations).await;
+
+	sqlx::query("SELECT pg_advisory_unlock($1)")
+		.bind(MIGRATIONS_LOCK_KEY)
+		.execute(&mut *conn)
+		.await?;
+
+	applied_res
+}
+
+async fn apply_pending(
+	conn: &mut PgConnection,
+	migrations: &[Migration],
+) -> Result<Vec<MigrationApplied>> {
+	conn.execute(SQL_CREATE_MIGRATIONS_TABLE).await?;
+
+	let applied: Vec<(i64, Vec<u8>)> = sqlx::query_as(
+		"SELECT version, checksum FROM schema_migrations ORDER BY version",
+	)
+	.fetch_all(&mut *conn)
+	.await?;
+
+	// -- Check the applied migrations (before applying any)
+	for (version, checksum) in &applied {
+		let migration = migrations
+			.iter()
+			.find(|m| m.version == *version)
+			.ok_or(Error::MigrationFileMissing { version: *version })?;
+		if &migration.checksum != checksum {
+			return Err(Error::MigrationChecksumMismatch {
+				version: *version,
+				file_name: migration.file_name.to_string(),
+			});
+		}
+	}
+
+	let pending: Vec<&Migration> = migrations
+		.iter()
+		.filter(|m| !applied.iter().any(|(version, _)| *version == m.version))
+		.collect();
+	if let (Some((last_applied_version, _)), Some(first_pending)) =
+		(applied.last(), pending.first())
+	{
+		if first_pending.version < *last_applied_version {
+			return Err(Error::MigrationOutOfOrder {
+				version: first_pending.version,
+				last_applied_version: *last_applied_version,
+			});
+		}
+	}
+
+	// -- Apply the pending migrations
+	let mut migrations_applied = Vec::new();
+	for migration in pending {
+		info!("{:<12} - apply {}", "MIGRATION", migration.file_name);
+
+		let mut txn = conn.begin().await?;
+		for statement in split_sql(&migration.sql) {
+			txn.execute(statement)
+				.await
+				.map_err(|cause| Error::MigrationFail {
+					version: migration.version,
+					file_name: migration.file_name.to_string(),
+					cause,
+				})?;
+		}
+		sqlx::query(
+			"INSERT INTO schema_migrations (version, description, checksum)
+			 VALUES ($1, $2, $3)",
+		)
+		.bind(migration.version)
+		.bind(&migration.description)
+		.bind(&migration.checksum)
+		.execute(&mut *txn)
+		.await?;
+		txn.commit().await?;
+
+		migrations_applied.push(MigrationApplied {
+			version: migration.version,
+			description: migration.description.to_string(),
+		});
+	}
+
+	Ok(migrations_applied)
+}
+
+/// Returns the migrations of the `dir` (i.e., its `.sql` files), by version.
+fn load_migrations(dir: &Path) -> Result<Vec<Migration>> {
+	let mut migrations = Vec::new();
+
+	for entry in fs::read_dir(dir)? {
+		let path = entry?.path();
+		if path.extension().is_none_or(|ext| ext != "sql") {
+			continue;
+		}
+
+		let file_name = path
+			.file_name()
+			.map(|name| name.to_string_lossy().to_string())
+			.unwrap_or_default();
+		let (version, description) =
+			parse_file_name(&file_name).ok_or_else(|| {
+				Error::MigrationFileNameInvalid {
+					file_name: file_name.to_string(),
+				}
+			})?;
+
+		let sql = fs::read_to_string(&path)?;
+		let checksum = Sha256::digest(sql.as_bytes()).to_vec();
+
+		migrations.push(Migration {
+			version,
+			description,
+			file_name,
+			sql,
+			checksum,
+		});
+	}
+
+	migrations.sort_by_key(|m| m.version);
+	if let Some(duplicate) =
+		migrations.windows(2).find(|w| w[0].version == w[1].version)
+	{
+		return Err(Error::MigrationVersionDuplicate {
+			version: duplicate[0].version,
+		});
+	}
+
+	Ok(migrations)
+}
+
+/// Returns the version and description of the `<version>_<description>.sql`
+/// file name (e.g., `0001_initial_schema.sql`).
+fn parse_file_name(file_name: &str) -> Option<(i64, String)> {
+	let stem = file_name.strip_suffix(".sql")?;
+	let (version, description) = stem.split_once('_')?;
+	if version.is_empty() || !version.chars().all(|c| c.is_ascii_digit()) {
+		return None;
+	}
+
+	Some((version.parse().ok()?, description.to_string()))
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serial_test::serial;
+	use std::path::PathBuf;
+	use uuid::Uuid;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_migrate_no_pending_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+
+		// -- Exec
+		let applied = migrate(&mm).await?;
+
+		// -- Check
+		assert!(applied.is_empty(), "should have no pending migration");
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_migrate_dir_pending_then_edited() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let fx_dir = fx_migrations_dir()?;
+		let fx_file = fx_dir.join("9001_test_migrate.sql");
+		let fx_sql = "
+CREATE TABLE test_migrate (name varchar(128));
+CREATE FUNCTION test_migrate_fn() RETURNS trigger AS $$
+BEGIN
+  NEW.name := lower(NEW.name);
+  RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+";
+		fs::write(&fx_file, fx_sql)?;
+
+		// -- Exec
+		let applied = migrate_dir(&mm, &fx_dir).await?;
+		let applied_again = migrate_dir(&mm, &fx_dir).await?;
+		fs::write(&fx_file, format!("{fx_sql}\n-- edited"))?;
+		let edited_res = migrate_dir(&mm, &fx_dir).await;
+
+		// -- Check
+		let versions: Vec<i64> = applied.iter().map(|m| m.version).collect();
+		assert_eq!(versions, &[9001]);
+		assert_eq!(applied[0].description, "test_migrate");
+		assert!(applied_again.is_empty(), "should be applied only once");
+		assert!(
+			matches!(
+				edited_res,
+				Err(Error::MigrationChecksumMismatch { version: 9001, .. })
+			),
+			"should be MigrationChecksumMismatch, but was {edited_res:?}"
+		);
+
+		// -- Clean
+		let db = mm.dbx().db_pool();
+		sqlx::query("DROP FUNCTION test_migrate_fn()")
+			.execute(db)
+			.await?;
+		sqlx::query("DROP TABLE test_migrate").execute(db).await?;
+		sqlx::query("DELETE FROM schema_migrations WHERE version = 9001")
+			.execute(db)
+			.await?;
+		fs::remove_dir_all(&fx_dir)?;
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_file_name() -> Result<()> {
+		// -- Exec & Check
+		assert_eq!(
+			parse_file_name("0001_initial_schema.sql"),
+			Some((1, "initial_schema".to_string()))
+		);
+		assert_eq!(parse_file_name("initial_schema.sql"), None);
+		assert_eq!(parse_file_name("01a_initial.sql"), None);
+
+		Ok(())
+	}
+
+	/// Returns a new temp dir with a copy of the migrations.
+	fn fx_migrations_dir() -> Result<PathBuf> {
+		let dir =
+			std::env::temp_dir().join(format!("migrations-{}", Uuid::new_v4()));
+		fs::create_dir_all(&dir)?;
+		for entry in fs::read_dir(&core_config().MIGRATIONS_DIR)? {
+			let path = entry?.path();
+			if let Some(file_name) = path.file_name() {
+				fs::copy(&path, dir.join(file_name))?;
+			}
+		}
+
+		Ok(dir)
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,193 @@
+//! The split of a sql script into its statements, on the `;` outside of
+//! the quoted strings and identifiers, the dollar-quoted strings
+//! (e.g., `$$ ... $$` or `$body$ ... $body$` function bodies), and the comments.
+
+/// Returns the statements of the `sql` script (trimmed, without their `;`),
+/// skipping the empty (or comment only) ones.
+pub fn split_sql(sql: &str) -> Vec<&str> {
+	let bytes = sql.as_bytes();
+	let mut statements = Vec::new();
+	let mut start = 0;
+	// If the current statement has something else than spaces and comments.
+	let mut has_content = false;
+	let mut i = 0;
+
+	while i < bytes.len() {
+		let rest = &bytes[i..];
+		i = match bytes[i] {
+			b';' => {
+				if has_content {
+					statements.push(sql[start..i].trim());
+				}
+				start = i + 1;
+				has_content = false;
+				i + 1
+			}
+			b'-' if rest.starts_with(b"--") => line_comment_end(bytes, i),
+			b'/' if rest.starts_with(b"/*") => block_comment_end(bytes, i),
+			c => {
+				if !c.is_ascii_whitespace() {
+					has_content = true;
+				}
+				match c {
+					b'\'' => {
+						let is_escape_string = i > 0
+							&& bytes[i - 1].eq_ignore_ascii_case(&b'e')
+							&& !is_ident_byte(bytes.get(i.wrapping_sub(2)));
+						quoted_end(bytes, i, b'\'', is_escape_string)
+					}
+					b'"' => quoted_end(bytes, i, b'"', false),
+					b'$' => dollar_quoted_end(bytes, i).unwrap_or(i + 1),
+					_ => i + 1,
+				}
+			}
+		};
+	}
+
+	if has_content {
+		statements.push(sql[start..].trim());
+	}
+
+	statements
+}
+
+/// Returns the index after the `--` comment line starting at `i`.
+fn line_comment_end(bytes: &[u8], i: usize) -> usize {
+	bytes[i..]
+		.iter()
+		.position(|c| *c == b'\n')
+		.map_or(bytes.len(), |pos| i + pos + 1)
+}
+
+/// Returns the index after the (possibly nested) `/* */` comment starting at `i`.
+fn block_comment_end(bytes: &[u8], i: usize) -> usize {
+	let mut depth = 0;
+	let mut i = i;
+	while i < bytes.len() {
+		let rest = &bytes[i..];
+		if rest.starts_with(b"/*") {
+			depth += 1;
+			i += 2;
+		} else if rest.starts_with(b"*/") {
+			depth -= 1;
+			i += 2;
+			if depth == 0 {
+				return i;
+			}
+		} else {
+			i += 1;
+		}
+	}
+
+	bytes.len()
+}
+
+/// Returns the index after the `quote` quoted string (or identifier) starting at `i`,
+/// with the doubled quote escapes (and the backslash ones for the `E'...'` strings).
+fn quoted_end(bytes: &[u8], i: usize, quote: u8, is_escape_string: bool) -> usize {
+	let mut i = i + 1;
+	while i < bytes.len() {
+		match bytes[i] {
+			b'\\' if is_escape_string => i += 2,
+			c if c == quote => {
+				if bytes.get(i + 1) == Some(&quote) {
+					i += 2;
+				} else {
+					return i + 1;
+				}
+			}
+			_ => i += 1,
+		}
+	}
+
+	bytes.len()
+}
+
+/// Returns the index after the `$tag$ ... $tag$` string starting at `i`,
+/// or `None` if the `$` does not start one (e.g., a `$1` parameter).
+fn dollar_quoted_end(bytes: &[u8], i: usize) -> Option<usize> {
+	// The dollar quote is not part of an identifier (e.g., `a$b`).
+	if is_ident_byte(i.checked_sub(1).and_then(|prev| bytes.get(prev))) {
+		return None;
+	}
+
+	// -- The opening `$tag$` (the tag is an identifier, possibly empty)
+	let tag_len = bytes[i + 1..]
+		.iter()
+		.position(|c| !is_ident_byte(Some(c)))
+		.unwrap_or(bytes.len() - i - 1);
+	let tag_end = i + 1 + tag_len;
+	if bytes.get(tag_end) != Some(&b'$')
+		|| bytes.get(i + 1).is_some_and(u8::is_ascii_digit)
+	{
+		return None;
+	}
+	let delimiter = &bytes[i..=tag_end];
+
+	// -- The closing `$tag$`
+	let body_start = tag_end + 1;
+	let end = bytes[body_start..]
+		.windows(delimiter.len())
+		.position(|window| window == delimiter)
+		.map_or(bytes.len(), |pos| body_start + pos + delimiter.len());
+
+	Some(end)
+}
+
+fn is_ident_byte(c: Option<&u8>) -> bool {
+	c.is_some_and(|c| c.is_ascii_alphanumeric() || *c == b'_')
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+
+	#[test]
+	fn test_split_sql_simple_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_sql =
+			"CREATE TABLE a (id int);\n\n  INSERT INTO a VALUES (1) ;\n-- end\n";
+
+		// -- Exec
+		let statements = split_sql(fx_sql);
+
+		// -- Check
+		assert_eq!(
+			statements,
+			&["CREATE TABLE a (id int)", "INSERT INTO a VALUES (1)"]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_split_sql_quoted_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_sql = r#"
+INSERT INTO "a;b" VALUES ('it''s; fine', E'\'; still', 'a\');
+-- a comment; not a statement
+/* a /* nested; */ comment; */
+CREATE FUNCTION f() RETURNS trigger AS $$
+BEGIN
+  NEW.a := 'x;y'; RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+DO $body$ BEGIN PERFORM $1; RAISE NOTICE '$$;'; END $body$;
+"#;
+
+		// -- Exec
+		let statements = split_sql(fx_sql);
+
+		// -- Check
+		assert_eq!(statements.len(), 3, "statements: {statements:#?}");
+		assert!(statements[0].ends_with(r"'a\')"));
+		assert!(statements[1].contains("*/\nCREATE FUNCTION f()"));
+		assert!(statements[1].ends_with("$$ LANGUAGE plpgsql"));
+		assert!(statements[2].ends_with("END $body$"));
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -1,7 +1,87 @@
 use time::serde::rfc3339;
+use time::OffsetDateTime;
 
 pub fn time_to_sea_value(
 	json_value: serde_json::Value,
 ) -> modql::filter::SeaResult<sea_query::Value> {
 	Ok(rfc3339::deserialize(json_value)?.into())
 }
+
+/// For the `OpValsValue` integer columns needing the `$null` operator
+/// (not supported by the `OpValsInt64` json deserialization).
+pub fn int64_to_sea_value(
+	json_value: serde_json::Value,
+) -> modql::filter::SeaResult<sea_query::Value> {
+	Ok(serde_json::from_value::<i64>(json_value)?.into())
+}
+
+/// The value of a nullable id column in an update data (i.e., an `Option<NullableId>`
+/// field, where `None` leaves the column unchanged, and `Some(NullableId(None))`
+/// sets it to `NULL`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NullableId(pub Option<i64>);
+
+impl From<i64> for NullableId {
+	fn from(id: i64) -> Self {
+		NullableId(Some(id))
+	}
+}
+
+impl From<NullableId> for sea_query::Value {
+	fn from(id: NullableId) -> Self {
+		id.0.into()
+	}
+}
+
+impl sea_query::Nullable for NullableId {
+	fn null() -> sea_query::Value {
+		sea_query::Value::BigInt(None)
+	}
+}
+
+/// For the `Option<NullableId>` fields, with `#[serde(default)]`
+/// (i.e., a json `null` is `Some(NullableId(None))`, and an absent field is `None`).
+pub fn deserialize_nullable_id<'de, D>(
+	deserializer: D,
+) -> Result<Option<NullableId>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let id: Option<i64> = serde::Deserialize::deserialize(deserializer)?;
+	Ok(Some(NullableId(id)))
+}
+
+/// The value of a nullable time column in an update data
+/// (i.e., as `NullableId`, for an `Option<NullableTime>` field).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct NullableTime(pub Option<OffsetDateTime>);
+
+impl From<OffsetDateTime> for NullableTime {
+	fn from(time: OffsetDateTime) -> Self {
+		NullableTime(Some(time))
+	}
+}
+
+impl From<NullableTime> for sea_query::Value {
+	fn from(time: NullableTime) -> Self {
+		time.0.into()
+	}
+}
+
+impl sea_query::Nullable for NullableTime {
+	fn null() -> sea_query::Value {
+		sea_query::Value::TimeDateTimeWithTimeZone(None)
+	}
+}
+
+/// For the `Option<NullableTime>` fields, with `#[serde(default)]`
+/// (i.e., a json `null` is `Some(NullableTime(None))`, and an absent field is `None`).
+pub fn deserialize_nullable_time<'de, D>(
+	deserializer: D,
+) -> Result<Option<NullableTime>, D::Error>
+where
+	D: serde::Deserializer<'de>,
+{
+	let time: Option<OffsetDateTime> = rfc3339::option::deserialize(deserializer)?;
+	Ok(Some(NullableTime(time)))
+}
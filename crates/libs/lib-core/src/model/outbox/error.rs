@@ -0,0 +1,46 @@
+use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
+
+pub type Result<T> = core::result::Result<T, Error>;
+
+#[serde_as]
+#[derive(Debug, Serialize)]
+pub enum Error {
+	/// The sink failed to deliver the event (to be delivered again).
+	OutboxSinkFail {
+		sink: &'static str,
+		event_id: i64,
+		cause: String,
+	},
+
+	// -- Externals
+	Sqlx(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
+	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+}
+
+// region:    --- Froms
+impl From<sqlx::Error> for Error {
+	fn from(val: sqlx::Error) -> Self {
+		Self::Sqlx(val)
+	}
+}
+
+impl From<serde_json::Error> for Error {
+	fn from(val: serde_json::Error) -> Self {
+		Self::SerdeJson(val)
+	}
+}
+// endregion: --- Froms
+
+// region:    --- Error Boilerplate
+impl core::fmt::Display for Error {
+	fn fmt(
+		&self,
+		fmt: &mut core::fmt::Formatter,
+	) -> core::result::Result<(), core::fmt::Error> {
+		write!(fmt, "{self:?}")
+	}
+}
+
+impl std::error::Error for Error {}
+// endregion: --- Error Boilerplate
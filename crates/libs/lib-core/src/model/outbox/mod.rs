@@ -0,0 +1,446 @@
+//! Transactional Outbox of the domain events
+//!
+//! Design:
+//!
+//! - The model writes record their domain events (`DomainEvent`) in the `outbox` table
+//!   in the same transaction as the change (see `ModelManager::in_txn`, in which
+//!   the `base` write functions, and the Bmc functions recording events, run).
+//! - The `base` write functions record an `EntityChanged` event per entity
+//!   (along with its `entity_history` row, see `base::audit`), and the Bmcs
+//!   their specific events (e.g., `TaskCompleted`, `ProjectDeleted`).
+//! - The `OutboxRelay` drains the outbox in the transaction order (by transaction id),
+//!   and the event id order within a transaction, delivering each event
+//!   to all its sinks (see `OutboxSink`) before deleting it.
+//! - An event is relayed only once all the transactions older than its own
+//!   have ended, so the events of a longer transaction (visible at its commit)
+//!   are never skipped, nor relayed after the ones of a newer transaction.
+//! - The delivery is at-least-once (i.e., an event is delivered again after
+//!   a sink failure), so the sinks should be idempotent (e.g., by the event `id`).
+//! - The events which cannot be decoded are moved to the `outbox_dead_letter` table
+//!   (and logged), rather than blocking the relay.
+//!
+//! Note: A long running transaction holds back the relay of the newer events
+//!       (i.e., until its end).
+//!
+
+// region:    --- Modules
+
+mod error;
+mod relay;
+mod sink;
+
+pub use self::error::{Error, Result};
+pub use self::relay::OutboxRelay;
+pub use self::sink::{LogSink, MemorySink, OutboxSink, OutboxSinkDispatcher};
+
+use crate::ctx::Ctx;
+use crate::model::entity_history::EntityAction;
+use crate::model::ModelManager;
+use lib_utils::time::{now_utc, Rfc3339};
+use sea_query::{Iden, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use strum_macros::AsRefStr;
+
+// endregion: --- Modules
+
+// region:    --- Outbox Types
+
+/// The domain events, as recorded in the outbox (i.e., `{"type": .., "data": {..}}`).
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, AsRefStr)]
+#[serde(tag = "type", content = "data")]
+pub enum DomainEvent {
+	/// A `base` write of the entity (e.g., `{"entity": "task", ...}`).
+	EntityChanged {
+		entity: String,
+		entity_id: i64,
+		action: EntityAction,
+	},
+	/// The task status changed to `Done`.
+	TaskCompleted { task_id: i64, project_id: i64 },
+	/// The project was moved to the trash.
+	ProjectDeleted { project_id: i64 },
+}
+
+/// A domain event of the outbox, as delivered to the sinks.
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct OutboxEvent {
+	/// The event id (i.e., its order in the outbox).
+	pub id: i64,
+	pub event: DomainEvent,
+
+	// -- Timestamps
+	//    (acting user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+}
+
+#[derive(Iden)]
+enum OutboxIden {
+	#[iden = "outbox"]
+	Table,
+	EventType,
+	Payload,
+	Cid,
+	Ctime,
+}
+
+// endregion: --- Outbox Types
+
+/// Records the domain `events` of the ctx in the outbox, in one multi-row insert.
+pub(in crate::model) async fn record(
+	ctx: &Ctx,
+	mm: &ModelManager,
+	events: Vec<DomainEvent>,
+) -> crate::model::Result<()> {
+	if events.is_empty() {
+		return Ok(());
+	}
+
+	let dbx = mm.dbx_for(ctx);
+	let now = now_utc();
+
+	// -- Build query
+	let mut query = Query::insert();
+	query.into_table(OutboxIden::Table).columns([
+		OutboxIden::EventType,
+		OutboxIden::Payload,
+		OutboxIden::Cid,
+		OutboxIden::Ctime,
+	]);
+	for event in events {
+		let payload = serde_json::to_value(&event).map_err(Error::from)?;
+		query.values([
+			event.as_ref().into(),
+			payload.into(),
+			ctx.user_id().into(),
+			now.into(),
+		])?;
+	}
+
+	// -- Exec query
+	let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+	let sqlx_query = sqlx::query_with(&sql, values);
+	dbx.execute(sqlx_query).await?;
+
+	Ok(())
+}
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_in_order_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		drain_outbox(&mm).await?;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_relay_in_order_ok project")
+				.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_relay_in_order_ok task"],
+		)
+		.await?[0]
+			.id;
+		let sink = MemorySink::new();
+		let relay = OutboxRelay::new(mm.clone(), vec![sink.clone().into()]);
+
+		// -- Exec
+		let ctx_ref = &ctx;
+		mm.in_txn(|mm| async move {
+			let task_u = TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			};
+			TaskBmc::update(ctx_ref, &mm, fx_task_id, task_u).await?;
+			ProjectBmc::delete(ctx_ref, &mm, fx_project_id).await
+		})
+		.await?;
+		let relayed_count = relay.relay_batch().await?;
+		let relayed_again_count = relay.relay_batch().await?;
+
+		// -- Check
+		let events: Vec<DomainEvent> =
+			sink.events().into_iter().map(|e| e.event).collect();
+		assert_eq!(relayed_count, events.len());
+		assert_eq!(relayed_again_count, 0, "outbox should be drained");
+		let entity_changed =
+			|entity: &str, entity_id: i64, action| DomainEvent::EntityChanged {
+				entity: entity.to_string(),
+				entity_id,
+				action,
+			};
+		assert_eq!(
+			events,
+			vec![
+				entity_changed("project", fx_project_id, EntityAction::Create),
+				entity_changed("task", fx_task_id, EntityAction::Create),
+				entity_changed("task", fx_task_id, EntityAction::Update),
+				DomainEvent::TaskCompleted {
+					task_id: fx_task_id,
+					project_id: fx_project_id,
+				},
+				entity_changed("project", fx_project_id, EntityAction::Delete),
+				DomainEvent::ProjectDeleted {
+					project_id: fx_project_id,
+				},
+			]
+		);
+
+		// -- Clean
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+		drain_outbox(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_txn_order_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		drain_outbox(&mm).await?;
+		// (an older transaction, recording its event after the newer one commits)
+		let mm_other = ModelManager::new().await?;
+		let mut fx_older_txn = mm_other.dbx().db_pool().begin().await?;
+		sqlx::query("SELECT pg_current_xact_id()")
+			.execute(&mut *fx_older_txn)
+			.await?;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_relay_txn_order_ok project")
+				.await?;
+		let fx_older_event = DomainEvent::ProjectDeleted { project_id: 0 };
+		let sink = MemorySink::new();
+		let relay = OutboxRelay::new(mm.clone(), vec![sink.clone().into()]);
+
+		// -- Exec
+		let relayed_running_count = relay.relay_batch().await?;
+		sqlx::query(
+			"INSERT INTO outbox (event_type, payload, cid, ctime)
+			 VALUES ($1, $2, 0, now())",
+		)
+		.bind(fx_older_event.as_ref())
+		.bind(serde_json::to_value(&fx_older_event)?)
+		.execute(&mut *fx_older_txn)
+		.await?;
+		fx_older_txn.commit().await?;
+		let relayed_count = relay.relay_batch().await?;
+
+		// -- Check
+		assert_eq!(
+			relayed_running_count, 0,
+			"newer events should wait for the older transaction"
+		);
+		let events: Vec<DomainEvent> =
+			sink.events().into_iter().map(|e| e.event).collect();
+		assert_eq!(relayed_count, 2);
+		assert_eq!(
+			events,
+			vec![
+				fx_older_event,
+				DomainEvent::EntityChanged {
+					entity: "project".to_string(),
+					entity_id: fx_project_id,
+					action: EntityAction::Create,
+				},
+			]
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+		drain_outbox(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_sink_fail_redelivered() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		drain_outbox(&mm).await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_relay_sink_fail_redelivered project",
+		)
+		.await?;
+		let sink = MemorySink::new();
+		let sink_failing = MemorySink::new();
+		sink_failing.set_failing(true);
+		let relay = OutboxRelay::new(
+			mm.clone(),
+			vec![sink.clone().into(), sink_failing.clone().into()],
+		);
+
+		// -- Exec
+		let fail_res = relay.relay_batch().await;
+		sink_failing.set_failing(false);
+		let relayed_count = relay.relay_batch().await?;
+
+		// -- Check
+		assert!(
+			matches!(fail_res, Err(Error::OutboxSinkFail { .. })),
+			"should be OutboxSinkFail, but was {fail_res:?}"
+		);
+		let sink_ids: Vec<i64> = sink.events().iter().map(|e| e.id).collect();
+		let sink_failing_ids: Vec<i64> =
+			sink_failing.events().iter().map(|e| e.id).collect();
+		assert_eq!(relayed_count, sink_failing_ids.len());
+		// (the first event delivered again to the first sink)
+		assert_eq!(sink_ids[0], sink_ids[1]);
+		assert_eq!(sink_failing_ids, &sink_ids[1..]);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		drain_outbox(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_relay_invalid_event_dead_lettered() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		drain_outbox(&mm).await?;
+		let dbx = mm.dbx();
+		let (fx_invalid_id,) = dbx
+			.fetch_one(sqlx::query_as::<_, (i64,)>(
+				"INSERT INTO outbox (event_type, payload, cid, ctime)
+				 VALUES ('Unknown', '{\"type\": \"Unknown\"}', 0, now())
+				 RETURNING id",
+			))
+			.await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_relay_invalid_event_dead_lettered project",
+		)
+		.await?;
+		let sink = MemorySink::new();
+		let relay = OutboxRelay::new(mm.clone(), vec![sink.clone().into()]);
+
+		// -- Exec
+		let relayed_count = relay.relay_batch().await?;
+		let relayed_again_count = relay.relay_batch().await?;
+
+		// -- Check
+		let events: Vec<DomainEvent> =
+			sink.events().into_iter().map(|e| e.event).collect();
+		assert_eq!(
+			relayed_count, 2,
+			"invalid and valid events should be relayed"
+		);
+		assert_eq!(relayed_again_count, 0, "outbox should be drained");
+		assert_eq!(
+			events,
+			vec![DomainEvent::EntityChanged {
+				entity: "project".to_string(),
+				entity_id: fx_project_id,
+				action: EntityAction::Create,
+			}]
+		);
+		let (event_type, cause): (String, String) = dbx
+			.fetch_one(
+				sqlx::query_as(
+					"SELECT event_type, cause FROM outbox_dead_letter WHERE id = $1",
+				)
+				.bind(fx_invalid_id),
+			)
+			.await?;
+		assert_eq!(event_type, "Unknown");
+		assert!(
+			cause.contains("Unknown"),
+			"cause should be the decode error"
+		);
+
+		// -- Clean
+		dbx.execute(
+			sqlx::query("DELETE FROM outbox_dead_letter WHERE id = $1")
+				.bind(fx_invalid_id),
+		)
+		.await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+		drain_outbox(&mm).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_record_fail_rolls_back_write() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let fx_title = "test_record_fail_rolls_back_write task";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_record_fail_rolls_back_write project",
+		)
+		.await?;
+		let fx_task_id =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title]).await?[0]
+				.id;
+		let dbx = mm.dbx();
+
+		// -- Exec
+		// Note: The ctx queries run with the `app_rls` role (SERVICE_DB_RLS),
+		//       so its outbox inserts fail without this grant.
+		dbx.execute(sqlx::query("REVOKE INSERT ON outbox FROM app_rls"))
+			.await?;
+		let task_u = TaskForUpdate {
+			title: Some("test_record_fail_rolls_back_write new".to_string()),
+			..Default::default()
+		};
+		let update_res = TaskBmc::update(&ctx, &mm, fx_task_id, task_u).await;
+		dbx.execute(sqlx::query("GRANT INSERT ON outbox TO app_rls"))
+			.await?;
+
+		// -- Check
+		assert!(
+			update_res.is_err(),
+			"update should fail with its outbox record"
+		);
+		let task = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		assert_eq!(task.title, fx_title, "update should be rolled back");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+		drain_outbox(&mm).await?;
+
+		Ok(())
+	}
+
+	/// Relays the pending outbox events (e.g., of the previous tests) to no sink.
+	async fn drain_outbox(mm: &ModelManager) -> Result<()> {
+		let relay = OutboxRelay::new(mm.clone(), Vec::new());
+		while relay.relay_batch().await? > 0 {}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,146 @@
+//! The outbox relay, draining the outbox events to the sinks.
+
+use crate::model::outbox::{
+	DomainEvent, OutboxEvent, OutboxSink, OutboxSinkDispatcher, Result,
+};
+use crate::model::ModelManager;
+use serde_json::Value;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::PgConnection;
+use std::time::Duration;
+use tracing::error;
+
+/// The advisory lock key of the relay runs (arbitrary, but app wide).
+const OUTBOX_RELAY_LOCK_KEY: i64 = 0x0000_6f75_7462_6f78;
+const RELAY_BATCH_SIZE: i64 = 100;
+/// The wait between two relay runs, when the outbox is empty
+/// (or the previous run failed).
+const RELAY_IDLE_WAIT: Duration = Duration::from_secs(1);
+
+pub struct OutboxRelay {
+	mm: ModelManager,
+	sinks: Vec<OutboxSinkDispatcher>,
+}
+
+impl OutboxRelay {
+	pub fn new(mm: ModelManager, sinks: Vec<OutboxSinkDispatcher>) -> Self {
+		OutboxRelay { mm, sinks }
+	}
+
+	/// Relay the outbox events until the task is dropped
+	/// (e.g., spawned at the service start).
+	pub async fn run(self) {
+		loop {
+			match self.relay_batch().await {
+				Ok(0) => tokio::time::sleep(RELAY_IDLE_WAIT).await,
+				Ok(_) => (),
+				Err(ex) => {
+					error!("{:<12} - relay fail - {ex:?}", "OUTBOX");
+					tokio::time::sleep(RELAY_IDLE_WAIT).await;
+				}
+			}
+		}
+	}
+
+	/// Relay the next outbox events (in the transaction, then event id, order)
+	/// to all the sinks,
+	/// and returns their count.
+	///
+	/// On a sink failure, only the events before the failing one are removed
+	/// from the outbox (i.e., the failing one and the next ones are relayed again
+	/// on the next run).
+	///
+	/// The invalid events (i.e., not a `DomainEvent`) are logged, and moved to the
+	/// `outbox_dead_letter` table (counted as relayed), so they do not block the
+	/// next ones.
+	///
+	/// Note: The relay runs are serialized by a postgres advisory lock
+	///       (i.e., returns 0 if another relay is running).
+	pub async fn relay_batch(&self) -> Result<usize> {
+		// Note: On the primary db, and regardless of the ctx (i.e., outside of
+		//       the model layer access control).
+		let mut txn = self.mm.dbx().db_pool().begin().await?;
+
+		let (locked,): (bool,) =
+			sqlx::query_as("SELECT pg_try_advisory_xact_lock($1)")
+				.bind(OUTBOX_RELAY_LOCK_KEY)
+				.fetch_one(&mut *txn)
+				.await?;
+		if !locked {
+			return Ok(0);
+		}
+
+		// Note: Only the events of the transactions older than all the running ones,
+		//       so an event is never relayed before one of an older transaction
+		//       (i.e., committed later, or not yet).
+		let rows: Vec<(i64, Value, i64, OffsetDateTime)> = sqlx::query_as(
+			"SELECT id, payload, cid, ctime FROM outbox
+			 WHERE txn_id < pg_snapshot_xmin(pg_current_snapshot())
+			 ORDER BY txn_id, id LIMIT $1",
+		)
+		.bind(RELAY_BATCH_SIZE)
+		.fetch_all(&mut *txn)
+		.await?;
+
+		// -- Deliver the events, up to the first failure
+		//    (the invalid ones being moved to the dead letters)
+		let mut removed_ids = Vec::new();
+		let mut delivery_res = Ok(());
+		for (id, payload, cid, ctime) in rows {
+			let event = match serde_json::from_value::<DomainEvent>(payload) {
+				Ok(event) => event,
+				Err(ex) => {
+					error!("{:<12} - event invalid - id: {id} - {ex:?}", "OUTBOX");
+					dead_letter(&mut txn, id, &ex.to_string()).await?;
+					removed_ids.push(id);
+					continue;
+				}
+			};
+			let event = OutboxEvent {
+				id,
+				event,
+				cid,
+				ctime,
+			};
+			if let Err(ex) = self.deliver(&event).await {
+				delivery_res = Err(ex);
+				break;
+			}
+			removed_ids.push(id);
+		}
+
+		// -- Remove the delivered (or dead lettered) events
+		sqlx::query("DELETE FROM outbox WHERE id = ANY($1)")
+			.bind(&removed_ids)
+			.execute(&mut *txn)
+			.await?;
+		txn.commit().await?;
+
+		delivery_res.map(|_| removed_ids.len())
+	}
+
+	async fn deliver(&self, event: &OutboxEvent) -> Result<()> {
+		for sink in self.sinks.iter() {
+			sink.deliver(event).await?;
+		}
+
+		Ok(())
+	}
+}
+
+/// Copies the outbox event `id` to the `outbox_dead_letter` table, with its `cause`
+/// (to be removed from the outbox by the caller).
+async fn dead_letter(conn: &mut PgConnection, id: i64, cause: &str) -> Result<()> {
+	sqlx::query(
+		"INSERT INTO outbox_dead_letter
+		   (id, event_type, payload, cause, cid, ctime, dead_letter_time)
+		 SELECT id, event_type, payload, $2, cid, ctime, now()
+		 FROM outbox WHERE id = $1",
+	)
+	.bind(id)
+	.bind(cause)
+	.execute(conn)
+	.await?;
+
+	Ok(())
+}
@@ -0,0 +1,100 @@
+//! The outbox sinks, to which the `OutboxRelay` delivers the events
+//! (i.e., the log and in-memory ones for now, a message broker later).
+
+use crate::model::outbox::{Error, OutboxEvent, Result};
+use async_trait::async_trait;
+use derive_more::From;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use tracing::info;
+
+#[async_trait]
+pub trait OutboxSink {
+	/// Deliver the `event`.
+	///
+	/// Note: The same event can be delivered more than once (e.g., after a failure).
+	async fn deliver(&self, event: &OutboxEvent) -> Result<()>;
+}
+
+#[derive(Clone, From)]
+pub enum OutboxSinkDispatcher {
+	Log(LogSink),
+	Memory(MemorySink),
+}
+
+#[async_trait]
+impl OutboxSink for OutboxSinkDispatcher {
+	async fn deliver(&self, event: &OutboxEvent) -> Result<()> {
+		match self {
+			Self::Log(sink) => sink.deliver(event).await,
+			Self::Memory(sink) => sink.deliver(event).await,
+		}
+	}
+}
+
+// region:    --- LogSink
+
+/// Logs the events (e.g., for dev).
+#[derive(Clone, Default)]
+pub struct LogSink;
+
+#[async_trait]
+impl OutboxSink for LogSink {
+	async fn deliver(&self, event: &OutboxEvent) -> Result<()> {
+		let payload = serde_json::to_string(&event.event)?;
+		info!("{:<12} - event {} - {payload}", "OUTBOX", event.id);
+
+		Ok(())
+	}
+}
+
+// endregion: --- LogSink
+
+// region:    --- MemorySink
+
+/// Keeps the delivered events in memory (e.g., for tests).
+#[derive(Clone, Default)]
+pub struct MemorySink {
+	events: Arc<Mutex<Vec<OutboxEvent>>>,
+	failing: Arc<AtomicBool>,
+}
+
+impl MemorySink {
+	pub fn new() -> Self {
+		Self::default()
+	}
+
+	/// Returns the delivered events, in their delivery order.
+	pub fn events(&self) -> Vec<OutboxEvent> {
+		self.events
+			.lock()
+			.map(|events| events.clone())
+			.unwrap_or_default()
+	}
+
+	/// Makes the next deliveries fail (or not), e.g., to test the redeliveries.
+	pub fn set_failing(&self, failing: bool) {
+		self.failing.store(failing, Ordering::Relaxed);
+	}
+}
+
+#[async_trait]
+impl OutboxSink for MemorySink {
+	async fn deliver(&self, event: &OutboxEvent) -> Result<()> {
+		if self.failing.load(Ordering::Relaxed) {
+			return Err(Error::OutboxSinkFail {
+				sink: "memory",
+				event_id: event.id,
+				cause: "memory sink set as failing".to_string(),
+			});
+		}
+
+		if let Ok(mut events) = self.events.lock() {
+			events.push(event.clone());
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- MemorySink
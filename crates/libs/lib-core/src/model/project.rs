@@ -1,12 +1,24 @@
 use crate::ctx::Ctx;
-use crate::model::base::{self, DbBmc};
+use crate::model::base::{self, AccessKind, DbBmc, TimestampIden};
 use crate::model::modql_utils::time_to_sea_value;
+use crate::model::outbox::{self, DomainEvent};
+use crate::model::project_member::{
+	ProjectMemberBmc, ProjectMemberIden, ProjectRole,
+};
+use crate::model::workspace::WorkspaceBmc;
+use crate::model::workspace_member::WorkspaceRole;
+use crate::model::ManyTarget;
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
+use crate::model::{ListPage, ListWithMeta};
 use lib_utils::time::Rfc3339;
 use modql::field::Fields;
 use modql::filter::{FilterNodes, OpValsString, OpValsValue};
 use modql::filter::{ListOptions, OpValsInt64};
+use sea_query::{
+	Condition, Expr, Iden, PostgresQueryBuilder, Query, SelectStatement,
+};
+use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
@@ -18,6 +30,7 @@ use sqlx::FromRow;
 pub struct Project {
 	pub id: i64,
 
+	pub workspace_id: i64,
 	pub owner_id: i64,
 	pub name: String,
 
@@ -29,6 +42,12 @@ pub struct Project {
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
 	pub mtime: OffsetDateTime,
+
+	// -- Soft delete
+	//    (deleter user_id/time, only when in the trash)
+	pub did: Option<i64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	pub dtime: Option<OffsetDateTime>,
 }
 
 #[derive(Fields, Deserialize)]
@@ -36,10 +55,11 @@ pub struct ProjectForCreate {
 	pub name: String,
 }
 
+/// Note: The project `owner_id` is not updatable, the ownership is shared
+///       with the `ProjectRole::Owner` members (see `ProjectMemberBmc`).
 #[derive(Fields, Deserialize)]
 pub struct ProjectForUpdate {
 	pub name: Option<String>,
-	pub owner_id: Option<i64>,
 }
 
 /// The `ProjectForCreateInner` contains all necessary properties
@@ -53,12 +73,14 @@ pub struct ProjectForUpdate {
 #[derive(Fields)]
 struct ProjectForCreateInner {
 	pub name: String,
+	pub workspace_id: i64,
 	pub owner_id: i64,
 }
 
 #[derive(FilterNodes, Default, Deserialize)]
 pub struct ProjectFilter {
 	id: Option<OpValsInt64>,
+	workspace_id: Option<OpValsInt64>,
 	name: Option<OpValsString>,
 
 	cid: Option<OpValsInt64>,
@@ -68,6 +90,13 @@ pub struct ProjectFilter {
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
 	mtime: Option<OpValsValue>,
 }
+
+#[derive(Iden)]
+enum ProjectIden {
+	Id,
+	WorkspaceId,
+	OwnerId,
+}
 // endregion: --- Project Types
 
 // region:    --- ProjectBmc
@@ -75,25 +104,158 @@ pub struct ProjectBmc;
 
 impl DbBmc for ProjectBmc {
 	const TABLE: &'static str = "project";
+	const SOFT_DELETE: bool = true;
+
+	/// Projects are readable by all members, but written only by the owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => ProjectRole::Viewer,
+			AccessKind::Write => ProjectRole::Owner,
+		};
+
+		Some(Self::role_cond(ctx, min_role))
+	}
 }
 
 impl ProjectBmc {
+	/// Returns the condition matching the projects of the ctx active workspace
+	/// on which the ctx user has at least the `min_role`
+	/// (i.e., the projects owned by the user, or with a matching `project_member` role).
+	fn role_cond(ctx: &Ctx, min_role: ProjectRole) -> Condition {
+		let member_project_ids = Query::select()
+			.column(ProjectMemberIden::ProjectId)
+			.from(ProjectMemberBmc::table_ref())
+			.and_where(Expr::col(ProjectMemberIden::UserId).eq(ctx.user_id()))
+			.and_where(
+				Expr::col(ProjectMemberIden::Role)
+					.is_in(min_role.roles_at_least().iter().copied()),
+			)
+			.to_owned();
+
+		// Note: Without an active workspace, no project matches.
+		let workspace_cond = match ctx.workspace_id() {
+			Some(workspace_id) => {
+				Expr::col(ProjectIden::WorkspaceId).eq(workspace_id)
+			}
+			None => Expr::cust("FALSE"),
+		};
+
+		Condition::all().add(workspace_cond).add(
+			Condition::any()
+				.add(Expr::col(ProjectIden::OwnerId).eq(ctx.user_id()))
+				.add(Expr::col(ProjectIden::Id).in_subquery(member_project_ids)),
+		)
+	}
+
+	/// Returns the sub query selecting the ids of the projects, not in the trash,
+	/// on which the ctx user has at least the `min_role`.
+	///
+	/// Note: For the entities scoped by their project (e.g., `TaskBmc::access_cond`),
+	///       which are then hidden while their project is in the trash.
+	pub(in crate::model) fn accessible_ids_query(
+		ctx: &Ctx,
+		min_role: ProjectRole,
+	) -> SelectStatement {
+		Query::select()
+			.column(ProjectIden::Id)
+			.from(Self::table_ref())
+			.cond_where(Self::role_cond(ctx, min_role))
+			.and_where(Expr::col(TimestampIden::Dtime).is_null())
+			.to_owned()
+	}
+
+	/// Checks that the ctx user has at least the `min_role` on the project `id`.
+	/// (i.e., `EntityNotFound` or `AccessDenied` otherwise)
+	pub async fn check_role(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		min_role: ProjectRole,
+	) -> Result<()> {
+		let access_cond = (!ctx.is_root()).then(|| Self::role_cond(ctx, min_role));
+
+		base::check_access_cond::<Self>(mm, id, access_cond).await
+	}
+
+	/// Returns true if the ctx user can read the project `id`, in the trash or not
+	/// (e.g., to notify its trash moves, see `change_feed`).
+	pub async fn is_readable(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<bool> {
+		let dbx = mm.dbx();
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(ProjectIden::Id)
+			.and_where(Expr::col(ProjectIden::Id).eq(id));
+		if !ctx.is_root() {
+			query.cond_where(Self::role_cond(ctx, ProjectRole::Viewer));
+		}
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let found = dbx.fetch_optional(sqlx_query).await?;
+
+		Ok(found.is_some())
+	}
+
+	/// Returns the ctx active workspace id, checking that the ctx user
+	/// can create projects in it.
+	async fn ctx_workspace_id(ctx: &Ctx, mm: &ModelManager) -> Result<i64> {
+		let workspace_id = ctx.workspace_id().ok_or(Error::CtxNoWorkspace)?;
+		WorkspaceBmc::check_role(ctx, mm, workspace_id, WorkspaceRole::Member)
+			.await?;
+
+		Ok(workspace_id)
+	}
+
+	/// Create the project in the ctx active workspace.
 	pub async fn create(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		project_c: ProjectForCreate,
 	) -> Result<i64> {
+		let workspace_id = Self::ctx_workspace_id(ctx, mm).await?;
 		let project_c = ProjectForCreateInner {
 			name: project_c.name,
+			workspace_id,
 			owner_id: ctx.user_id(),
 		};
 		base::create::<Self, _>(ctx, mm, project_c).await
 	}
 
+	/// Create the projects in the ctx active workspace,
+	/// returning their ids in the `projects_c` order.
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		projects_c: Vec<ProjectForCreate>,
+	) -> Result<Vec<i64>> {
+		let workspace_id = Self::ctx_workspace_id(ctx, mm).await?;
+		let projects_c = projects_c
+			.into_iter()
+			.map(|project_c| ProjectForCreateInner {
+				name: project_c.name,
+				workspace_id,
+				owner_id: ctx.user_id(),
+			})
+			.collect();
+		base::create_many::<Self, _>(ctx, mm, projects_c).await
+	}
+
 	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Project> {
 		base::get::<Self, _>(ctx, mm, id).await
 	}
 
+	pub async fn get_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ids: &[i64],
+	) -> Result<Vec<Project>> {
+		base::get_many::<Self, _>(ctx, mm, ids).await
+	}
+
 	pub async fn list(
 		ctx: &Ctx,
 		mm: &ModelManager,
@@ -103,6 +265,36 @@ impl ProjectBmc {
 		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
 	}
 
+	/// List a page of the projects after the `cursor` (keyset pagination).
+	pub async fn list_page(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectFilter>>,
+		list_options: Option<ListOptions>,
+		cursor: Option<String>,
+	) -> Result<ListPage<Project>> {
+		base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor).await
+	}
+
+	/// List the projects with their total count and the effective limit/offset.
+	pub async fn list_with_meta(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<ListWithMeta<Project>> {
+		base::list_with_meta::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Count the projects matching the filter.
+	pub async fn count(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectFilter>>,
+	) -> Result<i64> {
+		base::count::<Self, _>(ctx, mm, filter).await
+	}
+
 	pub async fn update(
 		ctx: &Ctx,
 		mm: &ModelManager,
@@ -112,8 +304,191 @@ impl ProjectBmc {
 		base::update::<Self, _>(ctx, mm, id, project_u).await
 	}
 
+	/// Update the project, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		project_u: ProjectForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		base::update_versioned::<Self, _>(ctx, mm, id, project_u, expected_mtime)
+			.await
+	}
+
+	/// Update the `target` projects with the same `project_u`, returning their ids.
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: ManyTarget<Vec<ProjectFilter>>,
+		project_u: ProjectForUpdate,
+	) -> Result<Vec<i64>> {
+		base::update_many::<Self, _, _>(ctx, mm, target, project_u).await
+	}
+
+	/// Move the project to the trash.
+	/// (its tasks are hidden until it is restored)
 	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
-		base::delete::<Self>(ctx, mm, id).await
+		mm.in_txn(|mm| async move {
+			let mm = &mm;
+			base::delete::<Self>(ctx, mm, id).await?;
+
+			let event = DomainEvent::ProjectDeleted { project_id: id };
+			outbox::record(ctx, mm, vec![event]).await
+		})
+		.await
+	}
+
+	/// Move the `target` projects to the trash, returning their ids.
+	pub async fn delete_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: ManyTarget<Vec<ProjectFilter>>,
+	) -> Result<Vec<i64>> {
+		mm.in_txn(|mm| async move {
+			let mm = &mm;
+			let ids = base::delete_many::<Self, _>(ctx, mm, target).await?;
+
+			let events = ids
+				.iter()
+				.map(|id| DomainEvent::ProjectDeleted { project_id: *id })
+				.collect();
+			outbox::record(ctx, mm, events).await?;
+
+			Ok(ids)
+		})
+		.await
+	}
+
+	pub async fn get_trashed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<Project> {
+		base::get_trashed::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list_trashed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Project>> {
+		base::list_trashed::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	pub async fn restore(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::restore::<Self>(ctx, mm, id).await
+	}
+
+	/// Permanently delete the project from the trash, with all its tasks.
+	pub async fn purge(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::purge::<Self>(ctx, mm, id).await
 	}
 }
 // endregion: --- ProjectBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::core_config;
+	use crate::model::store::Dbx;
+	use crate::model::Error;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_rls_scopes_ctx_queries_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		// (same workspace, but not a project member)
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_rls_scopes_ctx_queries_ok project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx_owner,
+			&mm,
+			fx_project_id,
+			&["test_rls_scopes_ctx_queries_ok task"],
+		)
+		.await?[0]
+			.id;
+
+		// -- Exec
+		// Note: Raw queries, without the model layer access conditions.
+		let count_project = |dbx: Dbx| async move {
+			let query = sqlx::query_as::<_, (i64,)>(
+				"SELECT count(*) FROM project WHERE id = $1",
+			)
+			.bind(fx_project_id);
+			dbx.fetch_one(query).await.map(|(count,)| count)
+		};
+		let count_task = |dbx: Dbx| async move {
+			let query = sqlx::query_as::<_, (i64,)>(
+				"SELECT count(*) FROM task WHERE id = $1",
+			)
+			.bind(fx_task_id);
+			dbx.fetch_one(query).await.map(|(count,)| count)
+		};
+		let ctx_owner_no_ws = Ctx::new(ctx_owner.user_id())?;
+
+		// -- Check
+		assert!(core_config().DB_RLS, "SERVICE_DB_RLS should be enabled");
+		assert_eq!(count_project(mm.dbx_for(&ctx_owner)).await?, 1);
+		assert_eq!(count_task(mm.dbx_for(&ctx_owner)).await?, 1);
+		assert_eq!(count_project(mm.dbx_for(&ctx_other)).await?, 0);
+		assert_eq!(count_task(mm.dbx_for(&ctx_other)).await?, 0);
+		assert_eq!(count_project(mm.dbx_for(&ctx_owner_no_ws)).await?, 0);
+		assert_eq!(count_project(mm.dbx_for(&Ctx::root_ctx())).await?, 1);
+		assert_eq!(count_task(mm.dbx().clone()).await?, 1);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_err_access_denied() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_delete_err_access_denied project",
+		)
+		.await?;
+
+		// -- Exec
+		let res = ProjectBmc::delete(&ctx_other, &mm, fx_project_id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::AccessDenied { entity: "project", id }) if id == fx_project_id
+			),
+			"AccessDenied not matching"
+		);
+		// Still there for the owner.
+		ProjectBmc::get(&ctx_owner, &mm, fx_project_id).await?;
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,531 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::project::ProjectBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use crate::model::{ListPage, ListWithMeta};
+use lib_utils::time::Rfc3339;
+use modql::field::{FieldValue, Fields};
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Iden};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- ProjectRole
+
+/// The role of a user on a project.
+///
+/// Note: The project `owner_id` user always has the `Owner` rights,
+///       without the need of a `project_member` row.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, FieldValue, sqlx::Type, Serialize, Deserialize,
+)]
+#[sqlx(type_name = "varchar")]
+pub enum ProjectRole {
+	/// Can read, write, and delete the project, and manage its members.
+	Owner,
+	/// Can read the project, and create, update, and delete its tasks.
+	Editor,
+	/// Can only read the project and its tasks.
+	Viewer,
+}
+
+impl ProjectRole {
+	/// Returns the roles having at least the rights of this role.
+	pub fn roles_at_least(self) -> &'static [ProjectRole] {
+		use ProjectRole::*;
+
+		match self {
+			Owner => &[Owner],
+			Editor => &[Owner, Editor],
+			Viewer => &[Owner, Editor, Viewer],
+		}
+	}
+}
+
+// endregion: --- ProjectRole
+
+// region:    --- ProjectMember Types
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct ProjectMember {
+	pub id: i64,
+	pub project_id: i64,
+	pub user_id: i64,
+
+	pub role: ProjectRole,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct ProjectMemberForCreate {
+	pub project_id: i64,
+	pub user_id: i64,
+	pub role: ProjectRole,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct ProjectMemberForUpdate {
+	pub role: ProjectRole,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct ProjectMemberFilter {
+	id: Option<OpValsInt64>,
+	project_id: Option<OpValsInt64>,
+	user_id: Option<OpValsInt64>,
+	role: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+pub(in crate::model) enum ProjectMemberIden {
+	ProjectId,
+	UserId,
+	Role,
+}
+// endregion: --- ProjectMember Types
+
+// region:    --- ProjectMemberBmc
+pub struct ProjectMemberBmc;
+
+impl DbBmc for ProjectMemberBmc {
+	const TABLE: &'static str = "project_member";
+
+	/// Members are visible to all project members,
+	/// but only managed by the project owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => ProjectRole::Viewer,
+			AccessKind::Write => ProjectRole::Owner,
+		};
+
+		Some(
+			Condition::all().add(
+				Expr::col(ProjectMemberIden::ProjectId)
+					.in_subquery(ProjectBmc::accessible_ids_query(ctx, min_role)),
+			),
+		)
+	}
+}
+
+impl ProjectMemberBmc {
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		project_member_c: ProjectMemberForCreate,
+	) -> Result<i64> {
+		// -- Check the project access
+		ProjectBmc::check_role(
+			ctx,
+			mm,
+			project_member_c.project_id,
+			ProjectRole::Owner,
+		)
+		.await?;
+
+		let project_id = project_member_c.project_id;
+		let user_id = project_member_c.user_id;
+		Self::check_in_workspace(mm, project_id, user_id).await?;
+
+		base::create::<Self, _>(ctx, mm, project_member_c)
+			.await
+			.map_err(|err| member_conflict_err(err, project_id, user_id))
+	}
+
+	pub async fn get(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<ProjectMember> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectMemberFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<ProjectMember>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// List a page of the project members after the `cursor` (keyset pagination).
+	pub async fn list_page(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectMemberFilter>>,
+		list_options: Option<ListOptions>,
+		cursor: Option<String>,
+	) -> Result<ListPage<ProjectMember>> {
+		base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor).await
+	}
+
+	/// List the project members with their total count and the effective limit/offset.
+	pub async fn list_with_meta(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectMemberFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<ListWithMeta<ProjectMember>> {
+		base::list_with_meta::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Count the project members matching the filter.
+	pub async fn count(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<ProjectMemberFilter>>,
+	) -> Result<i64> {
+		base::count::<Self, _>(ctx, mm, filter).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		project_member_u: ProjectMemberForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, project_member_u).await
+	}
+
+	/// Update the project member, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		project_member_u: ProjectMemberForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		base::update_versioned::<Self, _>(
+			ctx,
+			mm,
+			id,
+			project_member_u,
+			expected_mtime,
+		)
+		.await
+	}
+
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::delete::<Self>(ctx, mm, id).await
+	}
+}
+impl ProjectMemberBmc {
+	/// Checks that the user `user_id` is a member of the workspace
+	/// of the project `project_id`.
+	async fn check_in_workspace(
+		mm: &ModelManager,
+		project_id: i64,
+		user_id: i64,
+	) -> Result<()> {
+		let dbx = mm.dbx();
+
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(
+			r#"
+			SELECT m.id FROM workspace_member m
+			JOIN project p ON p.workspace_id = m.workspace_id
+			WHERE p.id = $1 AND m.user_id = $2
+			"#,
+		)
+		.bind(project_id)
+		.bind(user_id);
+		let found = dbx.fetch_optional(sqlx_query).await?;
+
+		match found {
+			Some(_) => Ok(()),
+			None => Err(Error::ProjectMemberNotInWorkspace {
+				project_id,
+				user_id,
+			}),
+		}
+	}
+}
+
+/// Returns the `ProjectMemberAlreadyExists` error for the `(project_id, user_id)`
+/// unique violation, otherwise the `err` as is.
+fn member_conflict_err(err: Error, project_id: i64, user_id: i64) -> Error {
+	match err {
+		Error::Sqlx(sqlx::Error::Database(db_err))
+			if db_err.is_unique_violation() =>
+		{
+			Error::ProjectMemberAlreadyExists {
+				project_id,
+				user_id,
+			}
+		}
+		err => err,
+	}
+}
+// endregion: --- ProjectMemberBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::task::{TaskBmc, TaskForUpdate};
+	use crate::model::user::{UserBmc, UserForCreate};
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_viewer_read_ok_write_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_viewer = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_viewer_read_ok_write_err project",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx_owner,
+			&mm,
+			fx_project_id,
+			&["test_viewer_read_ok_write_err 01"],
+		)
+		.await?
+		.remove(0);
+
+		// -- Exec
+		ProjectMemberBmc::create(
+			&ctx_owner,
+			&mm,
+			ProjectMemberForCreate {
+				project_id: fx_project_id,
+				user_id: ctx_viewer.user_id(),
+				role: ProjectRole::Viewer,
+			},
+		)
+		.await?;
+		let task = TaskBmc::get(&ctx_viewer, &mm, fx_task.id).await?;
+		let res = TaskBmc::update(
+			&ctx_viewer,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some("test_viewer_read_ok_write_err new".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
+
+		// -- Check
+		assert_eq!(task.id, fx_task.id);
+		assert!(
+			matches!(res, Err(Error::AccessDenied { entity: "task", .. })),
+			"AccessDenied not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_editor_write_task_ok_delete_project_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_editor = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_title_new = "test_editor_write_task_ok_delete_project_err new";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_editor_write_task_ok_delete_project_err project",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx_owner,
+			&mm,
+			fx_project_id,
+			&["test_editor_write_task_ok_delete_project_err 01"],
+		)
+		.await?
+		.remove(0);
+		let fx_member_id = ProjectMemberBmc::create(
+			&ctx_owner,
+			&mm,
+			ProjectMemberForCreate {
+				project_id: fx_project_id,
+				user_id: ctx_editor.user_id(),
+				role: ProjectRole::Viewer,
+			},
+		)
+		.await?;
+
+		// -- Exec
+		ProjectMemberBmc::update(
+			&ctx_owner,
+			&mm,
+			fx_member_id,
+			ProjectMemberForUpdate {
+				role: ProjectRole::Editor,
+			},
+		)
+		.await?;
+		TaskBmc::update(
+			&ctx_editor,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_new.to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+		let res = ProjectBmc::delete(&ctx_editor, &mm, fx_project_id).await;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx_owner, &mm, fx_task.id).await?;
+		assert_eq!(task.title, fx_title_new);
+		assert!(
+			matches!(
+				res,
+				Err(Error::AccessDenied {
+					entity: "project",
+					..
+				})
+			),
+			"AccessDenied not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_not_owner() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_create_err_not_owner project",
+		)
+		.await?;
+
+		// -- Exec
+		let res = ProjectMemberBmc::create(
+			&ctx_other,
+			&mm,
+			ProjectMemberForCreate {
+				project_id: fx_project_id,
+				user_id: ctx_other.user_id(),
+				role: ProjectRole::Owner,
+			},
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::AccessDenied { entity: "project", id }) if id == fx_project_id
+			),
+			"AccessDenied not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_not_in_workspace_and_duplicate() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_member = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let root_ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_create_err_not_in_workspace_and_duplicate project",
+		)
+		.await?;
+		// (not in the demo workspace)
+		let fx_outsider_id = UserBmc::create(
+			&root_ctx,
+			&mm,
+			UserForCreate {
+				username: "test_create_err_not_in_workspace_and_duplicate user"
+					.to_string(),
+				pwd_clear: "welcome".to_string(),
+			},
+		)
+		.await?;
+		let member_c = |user_id| ProjectMemberForCreate {
+			project_id: fx_project_id,
+			user_id,
+			role: ProjectRole::Viewer,
+		};
+
+		// -- Exec
+		let outsider_res =
+			ProjectMemberBmc::create(&ctx_owner, &mm, member_c(fx_outsider_id))
+				.await;
+		ProjectMemberBmc::create(&ctx_owner, &mm, member_c(ctx_member.user_id()))
+			.await?;
+		let dup_res = ProjectMemberBmc::create(
+			&ctx_owner,
+			&mm,
+			member_c(ctx_member.user_id()),
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				outsider_res,
+				Err(Error::ProjectMemberNotInWorkspace { user_id, .. })
+					if user_id == fx_outsider_id
+			),
+			"should be ProjectMemberNotInWorkspace, but was {outsider_res:?}"
+		);
+		assert!(
+			matches!(dup_res, Err(Error::ProjectMemberAlreadyExists { .. })),
+			"should be ProjectMemberAlreadyExists, but was {dup_res:?}"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+		base::delete::<UserBmc>(&root_ctx, &mm, fx_outsider_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,375 @@
+//! The task recurrence rules, a subset of the iCalendar RRULE (RFC 5545).
+//!
+//! Supported parts:
+//!
+//! - `FREQ` (required), `DAILY`, `WEEKLY`, `MONTHLY`, or `YEARLY`.
+//! - `INTERVAL` (default 1).
+//! - `BYDAY` (e.g., `MO,WE,FR`), with `FREQ=WEEKLY` only.
+//! - `COUNT` or `UNTIL` (e.g., `20261231` or `20261231T170000Z`), not both.
+//!
+//! e.g., `FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=10`
+//!
+//! The occurrences are chained, i.e., each occurrence has its own rule,
+//! with the `COUNT` of the remaining occurrences (itself included).
+//! The `MONTHLY` and `YEARLY` occurrences skip the months without
+//! their day (e.g., the 31st, or Feb 29th).
+
+use crate::model::{Error, Result};
+use std::fmt;
+use std::str::FromStr;
+use time::format_description::FormatItem;
+use time::macros::format_description;
+use time::{
+	Date, Duration, Month, OffsetDateTime, PrimitiveDateTime, Time, Weekday,
+};
+
+const UNTIL_DATE_FORMAT: &[FormatItem<'static>] =
+	format_description!("[year][month][day]");
+const UNTIL_DATE_TIME_FORMAT: &[FormatItem<'static>] =
+	format_description!("[year][month][day]T[hour][minute][second]Z");
+
+/// Max months/years skipped looking for a valid day
+/// (e.g., Feb 29th is at most 8 years away).
+const SKIP_MAX: u32 = 8 * 12;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Frequency {
+	Daily,
+	Weekly,
+	Monthly,
+	Yearly,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Recurrence {
+	pub freq: Frequency,
+	pub interval: u32,
+	/// The week days (in the week order, from Monday), for `Weekly` only.
+	pub by_day: Vec<Weekday>,
+	/// The number of occurrences, including the current one.
+	pub count: Option<u32>,
+	pub until: Option<OffsetDateTime>,
+}
+
+impl Recurrence {
+	/// Returns the occurrence following the one due at `due_date`,
+	/// with its own (chained) rule, or `None` if the recurrence is over.
+	pub fn next_occurrence(
+		&self,
+		due_date: OffsetDateTime,
+	) -> Option<(OffsetDateTime, Recurrence)> {
+		let count = match self.count {
+			Some(count) if count <= 1 => return None,
+			Some(count) => Some(count - 1),
+			None => None,
+		};
+
+		let next_due_date = self.next_due_date(due_date)?;
+		if self.until.is_some_and(|until| next_due_date > until) {
+			return None;
+		}
+
+		let next = Recurrence {
+			count,
+			..self.clone()
+		};
+
+		Some((next_due_date, next))
+	}
+
+	/// Returns the due dates of the (at most `limit`) occurrences
+	/// following the one due at `due_date`.
+	pub fn upcoming(
+		&self,
+		due_date: OffsetDateTime,
+		limit: usize,
+	) -> Vec<OffsetDateTime> {
+		let mut due_dates = Vec::new();
+		let mut current = (due_date, self.clone());
+		while due_dates.len() < limit {
+			let Some(next) = current.1.next_occurrence(current.0) else {
+				break;
+			};
+			due_dates.push(next.0);
+			current = next;
+		}
+
+		due_dates
+	}
+
+	fn next_due_date(&self, due_date: OffsetDateTime) -> Option<OffsetDateTime> {
+		let interval = self.interval as i64;
+
+		match self.freq {
+			Frequency::Daily => due_date.checked_add(Duration::days(interval)),
+			Frequency::Weekly => {
+				let weekday = due_date.weekday().number_days_from_monday() as i64;
+				// The next day of the same week, or the first day
+				// of the next `interval` week.
+				let days = match self
+					.by_day
+					.iter()
+					.map(|day| day.number_days_from_monday() as i64)
+					.find(|day| *day > weekday)
+				{
+					Some(day) => day - weekday,
+					None => match self.by_day.first() {
+						Some(first) => {
+							7 * interval - weekday
+								+ first.number_days_from_monday() as i64
+						}
+						None => 7 * interval,
+					},
+				};
+				due_date.checked_add(Duration::days(days))
+			}
+			Frequency::Monthly => add_months(due_date, self.interval),
+			Frequency::Yearly => add_months(due_date, self.interval * 12),
+		}
+	}
+}
+
+/// Returns the `due_date` plus `months` (or a multiple of them, when its day
+/// is not in the month).
+fn add_months(due_date: OffsetDateTime, months: u32) -> Option<OffsetDateTime> {
+	let month_index = due_date.year() as i64 * 12 + due_date.month() as i64 - 1;
+
+	(1..=SKIP_MAX).find_map(|k| {
+		let month_index = month_index + (months * k) as i64;
+		let year = i32::try_from(month_index.div_euclid(12)).ok()?;
+		let month = Month::try_from((month_index.rem_euclid(12) + 1) as u8).ok()?;
+		let date = Date::from_calendar_date(year, month, due_date.day()).ok()?;
+		Some(due_date.replace_date(date))
+	})
+}
+
+// region:    --- Parse & Display
+
+impl FromStr for Recurrence {
+	type Err = Error;
+
+	fn from_str(rule: &str) -> Result<Self> {
+		let invalid = |cause: &'static str| Error::TaskRecurrenceInvalid {
+			recurrence: rule.to_string(),
+			cause,
+		};
+
+		let mut freq = None;
+		let mut interval = 1;
+		let mut by_day = Vec::new();
+		let mut count = None;
+		let mut until = None;
+
+		let parts = rule.trim().trim_start_matches("RRULE:");
+		for part in parts.split(';').filter(|part| !part.is_empty()) {
+			let (name, value) = part
+				.split_once('=')
+				.ok_or(invalid("part is not NAME=VALUE"))?;
+			match name.to_ascii_uppercase().as_str() {
+				"FREQ" => {
+					freq = Some(match value.to_ascii_uppercase().as_str() {
+						"DAILY" => Frequency::Daily,
+						"WEEKLY" => Frequency::Weekly,
+						"MONTHLY" => Frequency::Monthly,
+						"YEARLY" => Frequency::Yearly,
+						_ => return Err(invalid("FREQ not supported")),
+					})
+				}
+				"INTERVAL" => {
+					interval = parse_positive(value)
+						.ok_or(invalid("INTERVAL must be a positive integer"))?
+				}
+				"BYDAY" => {
+					for day in value.split(',') {
+						by_day.push(
+							parse_weekday(day)
+								.ok_or(invalid("BYDAY day invalid"))?,
+						);
+					}
+				}
+				"COUNT" => {
+					let value = parse_positive(value)
+						.ok_or(invalid("COUNT must be a positive integer"))?;
+					count = Some(value)
+				}
+				"UNTIL" => {
+					until = Some(parse_until(value).ok_or(invalid("UNTIL invalid"))?)
+				}
+				_ => return Err(invalid("part not supported")),
+			}
+		}
+
+		let freq = freq.ok_or(invalid("FREQ missing"))?;
+		if !by_day.is_empty() && freq != Frequency::Weekly {
+			return Err(invalid("BYDAY only supported with FREQ=WEEKLY"));
+		}
+		if count.is_some() && until.is_some() {
+			return Err(invalid("COUNT and UNTIL are exclusive"));
+		}
+		by_day.sort_by_key(|day| day.number_days_from_monday());
+		by_day.dedup();
+
+		Ok(Recurrence {
+			freq,
+			interval,
+			by_day,
+			count,
+			until,
+		})
+	}
+}
+
+impl fmt::Display for Recurrence {
+	fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+		let freq = match self.freq {
+			Frequency::Daily => "DAILY",
+			Frequency::Weekly => "WEEKLY",
+			Frequency::Monthly => "MONTHLY",
+			Frequency::Yearly => "YEARLY",
+		};
+		write!(f, "FREQ={freq}")?;
+		if self.interval != 1 {
+			write!(f, ";INTERVAL={}", self.interval)?;
+		}
+		if !self.by_day.is_empty() {
+			let days: Vec<&str> =
+				self.by_day.iter().map(|day| weekday_code(*day)).collect();
+			write!(f, ";BYDAY={}", days.join(","))?;
+		}
+		if let Some(count) = self.count {
+			write!(f, ";COUNT={count}")?;
+		}
+		if let Some(until) = self.until {
+			// Note: The format of valid utc dates cannot fail.
+			let until = until
+				.format(UNTIL_DATE_TIME_FORMAT)
+				.map_err(|_| fmt::Error)?;
+			write!(f, ";UNTIL={until}")?;
+		}
+
+		Ok(())
+	}
+}
+
+const WEEKDAY_CODES: [(Weekday, &str); 7] = [
+	(Weekday::Monday, "MO"),
+	(Weekday::Tuesday, "TU"),
+	(Weekday::Wednesday, "WE"),
+	(Weekday::Thursday, "TH"),
+	(Weekday::Friday, "FR"),
+	(Weekday::Saturday, "SA"),
+	(Weekday::Sunday, "SU"),
+];
+
+fn parse_positive(value: &str) -> Option<u32> {
+	value.parse().ok().filter(|value| *value > 0)
+}
+
+fn parse_weekday(code: &str) -> Option<Weekday> {
+	WEEKDAY_CODES
+		.iter()
+		.find(|(_, day_code)| day_code.eq_ignore_ascii_case(code.trim()))
+		.map(|(day, _)| *day)
+}
+
+fn weekday_code(day: Weekday) -> &'static str {
+	WEEKDAY_CODES[day.number_days_from_monday() as usize].1
+}
+
+/// Parse the `UNTIL` utc date-time, or date (i.e., until the end of that day).
+fn parse_until(value: &str) -> Option<OffsetDateTime> {
+	if let Ok(until) = PrimitiveDateTime::parse(value, UNTIL_DATE_TIME_FORMAT) {
+		return Some(until.assume_utc());
+	}
+	let date = Date::parse(value, UNTIL_DATE_FORMAT).ok()?;
+	let end_of_day = Time::from_hms(23, 59, 59).ok()?;
+
+	Some(PrimitiveDateTime::new(date, end_of_day).assume_utc())
+}
+
+// endregion: --- Parse & Display
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use anyhow::Result;
+	use time::macros::datetime;
+
+	#[test]
+	fn test_weekly_by_day_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rule: Recurrence =
+			"FREQ=WEEKLY;INTERVAL=2;BYDAY=TH,MO;COUNT=4".parse()?;
+		// a Monday
+		let fx_due_date = datetime!(2026-10-19 9:00 UTC);
+
+		// -- Exec
+		let upcoming = fx_rule.upcoming(fx_due_date, 10);
+
+		// -- Check
+		assert_eq!(
+			fx_rule.to_string(),
+			"FREQ=WEEKLY;INTERVAL=2;BYDAY=MO,TH;COUNT=4"
+		);
+		assert_eq!(
+			upcoming,
+			&[
+				datetime!(2026-10-22 9:00 UTC),
+				datetime!(2026-11-02 9:00 UTC),
+				datetime!(2026-11-05 9:00 UTC),
+			]
+		);
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_monthly_until_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rule: Recurrence = "RRULE:FREQ=MONTHLY;UNTIL=20270531".parse()?;
+		let fx_due_date = datetime!(2027-01-31 17:00 UTC);
+
+		// -- Exec
+		let upcoming = fx_rule.upcoming(fx_due_date, 10);
+
+		// -- Check
+		// (skipping the months without a 31st)
+		assert_eq!(
+			upcoming,
+			&[
+				datetime!(2027-03-31 17:00 UTC),
+				datetime!(2027-05-31 17:00 UTC),
+			]
+		);
+		assert_eq!(fx_rule.to_string(), "FREQ=MONTHLY;UNTIL=20270531T235959Z");
+
+		Ok(())
+	}
+
+	#[test]
+	fn test_parse_err() -> Result<()> {
+		// -- Setup & Fixtures
+		let fx_rules = &[
+			"INTERVAL=2",
+			"FREQ=HOURLY",
+			"FREQ=DAILY;BYDAY=MO",
+			"FREQ=DAILY;COUNT=2;UNTIL=20261231",
+			"FREQ=DAILY;INTERVAL=0",
+		];
+
+		for rule in fx_rules {
+			// -- Exec
+			let res = rule.parse::<Recurrence>();
+
+			// -- Check
+			assert!(
+				matches!(res, Err(Error::TaskRecurrenceInvalid { .. })),
+				"'{rule}' should be invalid"
+			);
+		}
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,151 @@
+//! Full-text search across the tasks and projects.
+//!
+//! - The hits are ranked by `ts_rank` (best first), and highlighted with
+//!   `ts_headline` (the matching words in `<b>..</b>`).
+//! - Only the hits readable by the ctx user, and not in the trash, are returned.
+//!
+//! See `model::base::search` for the per entity search select.
+
+use crate::ctx::Ctx;
+use crate::model::base;
+use crate::model::project::ProjectBmc;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use modql::filter::ListOptions;
+use sea_query::{Alias, Order, PostgresQueryBuilder, UnionType};
+use sea_query_binder::SqlxBinder;
+use serde::Serialize;
+use sqlx::FromRow;
+
+// region:    --- SearchHit Types
+
+#[derive(Debug, Clone, FromRow, Serialize)]
+pub struct SearchHit {
+	/// The entity table name (i.e., "task" or "project").
+	pub entity: String,
+	pub id: i64,
+	/// The entity searched text (i.e., the task title or project name).
+	pub text: String,
+	/// The `text` with the matching words highlighted (e.g., `"<b>Review</b> PR"`).
+	pub headline: String,
+	pub rank: f32,
+}
+
+// endregion: --- SearchHit Types
+
+// region:    --- SearchBmc
+
+pub struct SearchBmc;
+
+impl SearchBmc {
+	/// Search the tasks and projects matching the `query`
+	/// (websearch syntax, e.g., `"design review" -draft`).
+	///
+	/// Note: The `list_options` limit and offset apply to the ranked hits,
+	///       and their `order_bys` are ignored.
+	pub async fn search(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		query: &str,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<SearchHit>> {
+		let list_options = base::compute_list_options(list_options)?;
+		if query.trim().is_empty() {
+			return Ok(Vec::new());
+		}
+
+		let dbx = mm.dbx_for(ctx);
+
+		// -- Build query
+		let mut select = base::search_select::<TaskBmc>(ctx, "title", query);
+		select
+			.union(
+				UnionType::All,
+				base::search_select::<ProjectBmc>(ctx, "name", query),
+			)
+			.order_by(Alias::new("rank"), Order::Desc)
+			.order_by(Alias::new("entity"), Order::Asc)
+			.order_by(Alias::new("id"), Order::Asc);
+		if let Some(limit) = list_options.limit {
+			select.limit(limit as u64);
+		}
+		if let Some(offset) = list_options.offset {
+			select.offset(offset as u64);
+		}
+
+		// -- Exec query
+		let (sql, values) = select.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, SearchHit, _>(&sql, values);
+		let hits = dbx.fetch_all(sqlx_query).await?;
+
+		Ok(hits)
+	}
+}
+
+// endregion: --- SearchBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_search_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_search_ok zebrafish project")
+				.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_search_ok zebrafish zebrafish feeding",
+				"test_search_ok unrelated",
+				"test_search_ok zebrafish trashed",
+			],
+		)
+		.await?;
+		TaskBmc::delete(&ctx, &mm, fx_tasks[2].id).await?;
+
+		// -- Exec
+		let hits = SearchBmc::search(&ctx, &mm, "zebrafish", None).await?;
+		let hits_paged = SearchBmc::search(
+			&ctx,
+			&mm,
+			"zebrafish",
+			Some(serde_json::from_value(json!({"limit": 1, "offset": 1}))?),
+		)
+		.await?;
+		let hits_other =
+			SearchBmc::search(&ctx_other, &mm, "zebrafish", None).await?;
+
+		// -- Check
+		let found: Vec<(&str, i64)> =
+			hits.iter().map(|h| (h.entity.as_str(), h.id)).collect();
+		assert_eq!(
+			found,
+			&[("task", fx_tasks[0].id), ("project", fx_project_id)]
+		);
+		assert!(hits[0].rank >= hits[1].rank);
+		assert!(hits[0].headline.contains("<b>zebrafish</b>"));
+		assert_eq!(hits_paged.len(), 1);
+		assert_eq!(hits_paged[0].id, fx_project_id);
+		assert!(hits_other.is_empty(), "hits should not be visible to other");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,410 @@
+//! `Dbx` is the db executor used by the model layer to run the sqlx queries.
+//!
+//! - By default, the queries run directly on the db pool.
+//! - In "txn mode" (see `ModelManager::new_with_txn`), once a transaction is begun,
+//!   the queries run on this transaction until it is committed or rolled back.
+//! - Transactions can be nested (e.g., a Bmc function beginning its own transaction
+//!   within the rpc handler one). The nested ones are savepoints of the outermost one
+//!   (i.e., a nested rollback only reverts its own writes), and only the outermost
+//!   commit is effective.
+//! - In "rls mode" (see `SERVICE_DB_RLS`), the queries of a ctx `Dbx`
+//!   (see `Dbx::for_ctx`) run with its row-level security session (see `rls`),
+//!   in the open transaction, or in their own one otherwise.
+//! - With read replicas (see `SERVICE_DB_REPLICA_URLS`), the queries of a
+//!   "read replica" `Dbx` (see `Dbx::read_replica`, i.e., declaring read-only queries)
+//!   run on a replica pool (round robin), unless in a transaction, or once a write
+//!   was run with this `Dbx` "request" (see `Dbx::new_for_request`), to read its own
+//!   writes. All the other queries, and all the queries out of a request
+//!   (e.g., of the app-level `Dbx`), run on the primary pool.
+//! - The writes of a request are its transactions (in which the `base` writes run)
+//!   and its `execute` queries.
+
+use crate::ctx::Ctx;
+use crate::model::store::rls::{self, RlsSession};
+use crate::model::store::{Db, Error, Result};
+use sqlx::postgres::PgRow;
+use sqlx::query::{Query, QueryAs};
+use sqlx::{FromRow, IntoArguments, Postgres, Transaction};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+
+#[derive(Debug, Clone)]
+pub struct Dbx {
+	/// The primary db pool.
+	db_pool: Db,
+	replica_pools: Arc<[Db]>,
+	replica_next: Arc<AtomicUsize>,
+	/// If a write was run in this `Dbx` "request" (then reading from the primary),
+	/// or `None` out of a request (i.e., always reading from the primary).
+	written: Option<Arc<AtomicBool>>,
+	/// If the read queries can run on a replica.
+	read_replica: bool,
+	txn_holder: Arc<Mutex<Option<TxnHolder>>>,
+	with_txn: bool,
+	with_rls: bool,
+	/// The row-level security session of the queries (when in rls mode).
+	rls_session: Option<RlsSession>,
+}
+
+#[derive(Debug)]
+struct TxnHolder {
+	txn: Transaction<'static, Postgres>,
+	/// The number of nested `begin_txn` not yet committed/rolled back.
+	counter: i32,
+}
+
+impl TxnHolder {
+	/// The savepoint name of the nested transaction at the current `counter`.
+	fn savepoint(&self) -> String {
+		format!("nested_txn_{}", self.counter)
+	}
+}
+
+impl Dbx {
+	pub fn new(db_pool: Db, replica_pools: Vec<Db>, with_rls: bool) -> Self {
+		Dbx {
+			db_pool,
+			replica_pools: replica_pools.into(),
+			replica_next: Arc::default(),
+			written: None,
+			read_replica: false,
+			txn_holder: Arc::default(),
+			with_txn: false,
+			with_rls,
+			rls_session: None,
+		}
+	}
+
+	/// Returns a new `Dbx` in "txn mode", sharing the same pools and "request".
+	pub fn new_with_txn(&self) -> Dbx {
+		Dbx {
+			txn_holder: Arc::default(),
+			with_txn: true,
+			read_replica: false,
+			rls_session: None,
+			..self.clone()
+		}
+	}
+
+	/// Returns a new `Dbx` sharing the same pools, for a new "request"
+	/// (i.e., reading from the replicas until its first write).
+	pub fn new_for_request(&self) -> Dbx {
+		Dbx {
+			written: Some(Arc::default()),
+			txn_holder: Arc::default(),
+			with_txn: false,
+			read_replica: false,
+			rls_session: None,
+			..self.clone()
+		}
+	}
+
+	/// Returns this `Dbx` (sharing its transaction and "request"),
+	/// running its read queries on a replica (when possible).
+	pub fn read_replica(&self) -> Dbx {
+		let mut dbx = self.clone();
+		dbx.read_replica = true;
+		dbx
+	}
+
+	/// Returns this `Dbx` (sharing its transaction), running its queries
+	/// with the `ctx` row-level security session (when in rls mode).
+	pub fn for_ctx(&self, ctx: &Ctx) -> Dbx {
+		let mut dbx = self.clone();
+		if self.with_rls {
+			dbx.rls_session = Some(RlsSession::from_ctx(ctx));
+		}
+		dbx
+	}
+
+	/// Returns the primary db pool.
+	pub fn db_pool(&self) -> &Db {
+		&self.db_pool
+	}
+
+	pub fn with_txn(&self) -> bool {
+		self.with_txn
+	}
+}
+
+// region:    --- Txn
+
+impl Dbx {
+	pub async fn begin_txn(&self) -> Result<()> {
+		if !self.with_txn {
+			return Err(Error::TxnCantBeginWithoutTxnMode);
+		}
+
+		// Note: The transactions are assumed to write.
+		self.set_written();
+
+		let mut txh_g = self.txn_holder.lock().await;
+		if let Some(txh) = txh_g.as_mut() {
+			let sql = format!("SAVEPOINT {}", txh.savepoint());
+			sqlx::query(&sql)
+				.execute(&mut *txh.txn)
+				.await
+				.map_err(Error::TxnBegin)?;
+			txh.counter += 1;
+		} else {
+			let txn = self.db_pool.begin().await.map_err(Error::TxnBegin)?;
+			*txh_g = Some(TxnHolder { txn, counter: 1 });
+		}
+
+		Ok(())
+	}
+
+	pub async fn commit_txn(&self) -> Result<()> {
+		let mut txh_g = self.txn_holder.lock().await;
+		let Some(mut txh) = txh_g.take() else {
+			return Err(Error::TxnCantCommitNoOpenTxn);
+		};
+
+		if txh.counter > 1 {
+			txh.counter -= 1;
+			let sql = format!("RELEASE SAVEPOINT {}", txh.savepoint());
+			let res = sqlx::query(&sql).execute(&mut *txh.txn).await;
+			*txh_g = Some(txh);
+			res.map_err(Error::TxnCommit)?;
+		} else {
+			txh.txn.commit().await.map_err(Error::TxnCommit)?;
+		}
+
+		Ok(())
+	}
+
+	pub async fn rollback_txn(&self) -> Result<()> {
+		let mut txh_g = self.txn_holder.lock().await;
+		let Some(mut txh) = txh_g.take() else {
+			return Err(Error::TxnCantRollbackNoOpenTxn);
+		};
+
+		if txh.counter > 1 {
+			txh.counter -= 1;
+			let sql = format!("ROLLBACK TO SAVEPOINT {}", txh.savepoint());
+			let res = sqlx::query(&sql).execute(&mut *txh.txn).await;
+			*txh_g = Some(txh);
+			res.map_err(Error::TxnRollback)?;
+		} else {
+			txh.txn.rollback().await.map_err(Error::TxnRollback)?;
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Txn
+
+// region:    --- Query Execs
+
+// Note: The query execs return the `sqlx::Result` as is,
+//       so that the model layer keeps its `Error::Sqlx`.
+impl Dbx {
+	pub async fn fetch_one<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> sqlx::Result<O>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		let db_pool = self.read_pool();
+
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				self.set_txn_rls_session(&mut txh.txn).await?;
+				return query.fetch_one(&mut *txh.txn).await;
+			}
+		}
+
+		if let Some(mut txn) = self.begin_rls_txn(db_pool).await? {
+			let res = query.fetch_one(&mut *txn).await?;
+			txn.commit().await?;
+			return Ok(res);
+		}
+
+		query.fetch_one(db_pool).await
+	}
+
+	pub async fn fetch_optional<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> sqlx::Result<Option<O>>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		let db_pool = self.read_pool();
+
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				self.set_txn_rls_session(&mut txh.txn).await?;
+				return query.fetch_optional(&mut *txh.txn).await;
+			}
+		}
+
+		if let Some(mut txn) = self.begin_rls_txn(db_pool).await? {
+			let res = query.fetch_optional(&mut *txn).await?;
+			txn.commit().await?;
+			return Ok(res);
+		}
+
+		query.fetch_optional(db_pool).await
+	}
+
+	pub async fn fetch_all<'q, O, A>(
+		&self,
+		query: QueryAs<'q, Postgres, O, A>,
+	) -> sqlx::Result<Vec<O>>
+	where
+		O: for<'r> FromRow<'r, PgRow> + Send + Unpin,
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		let db_pool = self.read_pool();
+
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				self.set_txn_rls_session(&mut txh.txn).await?;
+				return query.fetch_all(&mut *txh.txn).await;
+			}
+		}
+
+		if let Some(mut txn) = self.begin_rls_txn(db_pool).await? {
+			let res = query.fetch_all(&mut *txn).await?;
+			txn.commit().await?;
+			return Ok(res);
+		}
+
+		query.fetch_all(db_pool).await
+	}
+
+	/// Fetches the raw rows (e.g., for the queries with extra columns).
+	pub async fn fetch_all_rows<'q, A>(
+		&self,
+		query: Query<'q, Postgres, A>,
+	) -> sqlx::Result<Vec<PgRow>>
+	where
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		let db_pool = self.read_pool();
+
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				self.set_txn_rls_session(&mut txh.txn).await?;
+				return query.fetch_all(&mut *txh.txn).await;
+			}
+		}
+
+		if let Some(mut txn) = self.begin_rls_txn(db_pool).await? {
+			let rows = query.fetch_all(&mut *txn).await?;
+			txn.commit().await?;
+			return Ok(rows);
+		}
+
+		query.fetch_all(db_pool).await
+	}
+
+	/// Executes the query and returns the number of rows affected.
+	pub async fn execute<'q, A>(
+		&self,
+		query: Query<'q, Postgres, A>,
+	) -> sqlx::Result<u64>
+	where
+		A: IntoArguments<'q, Postgres> + 'q,
+	{
+		self.set_written();
+		let db_pool = &self.db_pool;
+
+		if self.with_txn {
+			let mut txh_g = self.txn_holder.lock().await;
+			if let Some(txh) = txh_g.as_mut() {
+				self.set_txn_rls_session(&mut txh.txn).await?;
+				let res = query.execute(&mut *txh.txn).await?;
+				return Ok(res.rows_affected());
+			}
+		}
+
+		if let Some(mut txn) = self.begin_rls_txn(db_pool).await? {
+			let res = query.execute(&mut *txn).await?;
+			txn.commit().await?;
+			return Ok(res.rows_affected());
+		}
+
+		let res = query.execute(db_pool).await?;
+		Ok(res.rows_affected())
+	}
+}
+
+// endregion: --- Query Execs
+
+// region:    --- Replicas
+
+impl Dbx {
+	/// Returns the pool of the fetch queries out of a transaction
+	/// (i.e., a replica one for a "read replica" `Dbx` of a request not written yet,
+	/// the primary one otherwise).
+	fn read_pool(&self) -> &Db {
+		let Some(written) = &self.written else {
+			return &self.db_pool;
+		};
+		if !self.read_replica
+			|| self.replica_pools.is_empty()
+			|| written.load(Ordering::Relaxed)
+		{
+			return &self.db_pool;
+		}
+
+		let idx = self.replica_next.fetch_add(1, Ordering::Relaxed);
+		&self.replica_pools[idx % self.replica_pools.len()]
+	}
+
+	/// Records a write of the request (if any),
+	/// so its next reads run on the primary.
+	fn set_written(&self) {
+		if let Some(written) = &self.written {
+			written.store(true, Ordering::Relaxed);
+		}
+	}
+}
+
+// endregion: --- Replicas
+
+// region:    --- Rls
+
+impl Dbx {
+	/// Sets the rls session of this `Dbx` on the open `txn`
+	/// (reset when none, since the txn can be shared with other `Dbx`s).
+	async fn set_txn_rls_session(
+		&self,
+		txn: &mut Transaction<'static, Postgres>,
+	) -> sqlx::Result<()> {
+		if self.with_rls {
+			rls::set_local(txn, self.rls_session.as_ref()).await?;
+		}
+
+		Ok(())
+	}
+
+	/// Begins the transaction of a query out of an open one, with the rls session
+	/// of this `Dbx`, if any (i.e., `None` when the query can run on the pool).
+	async fn begin_rls_txn(
+		&self,
+		db_pool: &Db,
+	) -> sqlx::Result<Option<Transaction<'static, Postgres>>> {
+		let Some(rls_session) = &self.rls_session else {
+			return Ok(None);
+		};
+
+		let mut txn = db_pool.begin().await?;
+		rls::set_local(&mut txn, Some(rls_session)).await?;
+
+		Ok(Some(txn))
+	}
+}
+
+// endregion: --- Rls
@@ -1,10 +1,20 @@
 use serde::Serialize;
+use serde_with::{serde_as, DisplayFromStr};
 
 pub type Result<T> = core::result::Result<T, Error>;
 
+#[serde_as]
 #[derive(Debug, Serialize)]
 pub enum Error {
 	FailToCreatePool(String),
+
+	// -- Txn
+	TxnCantBeginWithoutTxnMode,
+	TxnCantCommitNoOpenTxn,
+	TxnCantRollbackNoOpenTxn,
+	TxnBegin(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
+	TxnCommit(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
+	TxnRollback(#[serde_as(as = "DisplayFromStr")] sqlx::Error),
 }
 
 // region:    --- Error Boilerplate
@@ -1,7 +1,10 @@
 // region:    --- Modules
 
+mod dbx;
 mod error;
+mod rls;
 
+pub use self::dbx::Dbx;
 pub use self::error::{Error, Result};
 
 use crate::core_config;
@@ -13,12 +16,26 @@ use sqlx::{Pool, Postgres};
 pub type Db = Pool<Postgres>;
 
 pub async fn new_db_pool() -> Result<Db> {
+	new_db_pool_for_url(&core_config().DB_URL).await
+}
+
+/// Returns the read replica db pools (none when no replica is configured).
+pub async fn new_replica_db_pools() -> Result<Vec<Db>> {
+	let mut db_pools = Vec::new();
+	for db_url in &core_config().DB_REPLICA_URLS {
+		db_pools.push(new_db_pool_for_url(db_url).await?);
+	}
+
+	Ok(db_pools)
+}
+
+pub(in crate::model) async fn new_db_pool_for_url(db_url: &str) -> Result<Db> {
 	// * See NOTE 1) below
 	let max_connections = if cfg!(test) { 1 } else { 5 };
 
 	PgPoolOptions::new()
 		.max_connections(max_connections)
-		.connect(&core_config().DB_URL)
+		.connect(db_url)
 		.await
 		.map_err(|ex| Error::FailToCreatePool(ex.to_string()))
 }
@@ -0,0 +1,75 @@
+//! The Postgres row-level security (RLS) session of the db executor
+//! (see `sql/migrations/0003_row_level_security.sql` for the policies).
+//!
+//! - When enabled (`SERVICE_DB_RLS`), the queries run for a ctx
+//!   (see `ModelManager::dbx_for`) run in a transaction in which
+//!   the current role and the `app.*` settings are set locally from this ctx.
+//! - The user ctxs run with the `app_rls` role (subject to the policies),
+//!   with their `app.user_id` and `app.workspace_id`.
+//! - The root ctx runs with the `app_rls_bypass` role (`BYPASSRLS`).
+//! - The other queries (e.g., the model layer access checks) run with
+//!   the connection role (i.e., the tables owner, not subject to the policies).
+
+use crate::ctx::Ctx;
+use sqlx::PgConnection;
+
+const ROLE_RLS: &str = "app_rls";
+const ROLE_RLS_BYPASS: &str = "app_rls_bypass";
+/// The `role` setting value resetting the role to the connection one.
+const ROLE_NONE: &str = "none";
+
+#[derive(Debug, Clone)]
+pub(super) enum RlsSession {
+	/// The root ctx, bypassing the policies.
+	Bypass,
+	User {
+		user_id: i64,
+		workspace_id: Option<i64>,
+	},
+}
+
+impl RlsSession {
+	pub(super) fn from_ctx(ctx: &Ctx) -> Self {
+		if ctx.is_root() {
+			RlsSession::Bypass
+		} else {
+			RlsSession::User {
+				user_id: ctx.user_id(),
+				workspace_id: ctx.workspace_id(),
+			}
+		}
+	}
+}
+
+/// Sets the role and the `app.*` settings of the `session` for the current
+/// transaction of the `conn` (or resets them when `None`).
+pub(super) async fn set_local(
+	conn: &mut PgConnection,
+	session: Option<&RlsSession>,
+) -> sqlx::Result<()> {
+	let (role, user_id, workspace_id) = match session {
+		Some(RlsSession::Bypass) => (ROLE_RLS_BYPASS, String::new(), String::new()),
+		Some(RlsSession::User {
+			user_id,
+			workspace_id,
+		}) => (
+			ROLE_RLS,
+			user_id.to_string(),
+			workspace_id.map(|id| id.to_string()).unwrap_or_default(),
+		),
+		None => (ROLE_NONE, String::new(), String::new()),
+	};
+
+	sqlx::query(
+		"SELECT set_config('role', $1, true),
+		        set_config('app.user_id', $2, true),
+		        set_config('app.workspace_id', $3, true)",
+	)
+	.bind(role)
+	.bind(user_id)
+	.bind(workspace_id)
+	.execute(conn)
+	.await?;
+
+	Ok(())
+}
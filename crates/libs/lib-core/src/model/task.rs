@@ -1,27 +1,109 @@
 use crate::ctx::Ctx;
-use crate::model::base::{self, DbBmc};
-use crate::model::modql_utils::time_to_sea_value;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc, TimestampIden};
+use crate::model::label::{
+	labels_all_to_sea_condition, labels_any_to_sea_condition,
+};
+use crate::model::modql_utils::{
+	deserialize_nullable_id, deserialize_nullable_time, int64_to_sea_value,
+	time_to_sea_value, NullableId, NullableTime,
+};
+use crate::model::outbox::{self, DomainEvent};
+use crate::model::project::ProjectBmc;
+use crate::model::project_member::ProjectRole;
+use crate::model::recurrence::Recurrence;
+use crate::model::task_dependency::{
+	TaskDependencyBmc, TaskDependencyForCreate, TaskDependencyIden,
+};
+use crate::model::ManyTarget;
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
+use crate::model::{ListPage, ListWithMeta};
 use lib_utils::time::Rfc3339;
-use modql::field::Fields;
+use modql::field::{FieldValue, Fields};
 use modql::filter::{
-	FilterNodes, ListOptions, OpValsBool, OpValsInt64, OpValsString, OpValsValue,
+	FilterNodes, ListOptions, OpValsBool, OpValsFloat64, OpValsInt64, OpValsString,
+	OpValsValue,
+};
+use sea_query::{
+	Alias, Condition, Expr, Iden, JoinType, PostgresQueryBuilder, Query,
+	SelectStatement,
 };
+use sea_query_binder::SqlxBinder;
 use serde::{Deserialize, Serialize};
 use serde_with::serde_as;
 use sqlx::types::time::OffsetDateTime;
 use sqlx::FromRow;
+use std::collections::HashMap;
 
 // region:    --- Task Types
+
+/// The workflow status of a task.
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	FieldValue,
+	sqlx::Type,
+	Serialize,
+	Deserialize,
+)]
+#[sqlx(type_name = "varchar")]
+pub enum TaskStatus {
+	#[default]
+	Todo,
+	InProgress,
+	Blocked,
+	Done,
+}
+
+#[derive(
+	Debug,
+	Clone,
+	Copy,
+	Default,
+	PartialEq,
+	Eq,
+	FieldValue,
+	sqlx::Type,
+	Serialize,
+	Deserialize,
+)]
+#[sqlx(type_name = "varchar")]
+pub enum TaskPriority {
+	Low,
+	#[default]
+	Medium,
+	High,
+	Urgent,
+}
+
 #[serde_as]
 #[derive(Debug, Clone, Fields, FromRow, Serialize)]
 pub struct Task {
 	pub id: i64,
 	pub project_id: i64,
+	/// The parent task (of the same project), or `None` for a root task.
+	pub parent_id: Option<i64>,
 
 	pub title: String,
+	pub description: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	pub due_date: Option<OffsetDateTime>,
+	pub priority: TaskPriority,
+	pub status: TaskStatus,
+	/// Derived from the `status` (i.e., `status == Done`),
+	/// kept for the clients predating the `status`.
 	pub done: bool,
+	/// The position of the task in its project (see `TaskBmc::move_task`).
+	pub rank: f64,
+	/// The RRULE-style recurrence (see `model::recurrence`),
+	/// requiring a `due_date`.
+	pub recurrence: Option<String>,
+	/// The next occurrence, generated when this recurring task was done.
+	pub next_occurrence_id: Option<i64>,
 
 	// -- Timestamps
 	//    (creator and last modified user_id/time)
@@ -31,26 +113,220 @@ pub struct Task {
 	pub mid: i64,
 	#[serde_as(as = "Rfc3339")]
 	pub mtime: OffsetDateTime,
+
+	// -- Soft delete
+	//    (deleter user_id/time, only when in the trash)
+	pub did: Option<i64>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	pub dtime: Option<OffsetDateTime>,
 }
 
-#[derive(Fields, Deserialize)]
+#[serde_as]
+#[derive(Fields, Deserialize, Default)]
 pub struct TaskForCreate {
 	pub title: String,
 	pub project_id: i64,
+	pub parent_id: Option<i64>,
+
+	pub description: Option<String>,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[serde(default)]
+	pub due_date: Option<OffsetDateTime>,
+	pub priority: Option<TaskPriority>,
+	pub status: Option<TaskStatus>,
+	/// The RRULE-style recurrence (e.g., `FREQ=WEEKLY;BYDAY=MO`),
+	/// requiring a `due_date`.
+	pub recurrence: Option<String>,
+}
+
+/// The `TaskForCreate` with its computed `rank` (i.e., last of its project).
+#[derive(Fields)]
+struct TaskForInsert {
+	title: String,
+	project_id: i64,
+	parent_id: Option<i64>,
+	description: Option<String>,
+	due_date: Option<OffsetDateTime>,
+	priority: Option<TaskPriority>,
+	status: Option<TaskStatus>,
+	recurrence: Option<String>,
+	rank: f64,
+}
+
+impl TaskForInsert {
+	fn new(task_c: TaskForCreate, rank: f64) -> Self {
+		let TaskForCreate {
+			title,
+			project_id,
+			parent_id,
+			description,
+			due_date,
+			priority,
+			status,
+			recurrence,
+		} = task_c;
+
+		TaskForInsert {
+			title,
+			project_id,
+			parent_id,
+			description,
+			due_date,
+			priority,
+			status,
+			recurrence,
+			rank,
+		}
+	}
 }
 
 #[derive(Fields, Deserialize, Default)]
 pub struct TaskForUpdate {
 	pub title: Option<String>,
+	/// The new parent task (of the same project, and not a subtask of this one),
+	/// or `NullableId(None)` to make it a root task (i.e., `"parent_id": null` in json).
+	#[serde(default, deserialize_with = "deserialize_nullable_id")]
+	pub parent_id: Option<NullableId>,
+	pub description: Option<String>,
+	/// The new due date, or `NullableTime(None)` to clear it
+	/// (i.e., `"due_date": null` in json).
+	#[serde(default, deserialize_with = "deserialize_nullable_time")]
+	pub due_date: Option<NullableTime>,
+	pub priority: Option<TaskPriority>,
+	pub status: Option<TaskStatus>,
+	/// The new recurrence, or `""` to stop it.
+	pub recurrence: Option<String>,
+
+	/// Backward compatible `status` update, when no `status` is given
+	/// (i.e., `true` for `Done`, and `false` for `Todo`).
+	#[field(skip)]
 	pub done: Option<bool>,
 }
 
+impl TaskForUpdate {
+	/// Returns this update with the `done` compat field as its `status`.
+	fn with_done_as_status(mut self) -> Self {
+		if self.status.is_none() {
+			self.status = self.done.map(|done| match done {
+				true => TaskStatus::Done,
+				false => TaskStatus::Todo,
+			});
+		}
+		self
+	}
+
+	/// Returns true if this update may make the task recurrence invalid
+	/// (i.e., sets its recurrence, or clears its due date).
+	fn changes_recurrence(&self) -> bool {
+		self.recurrence.is_some() || self.due_date == Some(NullableTime(None))
+	}
+
+	/// Returns the recurrence and due date of the `task` after this update.
+	fn recurrence_after<'a>(
+		&'a self,
+		task: &'a Task,
+	) -> (Option<&'a str>, Option<OffsetDateTime>) {
+		let recurrence = self.recurrence.as_deref().or(task.recurrence.as_deref());
+		let due_date = match self.due_date {
+			Some(NullableTime(due_date)) => due_date,
+			None => task.due_date,
+		};
+		(recurrence, due_date)
+	}
+}
+
+/// The position to move a task to, relative to a sibling task
+/// (i.e., a task of the same project).
+///
+/// In json (e.g., rpc params), `{"before": 123}` or `{"after": 123}`.
+#[derive(Debug, Clone, Copy, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaskPosition {
+	Before(i64),
+	After(i64),
+}
+
+/// A task with its subtasks (see `TaskBmc::get_tree`).
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskTree {
+	#[serde(flatten)]
+	pub task: Task,
+	/// The done roll-up, i.e., `true` only when the task
+	/// and all its subtasks (recursively) are done.
+	///
+	/// Note: A task cannot be done with open subtasks, but a subtask can be
+	///       reopened, or created, under a done task.
+	pub done_rollup: bool,
+	pub children: Vec<TaskTree>,
+}
+
+impl TaskTree {
+	/// Returns the tree of the `task`, taking its subtasks
+	/// from the `subtasks_by_parent` (parent id to its ordered subtasks).
+	fn new(task: Task, subtasks_by_parent: &mut HashMap<i64, Vec<Task>>) -> Self {
+		let children: Vec<TaskTree> = subtasks_by_parent
+			.remove(&task.id)
+			.unwrap_or_default()
+			.into_iter()
+			.map(|subtask| TaskTree::new(subtask, subtasks_by_parent))
+			.collect();
+		let done_rollup = task.done && children.iter().all(|c| c.done_rollup);
+
+		TaskTree {
+			task,
+			done_rollup,
+			children,
+		}
+	}
+}
+
+#[derive(Fields)]
+struct TaskForRank {
+	rank: f64,
+}
+
+#[derive(Fields)]
+struct TaskForNextOccurrence {
+	next_occurrence_id: i64,
+}
+
+/// An upcoming occurrence of a recurring task (see `TaskBmc::list_occurrences`).
+#[serde_as]
+#[derive(Debug, Clone, Serialize)]
+pub struct TaskOccurrence {
+	#[serde_as(as = "Rfc3339")]
+	pub due_date: OffsetDateTime,
+}
+
+/// The task list filter.
+///
+/// Note: The subtasks of a task are `{"parent_id": 123}`,
+///       and the root tasks only are `{"parent_id": {"$null": true}}`.
+///
+/// Note: The tasks with a label are `{"labels_any": "bug"}`, and with any/all
+///       of the labels `{"labels_any": {"$eq": ["bug", "ui"]}}`/`labels_all`
+///       (the label names, of any project).
 #[derive(FilterNodes, Deserialize, Default, Debug)]
 pub struct TaskFilter {
 	id: Option<OpValsInt64>,
 	project_id: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "int64_to_sea_value")]
+	parent_id: Option<OpValsValue>,
 	title: Option<OpValsString>,
+	description: Option<OpValsString>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	due_date: Option<OpValsValue>,
+	priority: Option<OpValsString>,
+	status: Option<OpValsString>,
 	done: Option<OpValsBool>,
+	rank: Option<OpValsFloat64>,
+	recurrence: Option<OpValsString>,
+	#[modql(to_sea_value_fn = "int64_to_sea_value")]
+	next_occurrence_id: Option<OpValsValue>,
+	#[modql(to_sea_condition_fn = "labels_any_to_sea_condition")]
+	labels_any: Option<OpValsValue>,
+	#[modql(to_sea_condition_fn = "labels_all_to_sea_condition")]
+	labels_all: Option<OpValsValue>,
 
 	cid: Option<OpValsInt64>,
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
@@ -59,6 +335,13 @@ pub struct TaskFilter {
 	#[modql(to_sea_value_fn = "time_to_sea_value")]
 	mtime: Option<OpValsValue>,
 }
+
+#[derive(Iden)]
+enum TaskIden {
+	ProjectId,
+	Status,
+	Rank,
+}
 // endregion: --- Task Types
 
 // region:    --- TaskBmc
@@ -66,6 +349,23 @@ pub struct TaskBmc;
 
 impl DbBmc for TaskBmc {
 	const TABLE: &'static str = "task";
+	const SOFT_DELETE: bool = true;
+
+	/// Tasks are readable by all the project members,
+	/// and written by the project editors and owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => ProjectRole::Viewer,
+			AccessKind::Write => ProjectRole::Editor,
+		};
+
+		Some(
+			Condition::all().add(
+				Expr::col(TaskIden::ProjectId)
+					.in_subquery(ProjectBmc::accessible_ids_query(ctx, min_role)),
+			),
+		)
+	}
 }
 
 impl TaskBmc {
@@ -74,127 +374,2511 @@ impl TaskBmc {
 		mm: &ModelManager,
 		task_c: TaskForCreate,
 	) -> Result<i64> {
-		base::create::<Self, _>(ctx, mm, task_c).await
+		// -- Check the project access
+		ProjectBmc::check_role(ctx, mm, task_c.project_id, ProjectRole::Editor)
+			.await?;
+		if let Some(parent_id) = task_c.parent_id {
+			Self::check_parent(ctx, mm, task_c.project_id, parent_id, &[]).await?;
+		}
+		check_recurrence(task_c.recurrence.as_deref(), task_c.due_date)?;
+
+		let rank = Self::max_rank(mm, task_c.project_id).await?.unwrap_or(0.) + 1.;
+		base::create::<Self, _>(ctx, mm, TaskForInsert::new(task_c, rank)).await
+	}
+
+	/// Create the tasks, returning their ids in the `tasks_c` order.
+	pub async fn create_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		tasks_c: Vec<TaskForCreate>,
+	) -> Result<Vec<i64>> {
+		// -- Check the projects access
+		let mut project_ids: Vec<i64> =
+			tasks_c.iter().map(|task_c| task_c.project_id).collect();
+		project_ids.sort_unstable();
+		project_ids.dedup();
+		let mut last_rank_by_project: HashMap<i64, f64> = HashMap::new();
+		for project_id in project_ids {
+			ProjectBmc::check_role(ctx, mm, project_id, ProjectRole::Editor).await?;
+			let max_rank = Self::max_rank(mm, project_id).await?.unwrap_or(0.);
+			last_rank_by_project.insert(project_id, max_rank);
+		}
+		for task_c in tasks_c.iter() {
+			if let Some(parent_id) = task_c.parent_id {
+				Self::check_parent(ctx, mm, task_c.project_id, parent_id, &[])
+					.await?;
+			}
+			check_recurrence(task_c.recurrence.as_deref(), task_c.due_date)?;
+		}
+
+		// -- Append the tasks to their project, in the `tasks_c` order
+		let tasks_i: Vec<TaskForInsert> = tasks_c
+			.into_iter()
+			.map(|task_c| {
+				let last_rank =
+					last_rank_by_project.entry(task_c.project_id).or_default();
+				*last_rank += 1.;
+				TaskForInsert::new(task_c, *last_rank)
+			})
+			.collect();
+
+		base::create_many::<Self, _>(ctx, mm, tasks_i).await
 	}
 
 	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
 		base::get::<Self, _>(ctx, mm, id).await
 	}
 
+	pub async fn get_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ids: &[i64],
+	) -> Result<Vec<Task>> {
+		base::get_many::<Self, _>(ctx, mm, ids).await
+	}
+
+	/// Get the task with its subtasks (recursively), in their `rank` order.
+	pub async fn get_tree(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<TaskTree> {
+		let task = Self::get(ctx, mm, id).await?;
+
+		let dbx = mm.dbx_for(ctx);
+		let sql = r#"
+			WITH RECURSIVE subtask AS (
+				SELECT id FROM task WHERE parent_id = $1 AND did IS NULL
+				UNION
+				SELECT task.id FROM task JOIN subtask ON task.parent_id = subtask.id
+				WHERE task.did IS NULL
+			)
+			SELECT id FROM subtask"#;
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(sql).bind(id);
+		let subtask_ids: Vec<i64> = dbx
+			.fetch_all(sqlx_query)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		// -- Build the tree from the readable subtasks
+		let mut subtasks = Self::get_many(ctx, mm, &subtask_ids).await?;
+		subtasks.sort_by(|a, b| a.rank.total_cmp(&b.rank).then(a.id.cmp(&b.id)));
+		let mut subtasks_by_parent: HashMap<i64, Vec<Task>> = HashMap::new();
+		for subtask in subtasks {
+			if let Some(parent_id) = subtask.parent_id {
+				subtasks_by_parent
+					.entry(parent_id)
+					.or_default()
+					.push(subtask);
+			}
+		}
+
+		Ok(TaskTree::new(task, &mut subtasks_by_parent))
+	}
+
+	/// List the tasks, by default in their `rank` order.
 	pub async fn list(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		filter: Option<Vec<TaskFilter>>,
 		list_options: Option<ListOptions>,
 	) -> Result<Vec<Task>> {
+		let list_options = with_rank_order(list_options);
 		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
 	}
 
+	/// List a page of the tasks after the `cursor` (keyset pagination).
+	pub async fn list_page(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+		cursor: Option<String>,
+	) -> Result<ListPage<Task>> {
+		let list_options = with_rank_order(list_options);
+		base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor).await
+	}
+
+	/// List the tasks with their total count and the effective limit/offset.
+	pub async fn list_with_meta(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<ListWithMeta<Task>> {
+		let list_options = with_rank_order(list_options);
+		base::list_with_meta::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Count the tasks matching the filter.
+	pub async fn count(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+	) -> Result<i64> {
+		base::count::<Self, _>(ctx, mm, filter).await
+	}
+
 	pub async fn update(
 		ctx: &Ctx,
 		mm: &ModelManager,
 		id: i64,
 		task_u: TaskForUpdate,
 	) -> Result<()> {
-		base::update::<Self, _>(ctx, mm, id, task_u).await
+		Self::update_versioned(ctx, mm, id, task_u, None).await
+	}
+
+	/// Update the task, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		task_u: TaskForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		mm.in_txn(|mm| async move {
+			let mm = &mm;
+			let task_u = task_u.with_done_as_status();
+			if task_u.parent_id.is_some() || task_u.changes_recurrence() {
+				let task = Self::get(ctx, mm, id).await?;
+				if let Some(NullableId(Some(parent_id))) = task_u.parent_id {
+					Self::check_parent(ctx, mm, task.project_id, parent_id, &[id])
+						.await?;
+				}
+				if task_u.changes_recurrence() {
+					let (recurrence, due_date) = task_u.recurrence_after(&task);
+					check_recurrence(recurrence, due_date)?;
+				}
+			}
+			let is_done = task_u.status == Some(TaskStatus::Done);
+			let mut completed_events = Vec::new();
+			if is_done {
+				Self::check_not_blocked(mm, &[id]).await?;
+				Self::check_no_open_subtask(mm, &[id]).await?;
+				completed_events = Self::completed_events(ctx, mm, &[id]).await?;
+			}
+
+			base::update_versioned::<Self, _>(ctx, mm, id, task_u, expected_mtime)
+				.await?;
+
+			if is_done {
+				outbox::record(ctx, mm, completed_events).await?;
+				Self::create_next_occurrence(ctx, mm, id).await?;
+			}
+
+			Ok(())
+		})
+		.await
+	}
+
+	/// Update the `target` tasks with the same `task_u`, returning their ids.
+	pub async fn update_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: ManyTarget<Vec<TaskFilter>>,
+		task_u: TaskForUpdate,
+	) -> Result<Vec<i64>> {
+		mm.in_txn(|mm| async move {
+			let mm = &mm;
+			let task_u = task_u.with_done_as_status();
+
+			// -- Check the new parent and status against each target task
+			let is_done = task_u.status == Some(TaskStatus::Done);
+			let mut completed_events = Vec::new();
+			let target = if task_u.parent_id.is_some()
+				|| task_u.changes_recurrence()
+				|| is_done
+			{
+				let ids =
+					base::target_ids_for_write::<Self, _>(ctx, mm, target).await?;
+				if task_u.parent_id.is_some() || task_u.changes_recurrence() {
+					for task in Self::get_many(ctx, mm, &ids).await? {
+						if let Some(NullableId(Some(parent_id))) = task_u.parent_id {
+							Self::check_parent(
+								ctx,
+								mm,
+								task.project_id,
+								parent_id,
+								&ids,
+							)
+							.await?;
+						}
+						if task_u.changes_recurrence() {
+							let (recurrence, due_date) =
+								task_u.recurrence_after(&task);
+							check_recurrence(recurrence, due_date)?;
+						}
+					}
+				}
+				if is_done {
+					Self::check_not_blocked(mm, &ids).await?;
+					Self::check_no_open_subtask(mm, &ids).await?;
+					completed_events = Self::completed_events(ctx, mm, &ids).await?;
+				}
+				ManyTarget::Ids(ids)
+			} else {
+				target
+			};
+
+			let ids =
+				base::update_many::<Self, _, _>(ctx, mm, target, task_u).await?;
+
+			if is_done {
+				outbox::record(ctx, mm, completed_events).await?;
+				for id in ids.iter() {
+					Self::create_next_occurrence(ctx, mm, *id).await?;
+				}
+			}
+
+			Ok(ids)
+		})
+		.await
+	}
+
+	/// Move the task before or after a sibling task, in the `rank` order.
+	///
+	/// The new rank is between the sibling and its neighbor, so only the moved
+	/// task is updated (unless the ranks are too close, see `rebalance_ranks`).
+	pub async fn move_task(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		position: TaskPosition,
+	) -> Result<()> {
+		let sibling_id = match position {
+			TaskPosition::Before(sibling_id) | TaskPosition::After(sibling_id) => {
+				sibling_id
+			}
+		};
+
+		let task = Self::get(ctx, mm, id).await?;
+		ProjectBmc::check_role(ctx, mm, task.project_id, ProjectRole::Editor)
+			.await?;
+
+		mm.in_txn(|mm| async move {
+			let mm = &mm;
+			Self::lock_ranks(mm, task.project_id).await?;
+
+			// Note: Read after the lock, so with the ranks of the previous moves.
+			let task = Self::get(ctx, mm, id).await?;
+			let sibling = Self::get(ctx, mm, sibling_id).await?;
+			if sibling.id == task.id || sibling.project_id != task.project_id {
+				return Err(Error::TaskMoveSiblingInvalid { id, sibling_id });
+			}
+
+			let rank = match Self::rank_at(mm, &task, &sibling, position).await? {
+				Some(rank) => rank,
+				None => {
+					Self::rebalance_ranks(ctx, mm, task.project_id).await?;
+					let sibling = Self::get(ctx, mm, sibling_id).await?;
+					// Note: After the rebalance, the ranks are 1 apart.
+					Self::rank_at(mm, &task, &sibling, position)
+						.await?
+						.ok_or(Error::TaskMoveSiblingInvalid { id, sibling_id })?
+				}
+			};
+
+			base::update::<Self, _>(ctx, mm, id, TaskForRank { rank }).await
+		})
+		.await
+	}
+
+	/// Add the `blocker_id` task as a blocker of the `task_id` task
+	/// (no-op if it already is).
+	pub async fn add_blocker(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		blocker_id: i64,
+	) -> Result<()> {
+		let task = Self::get(ctx, mm, task_id).await?;
+		ProjectBmc::check_role(ctx, mm, task.project_id, ProjectRole::Editor)
+			.await?;
+		Self::get(ctx, mm, blocker_id).await?;
+
+		mm.in_txn(|mm| async move {
+			Self::lock_dependencies(&mm).await?;
+
+			// -- Check the cycle (i.e., the task is already a blocker of the blocker)
+			if task_id == blocker_id
+				|| Self::blocker_ids(&mm, blocker_id).await?.contains(&task_id)
+			{
+				return Err(Error::TaskDependencyCycle {
+					task_id,
+					blocker_id,
+				});
+			}
+
+			if TaskDependencyBmc::find_id(&mm, task_id, blocker_id)
+				.await?
+				.is_none()
+			{
+				let dependency_c = TaskDependencyForCreate {
+					task_id,
+					blocker_id,
+				};
+				base::create::<TaskDependencyBmc, _>(ctx, &mm, dependency_c).await?;
+			}
+
+			Ok(())
+		})
+		.await
+	}
+
+	/// Remove the `blocker_id` task from the blockers of the `task_id` task
+	/// (no-op if it is not).
+	pub async fn remove_blocker(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_id: i64,
+		blocker_id: i64,
+	) -> Result<()> {
+		if let Some(id) = TaskDependencyBmc::find_id(mm, task_id, blocker_id).await?
+		{
+			base::delete::<TaskDependencyBmc>(ctx, mm, id).await?;
+		}
+
+		Ok(())
+	}
+
+	/// List the not done tasks having at least one open (i.e., not done) blocker.
+	pub async fn list_blocked(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Task>> {
+		let cond = Condition::all()
+			.add(Expr::col(TaskIden::Status).ne(TaskStatus::Done))
+			.add(Expr::col(CommonIden::Id).in_subquery(Self::blocked_ids_query()));
+		let list_options = with_rank_order(list_options);
+
+		base::list_with_cond::<Self, _, _>(ctx, mm, filter, cond, list_options).await
+	}
+
+	/// List the not done tasks without open blockers (i.e., ready to be worked on).
+	pub async fn list_ready(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Task>> {
+		let cond = Condition::all()
+			.add(Expr::col(TaskIden::Status).ne(TaskStatus::Done))
+			.add(
+				Expr::col(CommonIden::Id).not_in_subquery(Self::blocked_ids_query()),
+			);
+		let list_options = with_rank_order(list_options);
+
+		base::list_with_cond::<Self, _, _>(ctx, mm, filter, cond, list_options).await
+	}
+
+	/// Preview the (at most `limit`) upcoming occurrences of the recurring task
+	/// (none if it is not recurring).
+	pub async fn list_occurrences(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		limit: i64,
+	) -> Result<Vec<TaskOccurrence>> {
+		if limit > OCCURRENCES_LIMIT_MAX {
+			return Err(Error::ListLimitOverMax {
+				max: OCCURRENCES_LIMIT_MAX,
+				actual: limit,
+			});
+		}
+
+		let task = Self::get(ctx, mm, id).await?;
+		let (Some(recurrence), Some(due_date)) =
+			(task_recurrence(&task)?, task.due_date)
+		else {
+			return Ok(Vec::new());
+		};
+
+		let occurrences = recurrence
+			.upcoming(due_date, limit.max(0) as usize)
+			.into_iter()
+			.map(|due_date| TaskOccurrence { due_date })
+			.collect();
+
+		Ok(occurrences)
 	}
 
+	/// Move the task to the trash.
 	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
 		base::delete::<Self>(ctx, mm, id).await
 	}
+
+	/// Move the `target` tasks to the trash, returning their ids.
+	pub async fn delete_many(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		target: ManyTarget<Vec<TaskFilter>>,
+	) -> Result<Vec<i64>> {
+		base::delete_many::<Self, _>(ctx, mm, target).await
+	}
+
+	pub async fn get_trashed(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Task> {
+		base::get_trashed::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn get_many_trashed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ids: &[i64],
+	) -> Result<Vec<Task>> {
+		base::get_many_trashed::<Self, _>(ctx, mm, ids).await
+	}
+
+	pub async fn list_trashed(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Task>> {
+		base::list_trashed::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	pub async fn restore(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::restore::<Self>(ctx, mm, id).await
+	}
+
+	/// Permanently delete the task from the trash.
+	pub async fn purge(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::purge::<Self>(ctx, mm, id).await
+	}
 }
 // endregion: --- TaskBmc
 
-// region:    --- Tests
-#[cfg(test)]
-mod tests {
-	use super::*;
-	use crate::_dev_utils;
-	use crate::model::project::ProjectBmc;
-	use crate::model::Error;
-	use anyhow::Result;
-	use lib_utils::time::{format_time, now_utc};
-	use modql::filter::OpValString;
-	use serde_json::json;
-	use serial_test::serial;
-	use std::time::Duration;
-	use tokio::time::sleep;
+// region:    --- Hierarchy Utils
+
+impl TaskBmc {
+	/// Checks that the `parent_id` task can be the parent of the `child_ids` tasks
+	/// of the `project_id` (or of a new one, when empty), i.e., that it is
+	/// readable, of the same project, and not one of them or of their subtasks.
+	async fn check_parent(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		project_id: i64,
+		parent_id: i64,
+		child_ids: &[i64],
+	) -> Result<()> {
+		let parent = Self::get(ctx, mm, parent_id).await?;
+		if parent.project_id != project_id {
+			return Err(Error::TaskParentNotInProject {
+				parent_id,
+				project_id,
+			});
+		}
+
+		if !child_ids.is_empty() {
+			let ancestor_ids = Self::ancestor_ids(mm, parent_id).await?;
+			if let Some(&id) = child_ids.iter().find(|id| ancestor_ids.contains(id))
+			{
+				return Err(Error::TaskParentCycle { id, parent_id });
+			}
+		}
+
+		Ok(())
+	}
+
+	/// Returns the ids of the task and its ancestors (parent, grandparent, ...).
+	async fn ancestor_ids(mm: &ModelManager, id: i64) -> Result<Vec<i64>> {
+		let dbx = mm.dbx();
+
+		// Note: `UNION` (vs. `UNION ALL`) stops on an (unexpected) existing cycle.
+		let sql = r#"
+			WITH RECURSIVE ancestor AS (
+				SELECT id, parent_id FROM task WHERE id = $1
+				UNION
+				SELECT task.id, task.parent_id
+				FROM task JOIN ancestor ON task.id = ancestor.parent_id
+			)
+			SELECT id FROM ancestor"#;
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(sql).bind(id);
+		let ids = dbx
+			.fetch_all(sqlx_query)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		Ok(ids)
+	}
+
+	/// Checks that none of the `ids` tasks has an open (i.e., not done, and not
+	/// in the trash) subtask, recursively, other than the `ids` tasks themselves.
+	///
+	/// Note: A subtask can still be reopened, or created, under a done task
+	///       (see `TaskTree::done_rollup`).
+	async fn check_no_open_subtask(mm: &ModelManager, ids: &[i64]) -> Result<()> {
+		let dbx = mm.dbx();
+
+		let sql = r#"
+			WITH RECURSIVE subtask AS (
+				SELECT id, parent_id AS root_id FROM task
+				WHERE parent_id = ANY($1) AND did IS NULL
+				UNION
+				SELECT task.id, subtask.root_id
+				FROM task JOIN subtask ON task.parent_id = subtask.id
+				WHERE task.did IS NULL
+			)
+			SELECT subtask.root_id, task.id
+			FROM subtask JOIN task ON task.id = subtask.id
+			WHERE task.status <> $2 AND NOT task.id = ANY($1)
+			LIMIT 1"#;
+		let sqlx_query = sqlx::query_as::<_, (i64, i64)>(sql)
+			.bind(ids)
+			.bind(TaskStatus::Done);
+		match dbx.fetch_optional(sqlx_query).await? {
+			Some((id, subtask_id)) => Err(Error::TaskOpenSubtask { id, subtask_id }),
+			None => Ok(()),
+		}
+	}
+}
+
+// endregion: --- Hierarchy Utils
+
+// region:    --- Dependency Utils
+
+/// The advisory lock key of the task dependency adds (arbitrary, but app wide).
+const TASK_DEPENDENCY_LOCK_KEY: i64 = 0x0074_6173_6b64_6570;
+
+impl TaskBmc {
+	/// Locks the task dependency adds until the end of the `mm` transaction,
+	/// so that concurrent adds cannot create a cycle (e.g., `A -> B` and `B -> A`,
+	/// each checked without the other).
+	///
+	/// Note: App wide (vs. per project), as the dependencies can cross projects.
+	async fn lock_dependencies(mm: &ModelManager) -> Result<()> {
+		let dbx = mm.dbx();
+
+		let sqlx_query = sqlx::query("SELECT pg_advisory_xact_lock($1)")
+			.bind(TASK_DEPENDENCY_LOCK_KEY);
+		dbx.execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Returns the ids of the task blockers, recursively
+	/// (i.e., the blockers, the blockers of the blockers, ...).
+	async fn blocker_ids(mm: &ModelManager, id: i64) -> Result<Vec<i64>> {
+		let dbx = mm.dbx();
+
+		let sql = r#"
+			WITH RECURSIVE blocker AS (
+				SELECT blocker_id FROM task_dependency WHERE task_id = $1
+				UNION
+				SELECT task_dependency.blocker_id
+				FROM task_dependency
+				JOIN blocker ON task_dependency.task_id = blocker.blocker_id
+			)
+			SELECT blocker_id FROM blocker"#;
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(sql).bind(id);
+		let ids = dbx
+			.fetch_all(sqlx_query)
+			.await?
+			.into_iter()
+			.map(|(id,)| id)
+			.collect();
+
+		Ok(ids)
+	}
+
+	/// Checks that none of the `ids` tasks has an open blocker.
+	async fn check_not_blocked(mm: &ModelManager, ids: &[i64]) -> Result<()> {
+		let dbx = mm.dbx();
+
+		// -- Build query
+		let mut query = Self::open_dependencies_query();
+		query
+			.column((Alias::new("d"), TaskDependencyIden::TaskId))
+			.column((Alias::new("d"), TaskDependencyIden::BlockerId))
+			.and_where(
+				Expr::col((Alias::new("d"), TaskDependencyIden::TaskId))
+					.is_in(ids.iter().copied()),
+			)
+			.limit(1);
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64, i64), _>(&sql, values);
+		match dbx.fetch_optional(sqlx_query).await? {
+			Some((id, blocker_id)) => Err(Error::TaskBlocked { id, blocker_id }),
+			None => Ok(()),
+		}
+	}
+
+	/// Returns the query of the ids of the tasks having an open blocker.
+	fn blocked_ids_query() -> SelectStatement {
+		let mut query = Self::open_dependencies_query();
+		query.column((Alias::new("d"), TaskDependencyIden::TaskId));
+		query
+	}
+
+	/// Returns the (column less) query of the dependencies `d` with an open
+	/// (i.e., not done, and not in the trash) blocker `b`.
+	fn open_dependencies_query() -> SelectStatement {
+		let (d, b) = (Alias::new("d"), Alias::new("b"));
+
+		let mut query = Query::select();
+		query
+			.from_as(TaskDependencyBmc::table_ref(), d.clone())
+			.join_as(
+				JoinType::InnerJoin,
+				Self::table_ref(),
+				b.clone(),
+				Expr::col((b.clone(), CommonIden::Id))
+					.equals((d, TaskDependencyIden::BlockerId)),
+			)
+			.and_where(Expr::col((b.clone(), TaskIden::Status)).ne(TaskStatus::Done))
+			.and_where(Expr::col((b, TimestampIden::Did)).is_null());
+
+		query
+	}
+}
+
+// endregion: --- Dependency Utils
+
+// region:    --- Outbox Utils
+
+impl TaskBmc {
+	/// Returns the `TaskCompleted` events of the `ids` tasks not done yet
+	/// (i.e., before their update to done).
+	async fn completed_events(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		ids: &[i64],
+	) -> Result<Vec<DomainEvent>> {
+		let events = Self::get_many(ctx, mm, ids)
+			.await?
+			.into_iter()
+			.filter(|task| task.status != TaskStatus::Done)
+			.map(|task| DomainEvent::TaskCompleted {
+				task_id: task.id,
+				project_id: task.project_id,
+			})
+			.collect();
+
+		Ok(events)
+	}
+}
+
+// endregion: --- Outbox Utils
+
+// region:    --- Recurrence Utils
+
+/// The max `TaskBmc::list_occurrences` limit.
+const OCCURRENCES_LIMIT_MAX: i64 = 100;
+
+impl TaskBmc {
+	/// Create the next occurrence of the done recurring task (same title,
+	/// description, priority, and parent, with the next due date),
+	/// if not already created, returning its id.
+	///
+	/// Note: The labels, comments, attachments, and dependencies are not carried over.
+	async fn create_next_occurrence(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<Option<i64>> {
+		let task = Self::get(ctx, mm, id).await?;
+		if task.status != TaskStatus::Done || task.next_occurrence_id.is_some() {
+			return Ok(None);
+		}
+		let (Some(recurrence), Some(due_date)) =
+			(task_recurrence(&task)?, task.due_date)
+		else {
+			return Ok(None);
+		};
+		let Some((next_due_date, next_recurrence)) =
+			recurrence.next_occurrence(due_date)
+		else {
+			return Ok(None);
+		};
+
+		let next_task_c = TaskForCreate {
+			title: task.title,
+			project_id: task.project_id,
+			parent_id: task.parent_id,
+			description: task.description,
+			due_date: Some(next_due_date),
+			priority: Some(task.priority),
+			status: None,
+			recurrence: Some(next_recurrence.to_string()),
+		};
+		let next_id = Self::create(ctx, mm, next_task_c).await?;
+		let task_u = TaskForNextOccurrence {
+			next_occurrence_id: next_id,
+		};
+		base::update::<Self, _>(ctx, mm, id, task_u).await?;
+
+		Ok(Some(next_id))
+	}
+}
+
+/// Returns the task recurrence, if any (i.e., `None` when empty).
+fn task_recurrence(task: &Task) -> Result<Option<Recurrence>> {
+	match task.recurrence.as_deref() {
+		Some(rule) if !rule.trim().is_empty() => Ok(Some(rule.parse()?)),
+		_ => Ok(None),
+	}
+}
+
+/// Check the recurrence rule (if any, and not empty) and its required due date.
+fn check_recurrence(
+	recurrence: Option<&str>,
+	due_date: Option<OffsetDateTime>,
+) -> Result<()> {
+	let Some(rule) = recurrence.filter(|rule| !rule.trim().is_empty()) else {
+		return Ok(());
+	};
+
+	rule.parse::<Recurrence>()?;
+	if due_date.is_none() {
+		return Err(Error::TaskRecurrenceNoDueDate);
+	}
+
+	Ok(())
+}
+
+// endregion: --- Recurrence Utils
+
+// region:    --- Rank Utils
+
+impl TaskBmc {
+	/// Returns the max rank of the project tasks (including the trashed ones),
+	/// or `None` if there are none.
+	async fn max_rank(mm: &ModelManager, project_id: i64) -> Result<Option<f64>> {
+		let dbx = mm.dbx();
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.expr(Expr::col(TaskIden::Rank).max())
+			.and_where(Expr::col(TaskIden::ProjectId).eq(project_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (Option<f64>,), _>(&sql, values);
+		let (max_rank,) = dbx.fetch_one(sqlx_query).await?;
+
+		Ok(max_rank)
+	}
+
+	/// Locks the rank changes of the project tasks (i.e., the moves),
+	/// until the end of the transaction.
+	///
+	/// Note: A `NO KEY UPDATE` lock of the project row, so the task inserts
+	///       (i.e., their `project_id` key share lock) are not blocked.
+	async fn lock_ranks(mm: &ModelManager, project_id: i64) -> Result<()> {
+		let dbx = mm.dbx();
+
+		let sqlx_query =
+			sqlx::query("SELECT id FROM project WHERE id = $1 FOR NO KEY UPDATE")
+				.bind(project_id);
+		dbx.execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Returns the rank for the `task` at the `position` of the `sibling`,
+	/// or `None` if there is no room between the sibling and its active neighbor.
+	async fn rank_at(
+		mm: &ModelManager,
+		task: &Task,
+		sibling: &Task,
+		position: TaskPosition,
+	) -> Result<Option<f64>> {
+		let dbx = mm.dbx();
+
+		// -- Build query (the neighbor rank, on the position side of the sibling)
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.and_where(Expr::col(TaskIden::ProjectId).eq(task.project_id))
+			.and_where(Expr::col(CommonIden::Id).ne(task.id))
+			.and_where(Expr::col(TimestampIden::Did).is_null());
+		match position {
+			TaskPosition::Before(_) => query
+				.expr(Expr::col(TaskIden::Rank).max())
+				.and_where(Expr::col(TaskIden::Rank).lt(sibling.rank)),
+			TaskPosition::After(_) => query
+				.expr(Expr::col(TaskIden::Rank).min())
+				.and_where(Expr::col(TaskIden::Rank).gt(sibling.rank)),
+		};
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (Option<f64>,), _>(&sql, values);
+		let (neighbor_rank,) = dbx.fetch_one(sqlx_query).await?;
+
+		// -- Compute the rank between the sibling and its neighbor
+		let rank = match (position, neighbor_rank) {
+			(_, Some(neighbor_rank)) => (sibling.rank + neighbor_rank) / 2.,
+			(TaskPosition::Before(_), None) => sibling.rank - 1.,
+			(TaskPosition::After(_), None) => sibling.rank + 1.,
+		};
+		let has_room = rank != sibling.rank && Some(rank) != neighbor_rank;
+
+		Ok(has_room.then_some(rank))
+	}
+
+	/// Renumber the ranks of the project active tasks from 1, in their current order
+	/// (recorded in their history, as any update).
+	///
+	/// Note: Only needed when the ranks between two tasks are too close
+	///       to insert another one (i.e., after many moves at the same position).
+	async fn rebalance_ranks(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		project_id: i64,
+	) -> Result<()> {
+		let dbx = mm.dbx();
+
+		let sqlx_query = sqlx::query_as::<_, (i64, f64)>(
+			"SELECT id, rank FROM task
+			 WHERE project_id = $1 AND did IS NULL ORDER BY rank, id",
+		)
+		.bind(project_id);
+		// (only the tasks with a new rank)
+		let ranks_u = dbx
+			.fetch_all(sqlx_query)
+			.await?
+			.into_iter()
+			.enumerate()
+			.filter_map(|(idx, (id, rank))| {
+				let new_rank = (idx + 1) as f64;
+				(new_rank != rank).then_some((id, TaskForRank { rank: new_rank }))
+			})
+			.collect();
+		base::update_each::<Self, _>(ctx, mm, ranks_u).await?;
+
+		Ok(())
+	}
+}
+
+/// Returns the list options, with the `rank` order by default.
+fn with_rank_order(list_options: Option<ListOptions>) -> Option<ListOptions> {
+	let mut list_options = list_options.unwrap_or_default();
+	if list_options.order_bys.is_none() {
+		list_options.order_bys = Some(vec!["rank", "id"].into());
+	}
+
+	Some(list_options)
+}
+
+// endregion: --- Rank Utils
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::entity_history::{EntityHistoryBmc, EntityHistoryFilter};
+	use crate::model::project::ProjectBmc;
+	use crate::model::project_member::{ProjectMemberBmc, ProjectMemberForCreate};
+	use crate::model::Error;
+	use anyhow::Result;
+	use lib_utils::time::{format_time, now_utc};
+	use modql::filter::OpValString;
+	use serde_json::json;
+	use serial_test::serial;
+	use std::time::Duration;
+	use time::macros::datetime;
+	use tokio::time::sleep;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_create_ok title";
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_create_ok project for task ")
+				.await?;
+
+		// -- Exec
+		let task_c = TaskForCreate {
+			project_id: fx_project_id,
+			title: fx_title.to_string(),
+			..Default::default()
+		};
+		let id = TaskBmc::create(&ctx, &mm, task_c).await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+		assert_eq!(task.title, fx_title);
+
+		// -- Clean
+		TaskBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_err_not_found() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_id = 100;
+
+		// -- Exec
+		let res = TaskBmc::get(&ctx, &mm, fx_id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::EntityNotFound {
+					entity: "task",
+					id: 100
+				})
+			),
+			"EntityNotFound not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_all_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &["test_list_all_ok-task 01", "test_list_all_ok-task 02"];
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_list_all_ok project for task")
+				.await?;
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		assert_eq!(tasks.len(), 2, "number of seeded tasks.");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_by_title_contains_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_by_title_contains_ok 01",
+			"test_list_by_title_contains_ok 02.1",
+			"test_list_by_title_contains_ok 02.2",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_by_title_contains_ok project for task ",
+		)
+		.await?;
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			title: Some(
+				OpValString::Contains("by_title_contains_ok 02".to_string()).into(),
+			),
+			..Default::default()
+		};
+		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		assert_eq!(tasks.len(), 2);
+
+		// -- Cleanup
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_with_list_options_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_with_list_options_ok 01",
+			"test_list_with_list_options_ok 02.1",
+			"test_list_with_list_options_ok 02.2",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_with_list_options_ok project for task ",
+		)
+		.await?;
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let filter: TaskFilter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let list_options: ListOptions = serde_json::from_value(json! ({
+			"offset": 0,
+			"limit": 2,
+			"order_bys": "!title"
+		}))?;
+		let tasks =
+			TaskBmc::list(&ctx, &mm, Some(vec![filter]), Some(list_options)).await?;
+
+		// -- Check
+		let titles: Vec<String> =
+			tasks.iter().map(|t| t.title.to_string()).collect();
+		assert_eq!(titles.len(), 2);
+		assert_eq!(
+			&titles,
+			&[
+				"test_list_with_list_options_ok 02.2",
+				"test_list_with_list_options_ok 02.1"
+			]
+		);
+
+		// -- Cleanup
+		// Will trash the project (and hide its tasks)
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_move_task_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_move_task_ok 01",
+			"test_move_task_ok 02",
+			"test_move_task_ok 03",
+			"test_move_task_ok 04",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_move_task_ok project for task",
+		)
+		.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		let list_titles = || async {
+			let filter = TaskFilter {
+				project_id: Some(fx_project_id.into()),
+				..Default::default()
+			};
+			let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+			Ok::<_, Error>(tasks.into_iter().map(|t| t.title).collect::<Vec<_>>())
+		};
+
+		// -- Exec
+		// 04 before 02, then 01 after 03 (i.e., last)
+		TaskBmc::move_task(
+			&ctx,
+			&mm,
+			fx_tasks[3].id,
+			TaskPosition::Before(fx_tasks[1].id),
+		)
+		.await?;
+		let titles_before = list_titles().await?;
+		TaskBmc::move_task(
+			&ctx,
+			&mm,
+			fx_tasks[0].id,
+			TaskPosition::After(fx_tasks[2].id),
+		)
+		.await?;
+		let titles_after = list_titles().await?;
+
+		// -- Check
+		assert_eq!(
+			titles_before,
+			&[fx_titles[0], fx_titles[3], fx_titles[1], fx_titles[2]]
+		);
+		assert_eq!(
+			titles_after,
+			&[fx_titles[3], fx_titles[1], fx_titles[2], fx_titles[0]]
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_move_task_rebalance_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_move_task_rebalance_ok 01",
+			"test_move_task_rebalance_ok 02",
+			"test_move_task_rebalance_ok 03",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_move_task_rebalance_ok project for task",
+		)
+		.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		// Each move halves the rank gap after 01, until there is no room left.
+		for _ in 0..40 {
+			for fx_task in [&fx_tasks[2], &fx_tasks[1]] {
+				TaskBmc::move_task(
+					&ctx,
+					&mm,
+					fx_task.id,
+					TaskPosition::After(fx_tasks[0].id),
+				)
+				.await?;
+			}
+		}
+
+		// -- Check
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let titles: Vec<String> = tasks.iter().map(|t| t.title.clone()).collect();
+		assert_eq!(titles, fx_titles);
+		assert!(
+			tasks.windows(2).all(|w| w[0].rank < w[1].rank),
+			"ranks should be distinct"
+		);
+		// (the 80 moves, and the rebalances)
+		let filter: EntityHistoryFilter = serde_json::from_value(json!({
+			"entity": "task",
+			"entity_id": {"$in": [fx_tasks[1].id, fx_tasks[2].id]},
+			"action": "Update",
+		}))?;
+		let history =
+			EntityHistoryBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert!(
+			history.len() > 80,
+			"rebalance should be recorded in the history"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_move_task_err_viewer() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_viewer = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_move_task_err_viewer project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_move_task_err_viewer 01",
+				"test_move_task_err_viewer 02",
+			],
+		)
+		.await?;
+		ProjectMemberBmc::create(
+			&ctx,
+			&mm,
+			ProjectMemberForCreate {
+				project_id: fx_project_id,
+				user_id: ctx_viewer.user_id(),
+				role: ProjectRole::Viewer,
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let res = TaskBmc::move_task(
+			&ctx_viewer,
+			&mm,
+			fx_tasks[1].id,
+			TaskPosition::Before(fx_tasks[0].id),
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::AccessDenied { .. })),
+			"viewer should not move a task, but was {res:?}"
+		);
+		let task = TaskBmc::get(&ctx, &mm, fx_tasks[1].id).await?;
+		assert_eq!(task.rank, fx_tasks[1].rank);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ProjectBmc::purge(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_move_task_err_sibling_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_move_task_err_sibling_invalid project 01",
+		)
+		.await?;
+		let fx_project_id_other = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_move_task_err_sibling_invalid project 02",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_move_task_err_sibling_invalid 01"],
+		)
+		.await?
+		.remove(0);
+		let fx_task_other = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id_other,
+			&["test_move_task_err_sibling_invalid 02"],
+		)
+		.await?
+		.remove(0);
+
+		// -- Exec
+		let res = TaskBmc::move_task(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskPosition::Before(fx_task_other.id),
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::TaskMoveSiblingInvalid { id, sibling_id })
+					if id == fx_task.id && sibling_id == fx_task_other.id
+			),
+			"should be TaskMoveSiblingInvalid, but was {res:?}"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id_other).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_tree_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_get_tree_ok project for task")
+				.await?;
+		let fx_root_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_get_tree_ok root"],
+		)
+		.await?
+		.remove(0)
+		.id;
+		let create_subtask = |title: &'static str, parent_id: i64| {
+			let (ctx, mm) = (&ctx, &mm);
+			async move {
+				let task_c = TaskForCreate {
+					project_id: fx_project_id,
+					parent_id: Some(parent_id),
+					title: title.to_string(),
+					status: Some(TaskStatus::Done),
+					..Default::default()
+				};
+				TaskBmc::create(ctx, mm, task_c).await
+			}
+		};
+		let fx_child_01_id =
+			create_subtask("test_get_tree_ok 01", fx_root_id).await?;
+		let fx_child_02_id =
+			create_subtask("test_get_tree_ok 02", fx_root_id).await?;
+		let fx_grandchild_id =
+			create_subtask("test_get_tree_ok 01.01", fx_child_01_id).await?;
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_root_id,
+			TaskForUpdate {
+				status: Some(TaskStatus::Done),
+				..Default::default()
+			},
+		)
+		.await?;
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_grandchild_id,
+			TaskForUpdate {
+				status: Some(TaskStatus::InProgress),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let tree = TaskBmc::get_tree(&ctx, &mm, fx_root_id).await?;
+		let filter: TaskFilter = serde_json::from_value(json!({
+			"project_id": fx_project_id,
+			"parent_id": {"$null": true},
+		}))?;
+		let roots = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		let child_ids: Vec<i64> = tree.children.iter().map(|c| c.task.id).collect();
+		assert_eq!(child_ids, &[fx_child_01_id, fx_child_02_id]);
+		assert_eq!(tree.children[0].children[0].task.id, fx_grandchild_id);
+		assert!(tree.children[0].children[0].children.is_empty());
+		// The root and child 01 are done, but not their (grand)child.
+		assert!(tree.task.done && !tree.done_rollup);
+		assert!(tree.children[0].task.done && !tree.children[0].done_rollup);
+		assert!(tree.children[1].done_rollup);
+		let root_ids: Vec<i64> = roots.iter().map(|t| t.id).collect();
+		assert_eq!(root_ids, &[fx_root_id]);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_parent_err_cycle() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_parent_err_cycle project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_update_parent_err_cycle 01",
+				"test_update_parent_err_cycle 02",
+			],
+		)
+		.await?;
+		let (fx_parent_id, fx_child_id) = (fx_tasks[0].id, fx_tasks[1].id);
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_child_id,
+			TaskForUpdate {
+				parent_id: Some(fx_parent_id.into()),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let res = TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_parent_id,
+			TaskForUpdate {
+				parent_id: Some(fx_child_id.into()),
+				..Default::default()
+			},
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::TaskParentCycle { id, parent_id })
+					if id == fx_parent_id && parent_id == fx_child_id
+			),
+			"should be TaskParentCycle, but was {res:?}"
+		);
+		let parent = TaskBmc::get(&ctx, &mm, fx_parent_id).await?;
+		assert_eq!(parent.parent_id, None);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_parent_clear_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_parent_clear_ok project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_update_parent_clear_ok 01",
+				"test_update_parent_clear_ok 02",
+			],
+		)
+		.await?;
+		let (fx_parent_id, fx_child_id) = (fx_tasks[0].id, fx_tasks[1].id);
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_child_id,
+			TaskForUpdate {
+				parent_id: Some(fx_parent_id.into()),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let task_u_title: TaskForUpdate = serde_json::from_value(json!({
+			"title": "test_update_parent_clear_ok 02 renamed",
+		}))?;
+		TaskBmc::update(&ctx, &mm, fx_child_id, task_u_title).await?;
+		let child_after_title = TaskBmc::get(&ctx, &mm, fx_child_id).await?;
+		let task_u_clear: TaskForUpdate =
+			serde_json::from_value(json!({ "parent_id": null }))?;
+		TaskBmc::update(&ctx, &mm, fx_child_id, task_u_clear).await?;
+
+		// -- Check
+		assert_eq!(
+			child_after_title.parent_id,
+			Some(fx_parent_id),
+			"parent_id should be unchanged when absent"
+		);
+		let child = TaskBmc::get(&ctx, &mm, fx_child_id).await?;
+		assert_eq!(child.parent_id, None, "parent_id should be cleared");
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_blocked_and_ready_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_blocked_and_ready_ok 01",
+			"test_list_blocked_and_ready_ok 02",
+			"test_list_blocked_and_ready_ok 03",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_blocked_and_ready_ok project for task",
+		)
+		.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		// 01 is blocked by 02, which is blocked by 03
+		TaskBmc::add_blocker(&ctx, &mm, fx_tasks[0].id, fx_tasks[1].id).await?;
+		TaskBmc::add_blocker(&ctx, &mm, fx_tasks[1].id, fx_tasks[2].id).await?;
+		let new_filter = || {
+			Some(vec![TaskFilter {
+				project_id: Some(fx_project_id.into()),
+				..Default::default()
+			}])
+		};
+		let list_titles = |tasks: Vec<Task>| -> Vec<String> {
+			tasks.into_iter().map(|t| t.title).collect()
+		};
+
+		// -- Exec
+		let blocked = TaskBmc::list_blocked(&ctx, &mm, new_filter(), None).await?;
+		let ready = TaskBmc::list_ready(&ctx, &mm, new_filter(), None).await?;
+		// 03 done unblocks 02
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_tasks[2].id,
+			TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
+		let blocked_after =
+			TaskBmc::list_blocked(&ctx, &mm, new_filter(), None).await?;
+		let ready_after = TaskBmc::list_ready(&ctx, &mm, new_filter(), None).await?;
+
+		// -- Check
+		assert_eq!(list_titles(blocked), &fx_titles[0..2]);
+		assert_eq!(list_titles(ready), &fx_titles[2..3]);
+		assert_eq!(list_titles(blocked_after), &fx_titles[0..1]);
+		assert_eq!(list_titles(ready_after), &fx_titles[1..2]);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_add_blocker_err_cycle() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_add_blocker_err_cycle project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_add_blocker_err_cycle 01",
+				"test_add_blocker_err_cycle 02",
+				"test_add_blocker_err_cycle 03",
+			],
+		)
+		.await?;
+		TaskBmc::add_blocker(&ctx, &mm, fx_tasks[0].id, fx_tasks[1].id).await?;
+		TaskBmc::add_blocker(&ctx, &mm, fx_tasks[1].id, fx_tasks[2].id).await?;
+
+		// -- Exec
+		let res =
+			TaskBmc::add_blocker(&ctx, &mm, fx_tasks[2].id, fx_tasks[0].id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::TaskDependencyCycle { task_id, blocker_id })
+					if task_id == fx_tasks[2].id && blocker_id == fx_tasks[0].id
+			),
+			"should be TaskDependencyCycle, but was {res:?}"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_add_blocker_concurrent_err_cycle() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		// (another db pool, for the adds to really run concurrently)
+		let mm_other = ModelManager::new().await?;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_add_blocker_concurrent_err_cycle project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_add_blocker_concurrent_err_cycle 01",
+				"test_add_blocker_concurrent_err_cycle 02",
+			],
+		)
+		.await?;
+		let (fx_a_id, fx_b_id) = (fx_tasks[0].id, fx_tasks[1].id);
+
+		// -- Exec
+		let (res_a, res_b) = tokio::join!(
+			TaskBmc::add_blocker(&ctx, &mm, fx_a_id, fx_b_id),
+			TaskBmc::add_blocker(&ctx, &mm_other, fx_b_id, fx_a_id),
+		);
+
+		// -- Check
+		let cycle_count = [&res_a, &res_b]
+			.iter()
+			.filter(|res| matches!(res, Err(Error::TaskDependencyCycle { .. })))
+			.count();
+		assert!(
+			cycle_count == 1 && (res_a.is_ok() || res_b.is_ok()),
+			"only one add should succeed, but was {res_a:?} and {res_b:?}"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_done_err_open_subtask() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_done_err_open_subtask project for task",
+		)
+		.await?;
+		let fx_root_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_update_done_err_open_subtask root"],
+		)
+		.await?
+		.remove(0)
+		.id;
+		let mut parent_id = fx_root_id;
+		let mut fx_ids = vec![fx_root_id];
+		for title in [
+			"test_update_done_err_open_subtask 01",
+			"test_update_done_err_open_subtask 01.01",
+		] {
+			let task_c = TaskForCreate {
+				project_id: fx_project_id,
+				parent_id: Some(parent_id),
+				title: title.to_string(),
+				..Default::default()
+			};
+			parent_id = TaskBmc::create(&ctx, &mm, task_c).await?;
+			fx_ids.push(parent_id);
+		}
+		let task_done_u = || TaskForUpdate {
+			status: Some(TaskStatus::Done),
+			..Default::default()
+		};
+
+		// -- Exec
+		let res = TaskBmc::update(&ctx, &mm, fx_root_id, task_done_u()).await;
+		let res_many = TaskBmc::update_many(
+			&ctx,
+			&mm,
+			ManyTarget::Ids(fx_ids[..2].to_vec()),
+			task_done_u(),
+		)
+		.await;
+		// (with all its subtasks)
+		TaskBmc::update_many(
+			&ctx,
+			&mm,
+			ManyTarget::Ids(fx_ids.clone()),
+			task_done_u(),
+		)
+		.await?;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::TaskOpenSubtask { id, .. }) if id == fx_root_id
+			),
+			"should be TaskOpenSubtask, but was {res:?}"
+		);
+		assert!(
+			matches!(
+				res_many,
+				Err(Error::TaskOpenSubtask { subtask_id, .. })
+					if subtask_id == fx_ids[2]
+			),
+			"should be TaskOpenSubtask, but was {res_many:?}"
+		);
+		let tasks = TaskBmc::get_many(&ctx, &mm, &fx_ids).await?;
+		assert!(tasks.iter().all(|t| t.status == TaskStatus::Done));
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_done_err_blocked() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_done_err_blocked project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_update_done_err_blocked 01",
+				"test_update_done_err_blocked 02",
+			],
+		)
+		.await?;
+		TaskBmc::add_blocker(&ctx, &mm, fx_tasks[0].id, fx_tasks[1].id).await?;
+
+		// -- Exec
+		let res = TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_tasks[0].id,
+			TaskForUpdate {
+				status: Some(TaskStatus::Done),
+				..Default::default()
+			},
+		)
+		.await;
+		TaskBmc::remove_blocker(&ctx, &mm, fx_tasks[0].id, fx_tasks[1].id).await?;
+		let res_unblocked = TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_tasks[0].id,
+			TaskForUpdate {
+				status: Some(TaskStatus::Done),
+				..Default::default()
+			},
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::TaskBlocked { id, blocker_id })
+					if id == fx_tasks[0].id && blocker_id == fx_tasks[1].id
+			),
+			"should be TaskBlocked, but was {res:?}"
+		);
+		assert!(res_unblocked.is_ok());
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_with_meta_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_with_meta_ok 01",
+			"test_list_with_meta_ok 02",
+			"test_list_with_meta_ok 03",
+			"test_list_with_meta_ok 04",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_with_meta_ok project for task",
+		)
+		.await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		// The deleted (trashed) tasks are not counted.
+		TaskBmc::delete(&ctx, &mm, fx_tasks[3].id).await?;
+
+		// -- Exec
+		let new_filter = || TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let list_options: ListOptions = serde_json::from_value(json!({
+			"limit": 2,
+			"offset": 1,
+			"order_bys": "title",
+		}))?;
+		let list_with_meta = TaskBmc::list_with_meta(
+			&ctx,
+			&mm,
+			Some(vec![new_filter()]),
+			Some(list_options),
+		)
+		.await?;
+		let count = TaskBmc::count(&ctx, &mm, Some(vec![new_filter()])).await?;
+
+		// -- Check
+		let titles: Vec<String> =
+			list_with_meta.items.into_iter().map(|t| t.title).collect();
+		assert_eq!(titles, &fx_titles[1..3]);
+		assert_eq!(list_with_meta.total, 3);
+		assert_eq!(list_with_meta.limit, 2);
+		assert_eq!(list_with_meta.offset, 1);
+		assert_eq!(count, 3);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_page_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_titles = &[
+			"test_list_page_ok 01",
+			"test_list_page_ok 02",
+			"test_list_page_ok 03",
+			"test_list_page_ok 04",
+			"test_list_page_ok 05",
+		];
+		let fx_title_inserted = "test_list_page_ok 05.5";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_page_ok project for task",
+		)
+		.await?;
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let new_filter = || TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let list_options: ListOptions = serde_json::from_value(json!({
+			"limit": 2,
+			"order_bys": "!title",
+		}))?;
+		let mut titles: Vec<String> = Vec::new();
+		let mut cursor: Option<String> = None;
+		loop {
+			let page = TaskBmc::list_page(
+				&ctx,
+				&mm,
+				Some(vec![new_filter()]),
+				Some(list_options.clone()),
+				cursor,
+			)
+			.await?;
+			// Insert a task before the current position after the first page.
+			if titles.is_empty() {
+				_dev_utils::seed_tasks(
+					&ctx,
+					&mm,
+					fx_project_id,
+					&[fx_title_inserted],
+				)
+				.await?;
+			}
+			titles.extend(page.items.into_iter().map(|t| t.title));
+			cursor = page.next_cursor;
+			if cursor.is_none() {
+				break;
+			}
+		}
+
+		// -- Check
+		let mut expected: Vec<&str> = fx_titles.to_vec();
+		expected.reverse();
+		assert_eq!(titles, expected);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_page_err_cursor_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_page_err_cursor_invalid project for task",
+		)
+		.await?;
+		_dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_list_page_err_cursor_invalid 01",
+				"test_list_page_err_cursor_invalid 02",
+			],
+		)
+		.await?;
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let list_options: ListOptions =
+			serde_json::from_value(json!({ "limit": 1, "order_bys": "title" }))?;
+		let fx_cursor = TaskBmc::list_page(
+			&ctx,
+			&mm,
+			Some(vec![filter]),
+			Some(list_options),
+			None,
+		)
+		.await?
+		.next_cursor;
+
+		// -- Exec
+		let list_options: ListOptions =
+			serde_json::from_value(json!({ "limit": 1, "order_bys": "!title" }))?;
+		let res =
+			TaskBmc::list_page(&ctx, &mm, None, Some(list_options), fx_cursor).await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::ListCursorInvalid)),
+			"ListCursorInvalid not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_page_err_cursor_filter_changed() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_list_page_err_cursor_filter_changed project for task",
+		)
+		.await?;
+		_dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&[
+				"test_list_page_err_cursor_filter_changed 01",
+				"test_list_page_err_cursor_filter_changed 02",
+			],
+		)
+		.await?;
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let list_options: ListOptions =
+			serde_json::from_value(json!({ "limit": 1, "order_bys": "title" }))?;
+		let fx_cursor = TaskBmc::list_page(
+			&ctx,
+			&mm,
+			Some(vec![filter]),
+			Some(list_options.clone()),
+			None,
+		)
+		.await?
+		.next_cursor;
+
+		// -- Exec
+		let filter_other = TaskFilter {
+			project_id: Some((fx_project_id + 1).into()),
+			..Default::default()
+		};
+		let res = TaskBmc::list_page(
+			&ctx,
+			&mm,
+			Some(vec![filter_other]),
+			Some(list_options),
+			fx_cursor,
+		)
+		.await;
+
+		// -- Check
+		assert!(
+			matches!(res, Err(Error::ListCursorInvalid)),
+			"ListCursorInvalid not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_ok - task 01";
+		let fx_title_new = "test_update_ok - task 01 - new";
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_update_ok project for task")
+				.await?;
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_new.to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, fx_title_new);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_status_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_status_ok - task 01";
+		let fx_description = "test_update_status_ok - description";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_status_ok project for task",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				description: Some(fx_description.to_string()),
+				priority: Some(TaskPriority::High),
+				status: Some(TaskStatus::InProgress),
+				..Default::default()
+			},
+		)
+		.await?;
+		let task_in_progress = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		// The `done` compat field sets the `status`.
+		TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
+		let task_done = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+
+		// -- Check
+		assert_eq!(fx_task.status, TaskStatus::Todo);
+		assert_eq!(fx_task.priority, TaskPriority::Medium);
+		assert!(!fx_task.done);
+		assert_eq!(
+			task_in_progress.description.as_deref(),
+			Some(fx_description)
+		);
+		assert_eq!(task_in_progress.priority, TaskPriority::High);
+		assert_eq!(task_in_progress.status, TaskStatus::InProgress);
+		assert!(!task_in_progress.done);
+		assert_eq!(task_done.status, TaskStatus::Done);
+		assert!(task_done.done);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_versioned_err_concurrent() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_versioned_err_concurrent - task 01";
+		let fx_title_tab_1 = "test_update_versioned_err_concurrent - tab 1";
+		let fx_title_tab_2 = "test_update_versioned_err_concurrent - tab 2";
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_versioned_err_concurrent project for task",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title])
+			.await?
+			.remove(0);
+
+		// -- Exec
+		// Both tabs update from the same read task version.
+		TaskBmc::update_versioned(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_tab_1.to_string()),
+				..Default::default()
+			},
+			Some(fx_task.mtime),
+		)
+		.await?;
+		let res = TaskBmc::update_versioned(
+			&ctx,
+			&mm,
+			fx_task.id,
+			TaskForUpdate {
+				title: Some(fx_title_tab_2.to_string()),
+				..Default::default()
+			},
+			Some(fx_task.mtime),
+		)
+		.await;
+
+		// -- Check
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert_eq!(task.title, fx_title_tab_1);
+		assert!(
+			matches!(
+				res,
+				Err(Error::ConcurrentModification { entity: "task", id, current_mtime })
+					if id == fx_task.id && current_mtime == task.mtime
+			),
+			"ConcurrentModification not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_by_ctime_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"project for tasks test_list_by_ctime_ok",
+		)
+		.await?;
+		let fx_titles_01 = &[
+			"test_list_by_ctime_ok 01.1",
+			"test_list_by_ctime_ok 01.2",
+			"test_list_by_ctime_ok 01.3",
+		];
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_01).await?;
+
+		let time_marker = format_time(now_utc());
+		sleep(Duration::from_millis(300)).await;
+		let fx_titles_02 =
+			&["test_list_by_ctime_ok 02.1", "test_list_by_ctime_ok 02.2"];
+		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_02).await?;
+
+		// -- Exec
+		let filter_json = json! ({
+			"ctime": {"$gt": time_marker}, // time in Rfc3339
+		});
+		let filter = vec![serde_json::from_value(filter_json)?];
+		let tasks = TaskBmc::list(&ctx, &mm, Some(filter), None).await?;
+
+		// -- Check
+		let titles: Vec<String> = tasks.into_iter().map(|t| t.title).collect();
+		assert_eq!(titles.len(), 2);
+		assert_eq!(&titles, fx_titles_02);
+
+		// -- Cleanup
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_get_err_access_denied() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_get_err_access_denied project for task",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx_owner,
+			&mm,
+			fx_project_id,
+			&["test_get_err_access_denied 01"],
+		)
+		.await?
+		.remove(0);
+
+		// -- Exec
+		let res = TaskBmc::get(&ctx_other, &mm, fx_task.id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::AccessDenied { entity: "task", id }) if id == fx_task.id
+			),
+			"AccessDenied not matching"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_scoped_by_ctx_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_titles = &[
+			"test_list_scoped_by_ctx_ok 01",
+			"test_list_scoped_by_ctx_ok 02",
+		];
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_owner,
+			&mm,
+			"test_list_scoped_by_ctx_ok project for task",
+		)
+		.await?;
+		_dev_utils::seed_tasks(&ctx_owner, &mm, fx_project_id, fx_titles).await?;
+
+		// -- Exec
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let tasks_owner =
+			TaskBmc::list(&ctx_owner, &mm, Some(vec![filter]), None).await?;
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
+		};
+		let tasks_other =
+			TaskBmc::list(&ctx_other, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		assert_eq!(tasks_owner.len(), 2, "number of tasks for owner");
+		assert!(
+			tasks_other.is_empty(),
+			"tasks should not be visible to other"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_create_ok() -> Result<()> {
+	async fn test_delete_restore_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_title = "test_create_ok title";
-		let fx_project_id =
-			_dev_utils::seed_project(&ctx, &mm, "test_create_ok project for task ")
-				.await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_delete_restore_ok project for task",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_delete_restore_ok 01"],
+		)
+		.await?
+		.remove(0);
 
-		// -- Exec
-		let task_c = TaskForCreate {
-			project_id: fx_project_id,
-			title: fx_title.to_string(),
+		// -- Exec & Check - delete
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+		let res = TaskBmc::get(&ctx, &mm, fx_task.id).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+			"EntityNotFound not matching"
+		);
+		let filter = TaskFilter {
+			project_id: Some(fx_project_id.into()),
+			..Default::default()
 		};
-		let id = TaskBmc::create(&ctx, &mm, task_c).await?;
+		let tasks =
+			TaskBmc::list_trashed(&ctx, &mm, Some(vec![filter]), None).await?;
+		assert_eq!(tasks.len(), 1, "number of trashed tasks");
+		assert_eq!(tasks[0].did, Some(ctx.user_id()));
 
-		// -- Check
-		let task = TaskBmc::get(&ctx, &mm, id).await?;
-		assert_eq!(task.title, fx_title);
+		// -- Exec & Check - restore
+		TaskBmc::restore(&ctx, &mm, fx_task.id).await?;
+		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
+		assert!(task.dtime.is_none(), "restored task dtime should be none");
 
 		// -- Clean
-		TaskBmc::delete(&ctx, &mm, id).await?;
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_get_err_not_found() -> Result<()> {
+	async fn test_purge_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_id = 100;
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_purge_ok project for task")
+				.await?;
+		let fx_task =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &["test_purge_ok 01"])
+				.await?
+				.remove(0);
 
 		// -- Exec
-		let res = TaskBmc::get(&ctx, &mm, fx_id).await;
+		// Only trashed tasks can be purged.
+		let res_active = TaskBmc::purge(&ctx, &mm, fx_task.id).await;
+		TaskBmc::delete(&ctx, &mm, fx_task.id).await?;
+		TaskBmc::purge(&ctx, &mm, fx_task.id).await?;
 
 		// -- Check
 		assert!(
 			matches!(
-				res,
-				Err(Error::EntityNotFound {
-					entity: "task",
-					id: 100
-				})
+				res_active,
+				Err(Error::EntityNotFound { entity: "task", .. })
 			),
-			"EntityNotFound not matching"
+			"EntityNotFound not matching for active task"
+		);
+		let res = TaskBmc::get_trashed(&ctx, &mm, fx_task.id).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { entity: "task", .. })),
+			"EntityNotFound not matching for purged task"
 		);
 
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_all_ok() -> Result<()> {
+	async fn test_create_many_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_titles = &["test_list_all_ok-task 01", "test_list_all_ok-task 02"];
+		let fx_titles: Vec<String> = (1..=3)
+			.map(|i| format!("test_create_many_ok 0{i}"))
+			.collect();
 		let fx_project_id =
-			_dev_utils::seed_project(&ctx, &mm, "test_list_all_ok project for task")
+			_dev_utils::seed_project(&ctx, &mm, "test_create_many_ok project")
 				.await?;
-		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
 
 		// -- Exec
-		let filter = TaskFilter {
-			project_id: Some(fx_project_id.into()),
-			..Default::default()
-		};
-		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let tasks_c = fx_titles
+			.iter()
+			.map(|title| TaskForCreate {
+				project_id: fx_project_id,
+				title: title.to_string(),
+				..Default::default()
+			})
+			.collect();
+		let ids = TaskBmc::create_many(&ctx, &mm, tasks_c).await?;
 
 		// -- Check
-		assert_eq!(tasks.len(), 2, "number of seeded tasks.");
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
+		let titles: Vec<String> = tasks.into_iter().map(|t| t.title).collect();
+		assert_eq!(titles, fx_titles);
 
 		// -- Clean
 		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
@@ -204,37 +2888,46 @@ mod tests {
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_by_title_contains_ok() -> Result<()> {
+	async fn test_update_many_by_filter_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
-		let ctx = Ctx::root_ctx();
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
 		let fx_titles = &[
-			"test_list_by_title_contains_ok 01",
-			"test_list_by_title_contains_ok 02.1",
-			"test_list_by_title_contains_ok 02.2",
+			"test_update_many_by_filter_ok 01",
+			"test_update_many_by_filter_ok 02",
+			"test_update_many_by_filter_ok 03",
 		];
 		let fx_project_id = _dev_utils::seed_project(
 			&ctx,
 			&mm,
-			"test_list_by_title_contains_ok project for task ",
+			"test_update_many_by_filter_ok project",
 		)
 		.await?;
-		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		let fx_tasks =
+			_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
 
 		// -- Exec
-		let filter = TaskFilter {
-			project_id: Some(fx_project_id.into()),
-			title: Some(
-				OpValString::Contains("by_title_contains_ok 02".to_string()).into(),
-			),
-			..Default::default()
-		};
-		let tasks = TaskBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+		let filter: TaskFilter = serde_json::from_value(json!({
+			"project_id": fx_project_id,
+			"title": {"$in": [fx_titles[0], fx_titles[2]]},
+		}))?;
+		let ids = TaskBmc::update_many(
+			&ctx,
+			&mm,
+			ManyTarget::Filters(vec![filter]),
+			TaskForUpdate {
+				done: Some(true),
+				..Default::default()
+			},
+		)
+		.await?;
 
 		// -- Check
-		assert_eq!(tasks.len(), 2);
+		assert_eq!(ids, &[fx_tasks[0].id, fx_tasks[2].id]);
+		let tasks = TaskBmc::get_many(&ctx, &mm, &[fx_tasks[1].id]).await?;
+		assert!(!tasks[0].done, "task not in filter should not be updated");
 
-		// -- Cleanup
+		// -- Clean
 		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
 
 		Ok(())
@@ -242,85 +2935,158 @@ mod tests {
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_with_list_options_ok() -> Result<()> {
+	async fn test_delete_many_err_access_denied() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
-		let ctx = Ctx::root_ctx();
-		let fx_titles = &[
-			"test_list_with_list_options_ok 01",
-			"test_list_with_list_options_ok 02.1",
-			"test_list_with_list_options_ok 02.2",
-		];
+		let ctx_owner = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
 		let fx_project_id = _dev_utils::seed_project(
-			&ctx,
+			&ctx_owner,
 			&mm,
-			"test_list_with_list_options_ok project for task ",
+			"test_delete_many_err_access_denied project owner",
 		)
 		.await?;
-		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles).await?;
+		let fx_project_other_id = _dev_utils::seed_project(
+			&ctx_other,
+			&mm,
+			"test_delete_many_err_access_denied project other",
+		)
+		.await?;
+		let fx_task = _dev_utils::seed_tasks(
+			&ctx_owner,
+			&mm,
+			fx_project_id,
+			&["test_delete_many_err_access_denied 01"],
+		)
+		.await?
+		.remove(0);
+		let fx_task_other = _dev_utils::seed_tasks(
+			&ctx_other,
+			&mm,
+			fx_project_other_id,
+			&["test_delete_many_err_access_denied 02"],
+		)
+		.await?
+		.remove(0);
 
 		// -- Exec
-		let filter: TaskFilter = TaskFilter {
-			project_id: Some(fx_project_id.into()),
-			..Default::default()
-		};
-		let list_options: ListOptions = serde_json::from_value(json! ({
-			"offset": 0,
-			"limit": 2,
-			"order_bys": "!title"
-		}))?;
-		let tasks =
-			TaskBmc::list(&ctx, &mm, Some(vec![filter]), Some(list_options)).await?;
+		let res = TaskBmc::delete_many(
+			&ctx_other,
+			&mm,
+			ManyTarget::Ids(vec![fx_task_other.id, fx_task.id]),
+		)
+		.await;
 
 		// -- Check
-		let titles: Vec<String> =
-			tasks.iter().map(|t| t.title.to_string()).collect();
-		assert_eq!(titles.len(), 2);
-		assert_eq!(
-			&titles,
-			&[
-				"test_list_with_list_options_ok 02.2",
-				"test_list_with_list_options_ok 02.1"
-			]
+		assert!(
+			matches!(
+				res,
+				Err(Error::AccessDenied { entity: "task", id }) if id == fx_task.id
+			),
+			"AccessDenied not matching"
 		);
+		TaskBmc::get(&ctx_other, &mm, fx_task_other.id).await?;
 
-		// -- Cleanup
-		// Will delete associated tasks
-		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+		// -- Clean
+		ProjectBmc::delete(&ctx_owner, &mm, fx_project_id).await?;
+		ProjectBmc::delete(&ctx_other, &mm, fx_project_other_id).await?;
 
 		Ok(())
 	}
 
 	#[serial]
 	#[tokio::test]
-	async fn test_update_ok() -> Result<()> {
+	async fn test_delete_err_not_found() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_title = "test_update_ok - task 01";
-		let fx_title_new = "test_update_ok - task 01 - new";
-		let fx_project_id =
-			_dev_utils::seed_project(&ctx, &mm, "test_update_ok project for task")
-				.await?;
-		let fx_task = _dev_utils::seed_tasks(&ctx, &mm, fx_project_id, &[fx_title])
-			.await?
-			.remove(0);
+		let fx_id = 100;
 
 		// -- Exec
-		TaskBmc::update(
+		let res = TaskBmc::delete(&ctx, &mm, fx_id).await;
+
+		// -- Check
+		assert!(
+			matches!(
+				res,
+				Err(Error::EntityNotFound {
+					entity: "task",
+					id: 100
+				})
+			),
+			"EntityNotFound not matching"
+		);
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_done_recurring_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_title = "test_update_done_recurring_ok - weekly chore";
+		let fx_due_date = datetime!(2026-10-19 9:00 UTC);
+		let fx_project_id = _dev_utils::seed_project(
 			&ctx,
 			&mm,
-			fx_task.id,
-			TaskForUpdate {
-				title: Some(fx_title_new.to_string()),
+			"test_update_done_recurring_ok project for task",
+		)
+		.await?;
+		let fx_task_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: fx_title.to_string(),
+				project_id: fx_project_id,
+				due_date: Some(fx_due_date),
+				recurrence: Some("FREQ=WEEKLY;COUNT=2".to_string()),
 				..Default::default()
 			},
 		)
 		.await?;
+		let set_done = |id: i64, done: bool| {
+			let task_u = TaskForUpdate {
+				done: Some(done),
+				..Default::default()
+			};
+			TaskBmc::update(&ctx, &mm, id, task_u)
+		};
+
+		// -- Exec
+		let occurrences =
+			TaskBmc::list_occurrences(&ctx, &mm, fx_task_id, 10).await?;
+		set_done(fx_task_id, true).await?;
+		let task_done = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		// done again (after undone), without a new occurrence
+		set_done(fx_task_id, false).await?;
+		set_done(fx_task_id, true).await?;
+		let next_id = task_done.next_occurrence_id.unwrap_or_default();
+		let next_task = TaskBmc::get(&ctx, &mm, next_id).await?;
+		// the last occurrence (COUNT=1)
+		set_done(next_id, true).await?;
+		let tasks: Vec<Task> = TaskBmc::list(
+			&ctx,
+			&mm,
+			Some(serde_json::from_value(
+				json!([{"project_id": fx_project_id}]),
+			)?),
+			None,
+		)
+		.await?;
 
 		// -- Check
-		let task = TaskBmc::get(&ctx, &mm, fx_task.id).await?;
-		assert_eq!(task.title, fx_title_new);
+		let next_due_date = datetime!(2026-10-26 9:00 UTC);
+		let occurrence_dates: Vec<OffsetDateTime> =
+			occurrences.into_iter().map(|o| o.due_date).collect();
+		assert_eq!(occurrence_dates, &[next_due_date]);
+		assert_eq!(next_task.title, fx_title);
+		assert_eq!(next_task.status, TaskStatus::Todo);
+		assert_eq!(next_task.due_date, Some(next_due_date));
+		assert_eq!(next_task.recurrence.as_deref(), Some("FREQ=WEEKLY;COUNT=1"));
+		assert_eq!(tasks.len(), 2, "should have only the 2 occurrences");
+		assert!(tasks.iter().all(|t| t.done));
 
 		// -- Clean
 		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
@@ -330,42 +3096,37 @@ mod tests {
 
 	#[serial]
 	#[tokio::test]
-	async fn test_list_by_ctime_ok() -> Result<()> {
+	async fn test_create_recurring_err_no_due_date() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
 		let fx_project_id = _dev_utils::seed_project(
 			&ctx,
 			&mm,
-			"project for tasks test_list_by_ctime_ok",
+			"test_create_recurring_err_no_due_date project for task",
 		)
 		.await?;
-		let fx_titles_01 = &[
-			"test_list_by_ctime_ok 01.1",
-			"test_list_by_ctime_ok 01.2",
-			"test_list_by_ctime_ok 01.3",
-		];
-		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_01).await?;
-
-		let time_marker = format_time(now_utc());
-		sleep(Duration::from_millis(300)).await;
-		let fx_titles_02 =
-			&["test_list_by_ctime_ok 02.1", "test_list_by_ctime_ok 02.2"];
-		_dev_utils::seed_tasks(&ctx, &mm, fx_project_id, fx_titles_02).await?;
 
 		// -- Exec
-		let filter_json = json! ({
-			"ctime": {"$gt": time_marker}, // time in Rfc3339
-		});
-		let filter = vec![serde_json::from_value(filter_json)?];
-		let tasks = TaskBmc::list(&ctx, &mm, Some(filter), None).await?;
+		let res = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_create_recurring_err_no_due_date".to_string(),
+				project_id: fx_project_id,
+				recurrence: Some("FREQ=DAILY".to_string()),
+				..Default::default()
+			},
+		)
+		.await;
 
 		// -- Check
-		let titles: Vec<String> = tasks.into_iter().map(|t| t.title).collect();
-		assert_eq!(titles.len(), 2);
-		assert_eq!(&titles, fx_titles_02);
+		assert!(
+			matches!(res, Err(Error::TaskRecurrenceNoDueDate)),
+			"should be TaskRecurrenceNoDueDate, but was {res:?}"
+		);
 
-		// -- Cleanup
+		// -- Clean
 		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
 
 		Ok(())
@@ -373,27 +3134,74 @@ mod tests {
 
 	#[serial]
 	#[tokio::test]
-	async fn test_delete_err_not_found() -> Result<()> {
+	async fn test_update_due_date_clear_ok() -> Result<()> {
 		// -- Setup & Fixtures
 		let mm = _dev_utils::init_test().await;
 		let ctx = Ctx::root_ctx();
-		let fx_id = 100;
+		let fx_due_date = datetime!(2026-10-19 9:00 UTC);
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx,
+			&mm,
+			"test_update_due_date_clear_ok project for task",
+		)
+		.await?;
+		let fx_tasks = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_update_due_date_clear_ok 01"],
+		)
+		.await?;
+		let fx_task_id = fx_tasks[0].id;
+		let fx_recurring_id = TaskBmc::create(
+			&ctx,
+			&mm,
+			TaskForCreate {
+				title: "test_update_due_date_clear_ok recurring".to_string(),
+				project_id: fx_project_id,
+				due_date: Some(fx_due_date),
+				recurrence: Some("FREQ=DAILY".to_string()),
+				..Default::default()
+			},
+		)
+		.await?;
 
 		// -- Exec
-		let res = TaskBmc::delete(&ctx, &mm, fx_id).await;
+		let task_u: TaskForUpdate =
+			serde_json::from_value(json!({"due_date": format_time(fx_due_date)}))?;
+		TaskBmc::update(&ctx, &mm, fx_task_id, task_u).await?;
+		let task_set = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		// (an absent due date leaves it as is)
+		let task_u: TaskForUpdate = serde_json::from_value(json!({"title": "new"}))?;
+		TaskBmc::update(&ctx, &mm, fx_task_id, task_u).await?;
+		let task_kept = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		let task_u: TaskForUpdate =
+			serde_json::from_value(json!({"due_date": null}))?;
+		TaskBmc::update(&ctx, &mm, fx_task_id, task_u).await?;
+		let task_cleared = TaskBmc::get(&ctx, &mm, fx_task_id).await?;
+		let recurring_res = TaskBmc::update(
+			&ctx,
+			&mm,
+			fx_recurring_id,
+			TaskForUpdate {
+				due_date: Some(NullableTime(None)),
+				..Default::default()
+			},
+		)
+		.await;
 
 		// -- Check
+		assert_eq!(task_set.due_date, Some(fx_due_date));
+		assert_eq!(task_kept.due_date, Some(fx_due_date));
+		assert_eq!(task_cleared.due_date, None);
 		assert!(
-			matches!(
-				res,
-				Err(Error::EntityNotFound {
-					entity: "task",
-					id: 100
-				})
-			),
-			"EntityNotFound not matching"
+			matches!(recurring_res, Err(Error::TaskRecurrenceNoDueDate)),
+			"should be TaskRecurrenceNoDueDate, but was {recurring_res:?}"
 		);
 
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
 		Ok(())
 	}
 }
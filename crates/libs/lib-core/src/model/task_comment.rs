@@ -0,0 +1,336 @@
+//! The task comments, i.e., the discussion about a task.
+//!
+//! - The comment author is its creator (i.e., the `cid`, from the ctx).
+//! - The comments are readable, and can be added, by all the task readers,
+//!   but only edited and deleted by their author.
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc, TimestampIden};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use crate::model::{ListPage, ListWithMeta};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Iden, Query};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- TaskComment Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct TaskComment {
+	pub id: i64,
+	pub task_id: i64,
+
+	pub content: String,
+
+	// -- Timestamps
+	//    (author and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct TaskCommentForCreate {
+	pub task_id: i64,
+	pub content: String,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct TaskCommentForUpdate {
+	pub content: String,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct TaskCommentFilter {
+	id: Option<OpValsInt64>,
+	task_id: Option<OpValsInt64>,
+	content: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum TaskCommentIden {
+	TaskId,
+}
+// endregion: --- TaskComment Types
+
+// region:    --- TaskCommentBmc
+pub struct TaskCommentBmc;
+
+impl DbBmc for TaskCommentBmc {
+	const TABLE: &'static str = "task_comment";
+
+	/// Comments are readable by the task readers,
+	/// and written by their author only.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let mut task_ids = Query::select();
+		task_ids.column(CommonIden::Id).from(TaskBmc::table_ref());
+		if let Some(access_cond) =
+			base::ctx_access_cond::<TaskBmc>(ctx, AccessKind::Read)
+		{
+			task_ids.cond_where(access_cond);
+		}
+
+		let mut cond = Condition::all()
+			.add(Expr::col(TaskCommentIden::TaskId).in_subquery(task_ids));
+		if let AccessKind::Write = access {
+			cond = cond.add(Expr::col(TimestampIden::Cid).eq(ctx.user_id()));
+		}
+
+		Some(cond)
+	}
+}
+
+impl TaskCommentBmc {
+	/// Add a comment on a task readable by the ctx user (its author).
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		task_comment_c: TaskCommentForCreate,
+	) -> Result<i64> {
+		// -- Check the task access
+		TaskBmc::get(ctx, mm, task_comment_c.task_id).await?;
+
+		base::create::<Self, _>(ctx, mm, task_comment_c).await
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<TaskComment> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskCommentFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<TaskComment>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// List a page of the task comments after the `cursor` (keyset pagination).
+	pub async fn list_page(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskCommentFilter>>,
+		list_options: Option<ListOptions>,
+		cursor: Option<String>,
+	) -> Result<ListPage<TaskComment>> {
+		base::list_page::<Self, _, _>(ctx, mm, filter, list_options, cursor).await
+	}
+
+	/// List the task comments with their total count and the effective limit/offset.
+	pub async fn list_with_meta(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskCommentFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<ListWithMeta<TaskComment>> {
+		base::list_with_meta::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	/// Count the task comments matching the filter.
+	pub async fn count(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<TaskCommentFilter>>,
+	) -> Result<i64> {
+		base::count::<Self, _>(ctx, mm, filter).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		task_comment_u: TaskCommentForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, task_comment_u).await
+	}
+
+	/// Update the task comment, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		task_comment_u: TaskCommentForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		base::update_versioned::<Self, _>(
+			ctx,
+			mm,
+			id,
+			task_comment_u,
+			expected_mtime,
+		)
+		.await
+	}
+
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		base::delete::<Self>(ctx, mm, id).await
+	}
+}
+// endregion: --- TaskCommentBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::ProjectBmc;
+	use crate::model::project_member::{
+		ProjectMemberBmc, ProjectMemberForCreate, ProjectRole,
+	};
+	use crate::model::Error;
+	use anyhow::Result;
+	use serde_json::json;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_list_page_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let fx_contents = &[
+			"test_list_page_ok comment 01",
+			"test_list_page_ok comment 02",
+			"test_list_page_ok comment 03",
+		];
+		let fx_project_id =
+			_dev_utils::seed_project(&ctx, &mm, "test_list_page_ok project").await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx,
+			&mm,
+			fx_project_id,
+			&["test_list_page_ok task"],
+		)
+		.await?[0]
+			.id;
+		for content in fx_contents {
+			let task_comment_c = TaskCommentForCreate {
+				task_id: fx_task_id,
+				content: content.to_string(),
+			};
+			TaskCommentBmc::create(&ctx, &mm, task_comment_c).await?;
+		}
+		let fx_filter: Vec<TaskCommentFilter> =
+			serde_json::from_value(json!([{"task_id": fx_task_id}]))?;
+		let fx_list_options = serde_json::from_value(json!({"limit": 2}))?;
+
+		// -- Exec
+		let page = TaskCommentBmc::list_page(
+			&ctx,
+			&mm,
+			Some(fx_filter),
+			Some(fx_list_options),
+			None,
+		)
+		.await?;
+
+		// -- Check
+		let contents: Vec<&str> =
+			page.items.iter().map(|c| c.content.as_str()).collect();
+		assert_eq!(contents, &fx_contents[0..2]);
+		assert!(page.next_cursor.is_some(), "should have a next page");
+		assert!(page.items.iter().all(|c| c.cid == ctx.user_id()));
+
+		// -- Clean
+		ProjectBmc::delete(&ctx, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_err_not_author() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx_author = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_author,
+			&mm,
+			"test_update_err_not_author project",
+		)
+		.await?;
+		ProjectMemberBmc::create(
+			&ctx_author,
+			&mm,
+			ProjectMemberForCreate {
+				project_id: fx_project_id,
+				user_id: ctx_other.user_id(),
+				role: ProjectRole::Editor,
+			},
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx_author,
+			&mm,
+			fx_project_id,
+			&["test_update_err_not_author task"],
+		)
+		.await?[0]
+			.id;
+		let fx_comment_id = TaskCommentBmc::create(
+			&ctx_author,
+			&mm,
+			TaskCommentForCreate {
+				task_id: fx_task_id,
+				content: "test_update_err_not_author comment".to_string(),
+			},
+		)
+		.await?;
+
+		// -- Exec
+		let other_comment =
+			TaskCommentBmc::get(&ctx_other, &mm, fx_comment_id).await;
+		let update_res = TaskCommentBmc::update(
+			&ctx_other,
+			&mm,
+			fx_comment_id,
+			TaskCommentForUpdate {
+				content: "edited".to_string(),
+			},
+		)
+		.await;
+		let delete_res =
+			TaskCommentBmc::delete(&ctx_other, &mm, fx_comment_id).await;
+
+		// -- Check
+		assert!(other_comment.is_ok(), "comment should be readable by other");
+		assert!(
+			matches!(update_res, Err(Error::AccessDenied { id, .. }) if id == fx_comment_id),
+			"update should be AccessDenied"
+		);
+		assert!(
+			matches!(delete_res, Err(Error::AccessDenied { id, .. }) if id == fx_comment_id),
+			"delete should be AccessDenied"
+		);
+		TaskCommentBmc::delete(&ctx_author, &mm, fx_comment_id).await?;
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_author, &mm, fx_project_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
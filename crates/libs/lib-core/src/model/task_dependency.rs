@@ -0,0 +1,101 @@
+//! The task dependencies, i.e., the "task A is blocked by task B" edges.
+//!
+//! The edges are added and removed with the `TaskBmc` dependency methods
+//! (e.g., `TaskBmc::add_blocker`), which check the task access and reject the cycles.
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc};
+use crate::model::task::TaskBmc;
+use crate::model::ModelManager;
+use crate::model::Result;
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use sea_query::{Condition, Expr, Iden, PostgresQueryBuilder, Query};
+use sea_query_binder::SqlxBinder;
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- TaskDependency Types
+
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct TaskDependency {
+	pub id: i64,
+	/// The blocked task.
+	pub task_id: i64,
+	/// The task blocking `task_id` until it is done.
+	pub blocker_id: i64,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct TaskDependencyForCreate {
+	pub task_id: i64,
+	pub blocker_id: i64,
+}
+
+#[derive(Iden)]
+pub(in crate::model) enum TaskDependencyIden {
+	TaskId,
+	BlockerId,
+}
+// endregion: --- TaskDependency Types
+
+// region:    --- TaskDependencyBmc
+pub struct TaskDependencyBmc;
+
+impl DbBmc for TaskDependencyBmc {
+	const TABLE: &'static str = "task_dependency";
+
+	/// The dependencies have the access of their (blocked) task.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let mut task_ids = Query::select();
+		task_ids.column(CommonIden::Id).from(TaskBmc::table_ref());
+		if let Some(access_cond) = base::ctx_access_cond::<TaskBmc>(ctx, access) {
+			task_ids.cond_where(access_cond);
+		}
+
+		Some(
+			Condition::all()
+				.add(Expr::col(TaskDependencyIden::TaskId).in_subquery(task_ids)),
+		)
+	}
+}
+
+impl TaskDependencyBmc {
+	/// Returns the id of the `task_id` blocked by `blocker_id` dependency,
+	/// if any (regardless of the ctx access).
+	pub(in crate::model) async fn find_id(
+		mm: &ModelManager,
+		task_id: i64,
+		blocker_id: i64,
+	) -> Result<Option<i64>> {
+		let dbx = mm.dbx();
+
+		// -- Build query
+		let mut query = Query::select();
+		query
+			.from(Self::table_ref())
+			.column(CommonIden::Id)
+			.and_where(Expr::col(TaskDependencyIden::TaskId).eq(task_id))
+			.and_where(Expr::col(TaskDependencyIden::BlockerId).eq(blocker_id));
+
+		// -- Exec query
+		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
+		let sqlx_query = sqlx::query_as_with::<_, (i64,), _>(&sql, values);
+		let id = dbx.fetch_optional(sqlx_query).await?.map(|(id,)| id);
+
+		Ok(id)
+	}
+}
+// endregion: --- TaskDependencyBmc
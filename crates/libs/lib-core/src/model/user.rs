@@ -1,7 +1,7 @@
 use crate::ctx::Ctx;
 use crate::model::base::{self, add_timestamps_for_update, DbBmc};
 use crate::model::ModelManager;
-use crate::model::Result;
+use crate::model::{Error, Result};
 use lib_auth::pwd::{self, ContentToHash};
 use modql::field::{Field, Fields, HasFields};
 use sea_query::{Expr, Iden, PostgresQueryBuilder, Query};
@@ -24,9 +24,12 @@ pub struct UserForCreate {
 	pub pwd_clear: String,
 }
 
+/// The `UserForCreate` with its hashed `pwd` and `pwd_salt`.
 #[derive(Fields)]
-pub struct UserForInsert {
-	pub username: String,
+struct UserForInsert {
+	username: String,
+	pwd: String,
+	pwd_salt: Uuid,
 }
 
 #[derive(Clone, FromRow, Fields, Debug)]
@@ -75,6 +78,38 @@ impl DbBmc for UserBmc {
 }
 
 impl UserBmc {
+	/// Create the user with its password hashed (with a new salt).
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		user_c: UserForCreate,
+	) -> Result<i64> {
+		let UserForCreate {
+			username,
+			pwd_clear,
+		} = user_c;
+		check_username(&username)?;
+		check_pwd_not_empty(&pwd_clear)?;
+
+		// -- Prep password
+		let pwd_salt = Uuid::new_v4();
+		let pwd = pwd::hash_pwd(ContentToHash {
+			content: pwd_clear,
+			salt: pwd_salt,
+		})
+		.await?;
+
+		let user_i = UserForInsert {
+			username: username.clone(),
+			pwd,
+			pwd_salt,
+		};
+
+		base::create::<Self, _>(ctx, mm, user_i)
+			.await
+			.map_err(|err| username_conflict_err(err, username))
+	}
+
 	pub async fn get<E>(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<E>
 	where
 		E: UserBy,
@@ -90,7 +125,7 @@ impl UserBmc {
 	where
 		E: UserBy,
 	{
-		let db = mm.db();
+		let dbx = mm.dbx();
 
 		// -- Build query
 		let mut query = Query::select();
@@ -101,9 +136,8 @@ impl UserBmc {
 
 		// -- Execute query
 		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-		let entity = sqlx::query_as_with::<_, E, _>(&sql, values)
-			.fetch_optional(db)
-			.await?;
+		let sqlx_query = sqlx::query_as_with::<_, E, _>(&sql, values);
+		let entity = dbx.fetch_optional(sqlx_query).await?;
 
 		Ok(entity)
 	}
@@ -114,7 +148,8 @@ impl UserBmc {
 		id: i64,
 		pwd_clear: &str,
 	) -> Result<()> {
-		let db = mm.db();
+		let dbx = mm.dbx_for(ctx);
+		check_pwd_not_empty(pwd_clear)?;
 
 		// -- Prep password
 		let user: UserForLogin = Self::get(ctx, mm, id).await?;
@@ -138,15 +173,72 @@ impl UserBmc {
 
 		// -- Exec query
 		let (sql, values) = query.build_sqlx(PostgresQueryBuilder);
-		let _count = sqlx::query_with(&sql, values)
-			.execute(db)
-			.await?
-			.rows_affected();
+		let sqlx_query = sqlx::query_with(&sql, values);
+		let _count = dbx.execute(sqlx_query).await?;
+
+		Ok(())
+	}
+
+	/// Returns an error if the `pwd_clear` is not the password of the `id` user.
+	pub async fn validate_pwd(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		pwd_clear: &str,
+	) -> Result<()> {
+		let user: UserForLogin = Self::get(ctx, mm, id).await?;
+		let Some(pwd) = user.pwd else {
+			return Err(Error::UserPwdNotMatching { user_id: id });
+		};
+
+		pwd::validate_pwd(
+			ContentToHash {
+				content: pwd_clear.to_string(),
+				salt: user.pwd_salt,
+			},
+			pwd,
+		)
+		.await
+		.map_err(|_| Error::UserPwdNotMatching { user_id: id })?;
 
 		Ok(())
 	}
 }
 
+/// The max username length, in chars (i.e., the `user.username` column one).
+const USERNAME_MAX_LEN: usize = 128;
+
+fn check_username(username: &str) -> Result<()> {
+	if username.trim().is_empty() {
+		Err(Error::UsernameEmpty)
+	} else if username.chars().count() > USERNAME_MAX_LEN {
+		Err(Error::UsernameTooLong {
+			max: USERNAME_MAX_LEN,
+		})
+	} else {
+		Ok(())
+	}
+}
+
+fn check_pwd_not_empty(pwd_clear: &str) -> Result<()> {
+	if pwd_clear.is_empty() {
+		Err(Error::UserPwdEmpty)
+	} else {
+		Ok(())
+	}
+}
+
+fn username_conflict_err(err: Error, username: String) -> Error {
+	match err {
+		Error::Sqlx(sqlx::Error::Database(db_err))
+			if db_err.is_unique_violation() =>
+		{
+			Error::UsernameAlreadyExists { username }
+		}
+		err => err,
+	}
+}
+
 // endregion: --- UserBmc
 
 // region:    --- Tests
@@ -154,7 +246,9 @@ impl UserBmc {
 mod tests {
 	use super::*;
 	use crate::_dev_utils;
+	use crate::model::entity_history::{EntityHistoryBmc, EntityHistoryFilter};
 	use anyhow::{Context, Result};
+	use serde_json::json;
 	use serial_test::serial;
 
 	#[serial]
@@ -175,5 +269,142 @@ mod tests {
 
 		Ok(())
 	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_history_no_pwd_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_username = "test_create_history_no_pwd_ok-user-01";
+
+		// -- Exec
+		let id = UserBmc::create(
+			&ctx,
+			&mm,
+			UserForCreate {
+				username: fx_username.to_string(),
+				pwd_clear: "test_create_history_no_pwd_ok pwd 01".to_string(),
+			},
+		)
+		.await?;
+		UserBmc::update_pwd(&ctx, &mm, id, "test_create_history_no_pwd_ok pwd 02")
+			.await?;
+		let filter: EntityHistoryFilter = serde_json::from_value(json!({
+			"entity": "user",
+			"entity_id": id,
+		}))?;
+		let history =
+			EntityHistoryBmc::list(&ctx, &mm, Some(vec![filter]), None).await?;
+
+		// -- Check
+		assert!(!history.is_empty(), "user create should be recorded");
+		assert_eq!(history[0].diff["username"], json!({ "new": fx_username }));
+		for entry in history.iter() {
+			for column in ["pwd", "pwd_salt", "token_salt"] {
+				assert!(
+					entry.diff.get(column).is_none(),
+					"'{column}' should not be in the history diff {}",
+					entry.diff
+				);
+			}
+		}
+
+		// -- Clean
+		base::delete::<UserBmc>(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let fx_username = "test_create_ok-user-01";
+		let fx_pwd_clear = "test_create_ok pwd 01";
+
+		// -- Exec
+		let id = UserBmc::create(
+			&ctx,
+			&mm,
+			UserForCreate {
+				username: fx_username.to_string(),
+				pwd_clear: fx_pwd_clear.to_string(),
+			},
+		)
+		.await?;
+		let user: User = UserBmc::get(&ctx, &mm, id).await?;
+		let valid_res = UserBmc::validate_pwd(&ctx, &mm, id, fx_pwd_clear).await;
+		let invalid_res = UserBmc::validate_pwd(&ctx, &mm, id, "wrong pwd").await;
+		let dup_res = UserBmc::create(
+			&ctx,
+			&mm,
+			UserForCreate {
+				username: fx_username.to_string(),
+				pwd_clear: "other pwd".to_string(),
+			},
+		)
+		.await;
+
+		// -- Check
+		assert_eq!(user.username, fx_username);
+		assert!(valid_res.is_ok(), "pwd should be valid");
+		assert!(
+			matches!(invalid_res, Err(Error::UserPwdNotMatching { .. })),
+			"wrong pwd should not match"
+		);
+		assert!(
+			matches!(dup_res, Err(Error::UsernameAlreadyExists { .. })),
+			"should be UsernameAlreadyExists, but was {dup_res:?}"
+		);
+
+		// -- Clean
+		base::delete::<UserBmc>(&ctx, &mm, id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_create_err_invalid() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = Ctx::root_ctx();
+		let user_c = |username: &str, pwd_clear: &str| UserForCreate {
+			username: username.to_string(),
+			pwd_clear: pwd_clear.to_string(),
+		};
+
+		// -- Exec
+		let empty_res = UserBmc::create(&ctx, &mm, user_c("", "welcome")).await;
+		let blank_res = UserBmc::create(&ctx, &mm, user_c(" \t ", "welcome")).await;
+		let long_res =
+			UserBmc::create(&ctx, &mm, user_c(&"u".repeat(129), "welcome")).await;
+		let no_pwd_res =
+			UserBmc::create(&ctx, &mm, user_c("test_create_err_invalid-user", ""))
+				.await;
+
+		// -- Check
+		assert!(
+			matches!(empty_res, Err(Error::UsernameEmpty)),
+			"should be UsernameEmpty, but was {empty_res:?}"
+		);
+		assert!(
+			matches!(blank_res, Err(Error::UsernameEmpty)),
+			"should be UsernameEmpty, but was {blank_res:?}"
+		);
+		assert!(
+			matches!(long_res, Err(Error::UsernameTooLong { max: 128 })),
+			"should be UsernameTooLong, but was {long_res:?}"
+		);
+		assert!(
+			matches!(no_pwd_res, Err(Error::UserPwdEmpty)),
+			"should be UserPwdEmpty, but was {no_pwd_res:?}"
+		);
+
+		Ok(())
+	}
 }
 // endregion: --- Tests
@@ -0,0 +1,351 @@
+//! The workspaces (i.e., the organizations), owning the projects.
+//!
+//! - The users belong to one or more workspaces (see `workspace_member`).
+//! - The ctx active workspace (i.e., `Ctx::workspace_id`) scopes the projects,
+//!   and so their entities (e.g., tasks), see `ProjectBmc::access_cond`.
+//! - The projects are created in the ctx active workspace.
+//!
+//! Note: The project owners and members still need to be members of its workspace
+//!       (i.e., to have it as their active workspace) to access it.
+
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, CommonIden, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::workspace_member::{
+	WorkspaceMemberBmc, WorkspaceMemberForCreate, WorkspaceRole,
+};
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::Fields;
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- Workspace Types
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct Workspace {
+	pub id: i64,
+
+	pub name: String,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct WorkspaceForCreate {
+	pub name: String,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct WorkspaceForUpdate {
+	pub name: Option<String>,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct WorkspaceFilter {
+	id: Option<OpValsInt64>,
+	name: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+// endregion: --- Workspace Types
+
+// region:    --- WorkspaceBmc
+pub struct WorkspaceBmc;
+
+impl DbBmc for WorkspaceBmc {
+	const TABLE: &'static str = "workspace";
+
+	/// Workspaces are readable by all their members, but written only by their owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => WorkspaceRole::Member,
+			AccessKind::Write => WorkspaceRole::Owner,
+		};
+
+		Some(
+			Condition::all().add(Expr::col(CommonIden::Id).in_subquery(
+				WorkspaceMemberBmc::workspace_ids_query(ctx, min_role),
+			)),
+		)
+	}
+}
+
+impl WorkspaceBmc {
+	/// Checks that the ctx user has at least the `min_role` in the workspace `id`.
+	/// (i.e., `EntityNotFound` or `AccessDenied` otherwise)
+	pub async fn check_role(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		min_role: WorkspaceRole,
+	) -> Result<()> {
+		let access_cond =
+			(!ctx.is_root()).then(|| {
+				Condition::all().add(Expr::col(CommonIden::Id).in_subquery(
+					WorkspaceMemberBmc::workspace_ids_query(ctx, min_role),
+				))
+			});
+
+		base::check_access_cond::<Self>(mm, id, access_cond).await
+	}
+
+	/// Create the workspace, with the ctx user as its owner.
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		workspace_c: WorkspaceForCreate,
+	) -> Result<i64> {
+		let id = base::create::<Self, _>(ctx, mm, workspace_c).await?;
+
+		let workspace_member_c = WorkspaceMemberForCreate {
+			workspace_id: id,
+			user_id: ctx.user_id(),
+			role: WorkspaceRole::Owner,
+		};
+		base::create::<WorkspaceMemberBmc, _>(ctx, mm, workspace_member_c).await?;
+
+		Ok(id)
+	}
+
+	pub async fn get(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<Workspace> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	/// Returns the id of the ctx user default workspace
+	/// (i.e., its first one), if any.
+	pub async fn default_id(ctx: &Ctx, mm: &ModelManager) -> Result<Option<i64>> {
+		let list_options = ListOptions {
+			limit: Some(1),
+			offset: None,
+			order_bys: Some("id".into()),
+		};
+		let workspaces = Self::list(ctx, mm, None, Some(list_options)).await?;
+
+		Ok(workspaces.first().map(|workspace| workspace.id))
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<WorkspaceFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<Workspace>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		workspace_u: WorkspaceForUpdate,
+	) -> Result<()> {
+		base::update::<Self, _>(ctx, mm, id, workspace_u).await
+	}
+
+	/// Update the workspace, only if it was not modified since the `expected_mtime`
+	/// (when given).
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		workspace_u: WorkspaceForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		base::update_versioned::<Self, _>(ctx, mm, id, workspace_u, expected_mtime)
+			.await
+	}
+
+	/// Delete the workspace (permanently), with its members.
+	///
+	/// Note: Only without projects (in the trash or not), so they are never deleted
+	///       without going through the trash (see `ProjectBmc::purge`).
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		Self::check_role(ctx, mm, id, WorkspaceRole::Owner).await?;
+
+		// -- Check the workspace has no projects
+		let dbx = mm.dbx();
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(
+			"SELECT id FROM project WHERE workspace_id = $1 LIMIT 1",
+		)
+		.bind(id);
+		if dbx.fetch_optional(sqlx_query).await?.is_some() {
+			return Err(Error::WorkspaceNotEmpty { id });
+		}
+
+		base::delete::<Self>(ctx, mm, id).await
+	}
+}
+// endregion: --- WorkspaceBmc
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::project::{ProjectBmc, ProjectForCreate};
+	use crate::model::task::TaskBmc;
+	use crate::model::user::{UserBmc, UserForCreate};
+	use crate::model::Error;
+	use anyhow::Result;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_projects_scoped_to_workspace_ok() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_workspace_id = WorkspaceBmc::create(
+			&ctx,
+			&mm,
+			WorkspaceForCreate {
+				name: "test_projects_scoped_to_workspace_ok workspace".to_string(),
+			},
+		)
+		.await?;
+		let ctx_ws = ctx.add_workspace_id(fx_workspace_id);
+		let fx_user_id = UserBmc::create(
+			&Ctx::root_ctx(),
+			&mm,
+			UserForCreate {
+				username: "test_projects_scoped_to_workspace_ok-user".to_string(),
+				pwd_clear: "welcome".to_string(),
+			},
+		)
+		.await?;
+		WorkspaceMemberBmc::create(
+			&ctx_ws,
+			&mm,
+			WorkspaceMemberForCreate {
+				workspace_id: fx_workspace_id,
+				user_id: ctx_other.user_id(),
+				role: WorkspaceRole::Member,
+			},
+		)
+		.await?;
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_ws,
+			&mm,
+			"test_projects_scoped_to_workspace_ok project",
+		)
+		.await?;
+		let fx_task_id = _dev_utils::seed_tasks(
+			&ctx_ws,
+			&mm,
+			fx_project_id,
+			&["test_projects_scoped_to_workspace_ok task"],
+		)
+		.await?[0]
+			.id;
+
+		// -- Exec
+		let project = ProjectBmc::get(&ctx_ws, &mm, fx_project_id).await?;
+		// (the same user, in its default workspace)
+		let projects = ProjectBmc::list(&ctx, &mm, None, None).await?;
+		let task_res = TaskBmc::get(&ctx, &mm, fx_task_id).await;
+		let other_ws_res = WorkspaceBmc::get(&ctx_other, &mm, fx_workspace_id).await;
+		// (not a member of the workspace)
+		let ctx_not_member = Ctx::new(fx_user_id)?.add_workspace_id(fx_workspace_id);
+		let create_res = ProjectBmc::create(
+			&ctx_not_member,
+			&mm,
+			ProjectForCreate {
+				name: "test_projects_scoped_to_workspace_ok other".to_string(),
+			},
+		)
+		.await;
+
+		// -- Check
+		assert_eq!(project.workspace_id, fx_workspace_id);
+		assert!(
+			projects.iter().all(|p| p.id != fx_project_id),
+			"project should not be in the default workspace"
+		);
+		assert!(
+			matches!(task_res, Err(Error::AccessDenied { .. })),
+			"task should not be accessible out of its workspace"
+		);
+		assert_eq!(other_ws_res?.id, fx_workspace_id);
+		assert!(
+			matches!(create_res, Err(Error::AccessDenied { .. })),
+			"should not create a project in a workspace not a member of"
+		);
+
+		// -- Clean
+		ProjectBmc::delete(&ctx_ws, &mm, fx_project_id).await?;
+		ProjectBmc::purge(&ctx_ws, &mm, fx_project_id).await?;
+		WorkspaceBmc::delete(&ctx, &mm, fx_workspace_id).await?;
+		base::delete::<UserBmc>(&Ctx::root_ctx(), &mm, fx_user_id).await?;
+
+		Ok(())
+	}
+
+	#[serial]
+	#[tokio::test]
+	async fn test_delete_err_not_empty() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let fx_workspace_id = WorkspaceBmc::create(
+			&ctx,
+			&mm,
+			WorkspaceForCreate {
+				name: "test_delete_err_not_empty workspace".to_string(),
+			},
+		)
+		.await?;
+		let ctx_ws = ctx.add_workspace_id(fx_workspace_id);
+		let fx_project_id = _dev_utils::seed_project(
+			&ctx_ws,
+			&mm,
+			"test_delete_err_not_empty project",
+		)
+		.await?;
+
+		// -- Exec
+		let res_active = WorkspaceBmc::delete(&ctx, &mm, fx_workspace_id).await;
+		ProjectBmc::delete(&ctx_ws, &mm, fx_project_id).await?;
+		let res_trashed = WorkspaceBmc::delete(&ctx, &mm, fx_workspace_id).await;
+		ProjectBmc::purge(&ctx_ws, &mm, fx_project_id).await?;
+		WorkspaceBmc::delete(&ctx, &mm, fx_workspace_id).await?;
+
+		// -- Check
+		assert!(
+			matches!(res_active, Err(Error::WorkspaceNotEmpty { id }) if id == fx_workspace_id),
+			"should not delete a workspace with an active project"
+		);
+		assert!(
+			matches!(res_trashed, Err(Error::WorkspaceNotEmpty { id }) if id == fx_workspace_id),
+			"should not delete a workspace with a trashed project"
+		);
+		let res = WorkspaceBmc::get(&ctx, &mm, fx_workspace_id).await;
+		assert!(
+			matches!(res, Err(Error::EntityNotFound { .. })),
+			"workspace should be deleted once empty"
+		);
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -0,0 +1,359 @@
+use crate::ctx::Ctx;
+use crate::model::base::{self, AccessKind, DbBmc};
+use crate::model::modql_utils::time_to_sea_value;
+use crate::model::workspace::WorkspaceBmc;
+use crate::model::ModelManager;
+use crate::model::{Error, Result};
+use lib_utils::time::Rfc3339;
+use modql::field::{FieldValue, Fields};
+use modql::filter::{
+	FilterNodes, ListOptions, OpValsInt64, OpValsString, OpValsValue,
+};
+use sea_query::{Condition, Expr, Iden, Query, SelectStatement};
+use serde::{Deserialize, Serialize};
+use serde_with::serde_as;
+use sqlx::types::time::OffsetDateTime;
+use sqlx::FromRow;
+
+// region:    --- WorkspaceRole
+
+/// The role of a user in a workspace.
+#[derive(
+	Debug, Clone, Copy, PartialEq, Eq, FieldValue, sqlx::Type, Serialize, Deserialize,
+)]
+#[sqlx(type_name = "varchar")]
+pub enum WorkspaceRole {
+	/// Can update and delete the workspace, and manage its members.
+	Owner,
+	/// Can see the workspace, and create projects in it.
+	Member,
+}
+
+impl WorkspaceRole {
+	/// Returns the roles having at least the rights of this role.
+	pub fn roles_at_least(self) -> &'static [WorkspaceRole] {
+		use WorkspaceRole::*;
+
+		match self {
+			Owner => &[Owner],
+			Member => &[Owner, Member],
+		}
+	}
+}
+
+// endregion: --- WorkspaceRole
+
+// region:    --- WorkspaceMember Types
+#[serde_as]
+#[derive(Debug, Clone, Fields, FromRow, Serialize)]
+pub struct WorkspaceMember {
+	pub id: i64,
+	pub workspace_id: i64,
+	pub user_id: i64,
+
+	pub role: WorkspaceRole,
+
+	// -- Timestamps
+	//    (creator and last modified user_id/time)
+	pub cid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub ctime: OffsetDateTime,
+	pub mid: i64,
+	#[serde_as(as = "Rfc3339")]
+	pub mtime: OffsetDateTime,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct WorkspaceMemberForCreate {
+	pub workspace_id: i64,
+	pub user_id: i64,
+	pub role: WorkspaceRole,
+}
+
+#[derive(Fields, Deserialize)]
+pub struct WorkspaceMemberForUpdate {
+	pub role: WorkspaceRole,
+}
+
+#[derive(FilterNodes, Deserialize, Default, Debug)]
+pub struct WorkspaceMemberFilter {
+	id: Option<OpValsInt64>,
+	workspace_id: Option<OpValsInt64>,
+	user_id: Option<OpValsInt64>,
+	role: Option<OpValsString>,
+
+	cid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	ctime: Option<OpValsValue>,
+	mid: Option<OpValsInt64>,
+	#[modql(to_sea_value_fn = "time_to_sea_value")]
+	mtime: Option<OpValsValue>,
+}
+
+#[derive(Iden)]
+enum WorkspaceMemberIden {
+	WorkspaceId,
+	UserId,
+	Role,
+}
+// endregion: --- WorkspaceMember Types
+
+// region:    --- WorkspaceMemberBmc
+pub struct WorkspaceMemberBmc;
+
+impl DbBmc for WorkspaceMemberBmc {
+	const TABLE: &'static str = "workspace_member";
+
+	/// Members are visible to all the workspace members,
+	/// but only managed by the workspace owners.
+	fn access_cond(ctx: &Ctx, access: AccessKind) -> Option<Condition> {
+		let min_role = match access {
+			AccessKind::Read => WorkspaceRole::Member,
+			AccessKind::Write => WorkspaceRole::Owner,
+		};
+
+		Some(
+			Condition::all().add(
+				Expr::col(WorkspaceMemberIden::WorkspaceId)
+					.in_subquery(Self::workspace_ids_query(ctx, min_role)),
+			),
+		)
+	}
+}
+
+impl WorkspaceMemberBmc {
+	/// Returns the sub query selecting the ids of the workspaces
+	/// in which the ctx user has at least the `min_role`.
+	pub(in crate::model) fn workspace_ids_query(
+		ctx: &Ctx,
+		min_role: WorkspaceRole,
+	) -> SelectStatement {
+		Query::select()
+			.column(WorkspaceMemberIden::WorkspaceId)
+			.from(Self::table_ref())
+			.and_where(Expr::col(WorkspaceMemberIden::UserId).eq(ctx.user_id()))
+			.and_where(
+				Expr::col(WorkspaceMemberIden::Role)
+					.is_in(min_role.roles_at_least().iter().copied()),
+			)
+			.to_owned()
+	}
+
+	pub async fn create(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		workspace_member_c: WorkspaceMemberForCreate,
+	) -> Result<i64> {
+		// -- Check the workspace access
+		WorkspaceBmc::check_role(
+			ctx,
+			mm,
+			workspace_member_c.workspace_id,
+			WorkspaceRole::Owner,
+		)
+		.await?;
+
+		base::create::<Self, _>(ctx, mm, workspace_member_c).await
+	}
+
+	pub async fn get(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+	) -> Result<WorkspaceMember> {
+		base::get::<Self, _>(ctx, mm, id).await
+	}
+
+	pub async fn list(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		filter: Option<Vec<WorkspaceMemberFilter>>,
+		list_options: Option<ListOptions>,
+	) -> Result<Vec<WorkspaceMember>> {
+		base::list::<Self, _, _>(ctx, mm, filter, list_options).await
+	}
+
+	pub async fn update(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		workspace_member_u: WorkspaceMemberForUpdate,
+	) -> Result<()> {
+		Self::update_versioned(ctx, mm, id, workspace_member_u, None).await
+	}
+
+	/// Update the workspace member, only if it was not modified
+	/// since the `expected_mtime` (when given).
+	///
+	/// Note: Cannot demote the last owner of the workspace.
+	pub async fn update_versioned(
+		ctx: &Ctx,
+		mm: &ModelManager,
+		id: i64,
+		workspace_member_u: WorkspaceMemberForUpdate,
+		expected_mtime: Option<OffsetDateTime>,
+	) -> Result<()> {
+		mm.in_txn(|mm| async move {
+			let workspace_id = Self::lock_workspace(&mm, id).await?;
+
+			base::update_versioned::<Self, _>(
+				ctx,
+				&mm,
+				id,
+				workspace_member_u,
+				expected_mtime,
+			)
+			.await?;
+
+			Self::check_has_owner(&mm, workspace_id).await
+		})
+		.await
+	}
+
+	/// Note: Cannot remove the last owner of the workspace.
+	pub async fn delete(ctx: &Ctx, mm: &ModelManager, id: i64) -> Result<()> {
+		mm.in_txn(|mm| async move {
+			let workspace_id = Self::lock_workspace(&mm, id).await?;
+
+			base::delete::<Self>(ctx, &mm, id).await?;
+
+			Self::check_has_owner(&mm, workspace_id).await
+		})
+		.await
+	}
+}
+// endregion: --- WorkspaceMemberBmc
+
+// region:    --- Owner Utils
+
+impl WorkspaceMemberBmc {
+	/// Locks the workspace of the member `id` until the end of the transaction
+	/// (i.e., serializing its member changes), and returns its id, if found.
+	async fn lock_workspace(mm: &ModelManager, id: i64) -> Result<Option<i64>> {
+		let dbx = mm.dbx();
+
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(
+			r#"
+			SELECT w.id FROM workspace w
+			JOIN workspace_member m ON m.workspace_id = w.id
+			WHERE m.id = $1
+			FOR UPDATE OF w
+			"#,
+		)
+		.bind(id);
+		let workspace_id = dbx.fetch_optional(sqlx_query).await?;
+
+		Ok(workspace_id.map(|(workspace_id,)| workspace_id))
+	}
+
+	/// Checks that the workspace `workspace_id` (when given) still has an owner.
+	async fn check_has_owner(
+		mm: &ModelManager,
+		workspace_id: Option<i64>,
+	) -> Result<()> {
+		let Some(workspace_id) = workspace_id else {
+			return Ok(());
+		};
+		let dbx = mm.dbx();
+
+		let sqlx_query = sqlx::query_as::<_, (i64,)>(
+			"SELECT count(*) FROM workspace_member WHERE workspace_id = $1 AND role = $2",
+		)
+		.bind(workspace_id)
+		.bind(WorkspaceRole::Owner);
+		let (owner_count,) = dbx.fetch_one(sqlx_query).await?;
+
+		if owner_count == 0 {
+			return Err(Error::WorkspaceLastOwner { workspace_id });
+		}
+
+		Ok(())
+	}
+}
+
+// endregion: --- Owner Utils
+
+// region:    --- Tests
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::_dev_utils;
+	use crate::model::workspace::WorkspaceForCreate;
+	use anyhow::Result;
+	use modql::filter::OpValInt64;
+	use serial_test::serial;
+
+	#[serial]
+	#[tokio::test]
+	async fn test_update_delete_err_last_owner() -> Result<()> {
+		// -- Setup & Fixtures
+		let mm = _dev_utils::init_test().await;
+		let ctx = _dev_utils::ctx_for_username(&mm, "demo1").await;
+		let ctx_other = _dev_utils::ctx_for_username(&mm, "demo2").await;
+		let fx_workspace_id = WorkspaceBmc::create(
+			&ctx,
+			&mm,
+			WorkspaceForCreate {
+				name: "test_update_delete_err_last_owner workspace".to_string(),
+			},
+		)
+		.await?;
+		let filter = WorkspaceMemberFilter {
+			workspace_id: Some(OpValInt64::Eq(fx_workspace_id).into()),
+			..Default::default()
+		};
+		let fx_owner_id =
+			WorkspaceMemberBmc::list(&ctx, &mm, Some(vec![filter]), None).await?[0]
+				.id;
+
+		// -- Exec
+		let res_demote = WorkspaceMemberBmc::update(
+			&ctx,
+			&mm,
+			fx_owner_id,
+			WorkspaceMemberForUpdate {
+				role: WorkspaceRole::Member,
+			},
+		)
+		.await;
+		let res_remove = WorkspaceMemberBmc::delete(&ctx, &mm, fx_owner_id).await;
+		// (with another owner)
+		WorkspaceMemberBmc::create(
+			&ctx,
+			&mm,
+			WorkspaceMemberForCreate {
+				workspace_id: fx_workspace_id,
+				user_id: ctx_other.user_id(),
+				role: WorkspaceRole::Owner,
+			},
+		)
+		.await?;
+		WorkspaceMemberBmc::update(
+			&ctx,
+			&mm,
+			fx_owner_id,
+			WorkspaceMemberForUpdate {
+				role: WorkspaceRole::Member,
+			},
+		)
+		.await?;
+
+		// -- Check
+		assert!(
+			matches!(res_demote, Err(Error::WorkspaceLastOwner { workspace_id }) if workspace_id == fx_workspace_id),
+			"should not demote the last owner"
+		);
+		assert!(
+			matches!(res_remove, Err(Error::WorkspaceLastOwner { workspace_id }) if workspace_id == fx_workspace_id),
+			"should not remove the last owner"
+		);
+		let member = WorkspaceMemberBmc::get(&ctx, &mm, fx_owner_id).await?;
+		assert_eq!(member.role, WorkspaceRole::Member);
+
+		// -- Clean
+		WorkspaceBmc::delete(&ctx_other, &mm, fx_workspace_id).await?;
+
+		Ok(())
+	}
+}
+// endregion: --- Tests
@@ -3,6 +3,7 @@
 mod error;
 mod params;
 mod resources;
+mod results;
 mod rpcs;
 
 pub mod router;
@@ -10,6 +11,7 @@ pub mod router;
 pub use self::error::{Error, Result};
 pub use params::*;
 pub use resources::RpcResources;
+pub use results::*;
 pub use router::RpcRequest;
 
 pub use rpcs::*;
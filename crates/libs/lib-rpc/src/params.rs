@@ -9,11 +9,14 @@
 
 use crate::router::{IntoDefaultParams, IntoParams};
 use crate::Result;
+use lib_core::model::ManyTarget;
+use lib_utils::time::Rfc3339;
 use modql::filter::ListOptions;
 use serde::de::DeserializeOwned;
 use serde::Deserialize;
 use serde_json::Value;
 use serde_with::{serde_as, OneOrMany};
+use time::OffsetDateTime;
 
 /// Params structure for any RPC Create call.
 #[derive(Deserialize)]
@@ -23,15 +26,58 @@ pub struct ParamsForCreate<D> {
 
 impl<D> IntoParams for ParamsForCreate<D> where D: DeserializeOwned + Send {}
 
+/// Params structure for any RPC Create Many call.
+#[derive(Deserialize)]
+pub struct ParamsForCreateMany<D> {
+	pub data: Vec<D>,
+}
+
+impl<D> IntoParams for ParamsForCreateMany<D> where D: DeserializeOwned + Send {}
+
 /// Params structure for any RPC Update call.
+///
+/// The optional `expected_mtime` is the `mtime` (i.e., version) of the entity
+/// the client last read. When given, the update fails with
+/// `ConcurrentModification` if the entity was modified since.
+#[serde_as]
 #[derive(Deserialize)]
 pub struct ParamsForUpdate<D> {
 	pub id: i64,
 	pub data: D,
+	#[serde_as(as = "Option<Rfc3339>")]
+	#[serde(default)]
+	pub expected_mtime: Option<OffsetDateTime>,
 }
 
 impl<D> IntoParams for ParamsForUpdate<D> where D: DeserializeOwned + Send {}
 
+/// Params structure for any RPC Update Many call, targeting the entities
+/// by `ids` or by `filters` (e.g., `{"ids": [123, 124], "data": {..}}`).
+#[derive(Deserialize)]
+pub struct ParamsForUpdateMany<D, F> {
+	#[serde(flatten)]
+	pub target: ManyTarget<F>,
+	pub data: D,
+}
+
+impl<D, F> IntoParams for ParamsForUpdateMany<D, F>
+where
+	D: DeserializeOwned + Send,
+	F: DeserializeOwned + Send,
+{
+}
+
+/// Params structure for any RPC Move call, with the entity `id`
+/// and its new `position` (e.g., `{"id": 123, "before": 124}`).
+#[derive(Deserialize)]
+pub struct ParamsForMove<P> {
+	pub id: i64,
+	#[serde(flatten)]
+	pub position: P,
+}
+
+impl<P> IntoParams for ParamsForMove<P> where P: DeserializeOwned + Send {}
+
 /// Params structure for any RPC Update call.
 #[derive(Deserialize)]
 pub struct ParamsIded {
@@ -39,6 +85,16 @@ pub struct ParamsIded {
 }
 impl IntoParams for ParamsIded {}
 
+/// Params structure for any RPC Delete Many call, targeting the entities
+/// by `ids` or by `filters` (e.g., `{"ids": [123, 124]}`).
+#[derive(Deserialize)]
+pub struct ParamsIdsOrFilters<F> {
+	#[serde(flatten)]
+	pub target: ManyTarget<F>,
+}
+
+impl<F> IntoParams for ParamsIdsOrFilters<F> where F: DeserializeOwned + Send {}
+
 /// Params structure for any RPC List call.
 #[serde_as]
 #[derive(Deserialize, Default)]
@@ -49,11 +105,45 @@ where
 	#[serde_as(deserialize_as = "Option<OneOrMany<_>>")]
 	pub filters: Option<Vec<F>>,
 	pub list_options: Option<ListOptions>,
+
+	/// Opt-in keyset pagination, returning the `ListResult::Page` envelope
+	/// with the `next_cursor` to pass as the `cursor` of the next call.
+	#[serde(default)]
+	pub with_cursor: bool,
+	pub cursor: Option<String>,
+
+	/// Opt-in total count, returning the `ListResult::WithMeta` envelope
+	/// (i.e., `items`, `total`, `limit`, `offset`).
+	///
+	/// Note: Ignored with the keyset pagination (see `is_paged`).
+	#[serde(default)]
+	pub with_meta: bool,
+}
+
+impl<F> ParamsList<F>
+where
+	F: DeserializeOwned,
+{
+	/// Returns `true` if the keyset pagination is requested
+	/// (i.e., `with_cursor` or a `cursor`).
+	pub fn is_paged(&self) -> bool {
+		self.with_cursor || self.cursor.is_some()
+	}
 }
 
 impl<D> IntoDefaultParams for ParamsList<D> where D: DeserializeOwned + Send + Default
 {}
 
+/// Params structure for any RPC Search call
+/// (e.g., `{"query": "design review", "list_options": {"limit": 20}}`).
+#[derive(Deserialize)]
+pub struct ParamsSearch {
+	pub query: String,
+	pub list_options: Option<ListOptions>,
+}
+
+impl IntoParams for ParamsSearch {}
+
 // region:    --- General Implementations
 
 /// Implements `IntoParams` for any type that also implements `IntoParams`.
@@ -0,0 +1,20 @@
+//! Base constructs for the typed RPC results of the rpc handler functions
+//! (e.g., `task_rpc::list_tasks`).
+
+use lib_core::model::{ListPage, ListWithMeta};
+use serde::Serialize;
+
+/// Result structure for any RPC List call.
+///
+/// - By default, the bare list of items (e.g., `[{..}, {..}]`).
+/// - When requested in the `ParamsList` (e.g., `with_cursor`), the page envelope
+///   (e.g., `{"items": [{..}, {..}], "next_cursor": ".."}`).
+/// - When requested with `with_meta`, the meta envelope
+///   (e.g., `{"items": [{..}, {..}], "total": 3214, "limit": 50, "offset": 50}`).
+#[derive(Serialize)]
+#[serde(untagged)]
+pub enum ListResult<E> {
+	Items(Vec<E>),
+	Page(ListPage<E>),
+	WithMeta(ListWithMeta<E>),
+}
@@ -0,0 +1,51 @@
+use crate::router::RpcRouter;
+use crate::rpc_router;
+use crate::ListResult;
+use crate::ParamsList;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::entity_history::{
+	EntityHistory, EntityHistoryBmc, EntityHistoryFilter,
+};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		list_entity_history,
+	)
+}
+
+pub async fn list_entity_history(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<EntityHistoryFilter>,
+) -> Result<ListResult<EntityHistory>> {
+	let list_result = if params.is_paged() {
+		let page = EntityHistoryBmc::list_page(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+			params.cursor,
+		)
+		.await?;
+		ListResult::Page(page)
+	} else if params.with_meta {
+		let list_with_meta = EntityHistoryBmc::list_with_meta(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+		)
+		.await?;
+		ListResult::WithMeta(list_with_meta)
+	} else {
+		let history =
+			EntityHistoryBmc::list(&ctx, &mm, params.filters, params.list_options)
+				.await?;
+		ListResult::Items(history)
+	};
+
+	Ok(list_result)
+}
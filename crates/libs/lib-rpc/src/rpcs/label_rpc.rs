@@ -0,0 +1,128 @@
+use crate::router::{IntoParams, RpcRouter};
+use crate::rpc_router;
+use crate::Result;
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use lib_core::ctx::Ctx;
+use lib_core::model::label::{
+	Label, LabelBmc, LabelFilter, LabelForCreate, LabelForUpdate,
+};
+use lib_core::model::ModelManager;
+use serde::Deserialize;
+
+/// Params structure for the task label rpcs.
+#[derive(Deserialize)]
+pub struct ParamsTaskLabel {
+	pub task_id: i64,
+	pub label_id: i64,
+}
+
+impl IntoParams for ParamsTaskLabel {}
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		create_label,
+		list_labels,
+		update_label,
+		delete_label,
+		add_task_label,
+		remove_task_label,
+	)
+}
+
+pub async fn create_label(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<LabelForCreate>,
+) -> Result<Label> {
+	let ParamsForCreate { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let id = LabelBmc::create(&ctx, &mm, data).await?;
+		let label = LabelBmc::get(&ctx, &mm, id).await?;
+
+		Ok(label)
+	})
+	.await
+}
+
+pub async fn list_labels(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<LabelFilter>,
+) -> Result<Vec<Label>> {
+	let labels =
+		LabelBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(labels)
+}
+
+pub async fn update_label(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<LabelForUpdate>,
+) -> Result<Label> {
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		LabelBmc::update_versioned(&ctx, &mm, id, data, expected_mtime).await?;
+		let label = LabelBmc::get(&ctx, &mm, id).await?;
+
+		Ok(label)
+	})
+	.await
+}
+
+pub async fn delete_label(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Label> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let label = LabelBmc::get(&ctx, &mm, id).await?;
+		LabelBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(label)
+	})
+	.await
+}
+
+/// Returns the task labels.
+pub async fn add_task_label(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskLabel,
+) -> Result<Vec<Label>> {
+	let ParamsTaskLabel { task_id, label_id } = params;
+
+	mm.in_txn(|mm| async move {
+		LabelBmc::add_to_task(&ctx, &mm, task_id, label_id).await?;
+		let labels = LabelBmc::list_for_task(&ctx, &mm, task_id).await?;
+
+		Ok(labels)
+	})
+	.await
+}
+
+/// Returns the task labels.
+pub async fn remove_task_label(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskLabel,
+) -> Result<Vec<Label>> {
+	let ParamsTaskLabel { task_id, label_id } = params;
+
+	mm.in_txn(|mm| async move {
+		LabelBmc::remove_from_task(&ctx, &mm, task_id, label_id).await?;
+		let labels = LabelBmc::list_for_task(&ctx, &mm, task_id).await?;
+
+		Ok(labels)
+	})
+	.await
+}
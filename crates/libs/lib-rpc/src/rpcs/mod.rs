@@ -1,2 +1,9 @@
+pub mod entity_history_rpc;
+pub mod label_rpc;
+pub mod project_member_rpc;
 pub mod project_rpc;
+pub mod search_rpc;
+pub mod task_comment_rpc;
 pub mod task_rpc;
+pub mod user_rpc;
+pub mod workspace_rpc;
@@ -0,0 +1,109 @@
+use crate::router::RpcRouter;
+use crate::rpc_router;
+use crate::ListResult;
+use crate::Result;
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use lib_core::ctx::Ctx;
+use lib_core::model::project_member::{
+	ProjectMember, ProjectMemberBmc, ProjectMemberFilter, ProjectMemberForCreate,
+	ProjectMemberForUpdate,
+};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		add_project_member,
+		list_project_members,
+		change_project_member_role,
+		remove_project_member,
+	)
+}
+
+pub async fn add_project_member(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<ProjectMemberForCreate>,
+) -> Result<ProjectMember> {
+	let ParamsForCreate { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let id = ProjectMemberBmc::create(&ctx, &mm, data).await?;
+		let project_member = ProjectMemberBmc::get(&ctx, &mm, id).await?;
+
+		Ok(project_member)
+	})
+	.await
+}
+
+pub async fn list_project_members(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<ProjectMemberFilter>,
+) -> Result<ListResult<ProjectMember>> {
+	let list_result = if params.is_paged() {
+		let page = ProjectMemberBmc::list_page(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+			params.cursor,
+		)
+		.await?;
+		ListResult::Page(page)
+	} else if params.with_meta {
+		let list_with_meta = ProjectMemberBmc::list_with_meta(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+		)
+		.await?;
+		ListResult::WithMeta(list_with_meta)
+	} else {
+		let project_members =
+			ProjectMemberBmc::list(&ctx, &mm, params.filters, params.list_options)
+				.await?;
+		ListResult::Items(project_members)
+	};
+
+	Ok(list_result)
+}
+
+pub async fn change_project_member_role(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<ProjectMemberForUpdate>,
+) -> Result<ProjectMember> {
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		ProjectMemberBmc::update_versioned(&ctx, &mm, id, data, expected_mtime)
+			.await?;
+
+		let project_member = ProjectMemberBmc::get(&ctx, &mm, id).await?;
+
+		Ok(project_member)
+	})
+	.await
+}
+
+pub async fn remove_project_member(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<ProjectMember> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let project_member = ProjectMemberBmc::get(&ctx, &mm, id).await?;
+		ProjectMemberBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(project_member)
+	})
+	.await
+}
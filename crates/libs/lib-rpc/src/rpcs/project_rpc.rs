@@ -1,5 +1,6 @@
 use crate::router::RpcRouter;
 use crate::rpc_router;
+use crate::ListResult;
 use crate::Result;
 use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
 use lib_core::ctx::Ctx;
@@ -15,6 +16,9 @@ pub fn rpc_router() -> RpcRouter {
 		list_projects,
 		update_project,
 		delete_project,
+		restore_project,
+		list_trash_projects,
+		purge_project,
 	)
 }
 
@@ -25,21 +29,46 @@ pub async fn create_project(
 ) -> Result<Project> {
 	let ParamsForCreate { data } = params;
 
-	let id = ProjectBmc::create(&ctx, &mm, data).await?;
-	let project = ProjectBmc::get(&ctx, &mm, id).await?;
+	mm.in_txn(|mm| async move {
+		let id = ProjectBmc::create(&ctx, &mm, data).await?;
+		let project = ProjectBmc::get(&ctx, &mm, id).await?;
 
-	Ok(project)
+		Ok(project)
+	})
+	.await
 }
 
 pub async fn list_projects(
 	ctx: Ctx,
 	mm: ModelManager,
 	params: ParamsList<ProjectFilter>,
-) -> Result<Vec<Project>> {
-	let projects =
-		ProjectBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+) -> Result<ListResult<Project>> {
+	let list_result = if params.is_paged() {
+		let page = ProjectBmc::list_page(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+			params.cursor,
+		)
+		.await?;
+		ListResult::Page(page)
+	} else if params.with_meta {
+		let list_with_meta = ProjectBmc::list_with_meta(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+		)
+		.await?;
+		ListResult::WithMeta(list_with_meta)
+	} else {
+		let projects =
+			ProjectBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+		ListResult::Items(projects)
+	};
 
-	Ok(projects)
+	Ok(list_result)
 }
 
 pub async fn update_project(
@@ -47,13 +76,20 @@ pub async fn update_project(
 	mm: ModelManager,
 	params: ParamsForUpdate<ProjectForUpdate>,
 ) -> Result<Project> {
-	let ParamsForUpdate { id, data } = params;
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
 
-	ProjectBmc::update(&ctx, &mm, id, data).await?;
+	mm.in_txn(|mm| async move {
+		ProjectBmc::update_versioned(&ctx, &mm, id, data, expected_mtime).await?;
 
-	let project = ProjectBmc::get(&ctx, &mm, id).await?;
+		let project = ProjectBmc::get(&ctx, &mm, id).await?;
 
-	Ok(project)
+		Ok(project)
+	})
+	.await
 }
 
 pub async fn delete_project(
@@ -63,8 +99,56 @@ pub async fn delete_project(
 ) -> Result<Project> {
 	let ParamsIded { id } = params;
 
-	let project = ProjectBmc::get(&ctx, &mm, id).await?;
-	ProjectBmc::delete(&ctx, &mm, id).await?;
+	mm.in_txn(|mm| async move {
+		let project = ProjectBmc::get(&ctx, &mm, id).await?;
+		ProjectBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(project)
+	})
+	.await
+}
+
+pub async fn restore_project(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Project> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		ProjectBmc::restore(&ctx, &mm, id).await?;
+
+		let project = ProjectBmc::get(&ctx, &mm, id).await?;
+
+		Ok(project)
+	})
+	.await
+}
+
+pub async fn list_trash_projects(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<ProjectFilter>,
+) -> Result<Vec<Project>> {
+	let projects =
+		ProjectBmc::list_trashed(&ctx, &mm, params.filters, params.list_options)
+			.await?;
+
+	Ok(projects)
+}
+
+pub async fn purge_project(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Project> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let project = ProjectBmc::get_trashed(&ctx, &mm, id).await?;
+		ProjectBmc::purge(&ctx, &mm, id).await?;
 
-	Ok(project)
+		Ok(project)
+	})
+	.await
 }
@@ -0,0 +1,29 @@
+use crate::router::RpcRouter;
+use crate::rpc_router;
+use crate::ParamsSearch;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::search::{SearchBmc, SearchHit};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		search,
+	)
+}
+
+pub async fn search(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsSearch,
+) -> Result<Vec<SearchHit>> {
+	let ParamsSearch {
+		query,
+		list_options,
+	} = params;
+
+	let hits = SearchBmc::search(&ctx, &mm, &query, list_options).await?;
+
+	Ok(hits)
+}
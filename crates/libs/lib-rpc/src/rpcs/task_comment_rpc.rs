@@ -0,0 +1,109 @@
+use crate::router::RpcRouter;
+use crate::rpc_router;
+use crate::ListResult;
+use crate::Result;
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use lib_core::ctx::Ctx;
+use lib_core::model::task_comment::{
+	TaskComment, TaskCommentBmc, TaskCommentFilter, TaskCommentForCreate,
+	TaskCommentForUpdate,
+};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		add_task_comment,
+		list_task_comments,
+		update_task_comment,
+		delete_task_comment,
+	)
+}
+
+pub async fn add_task_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<TaskCommentForCreate>,
+) -> Result<TaskComment> {
+	let ParamsForCreate { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let id = TaskCommentBmc::create(&ctx, &mm, data).await?;
+		let task_comment = TaskCommentBmc::get(&ctx, &mm, id).await?;
+
+		Ok(task_comment)
+	})
+	.await
+}
+
+pub async fn list_task_comments(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TaskCommentFilter>,
+) -> Result<ListResult<TaskComment>> {
+	let list_result = if params.is_paged() {
+		let page = TaskCommentBmc::list_page(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+			params.cursor,
+		)
+		.await?;
+		ListResult::Page(page)
+	} else if params.with_meta {
+		let list_with_meta = TaskCommentBmc::list_with_meta(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+		)
+		.await?;
+		ListResult::WithMeta(list_with_meta)
+	} else {
+		let task_comments =
+			TaskCommentBmc::list(&ctx, &mm, params.filters, params.list_options)
+				.await?;
+		ListResult::Items(task_comments)
+	};
+
+	Ok(list_result)
+}
+
+pub async fn update_task_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<TaskCommentForUpdate>,
+) -> Result<TaskComment> {
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		TaskCommentBmc::update_versioned(&ctx, &mm, id, data, expected_mtime)
+			.await?;
+
+		let task_comment = TaskCommentBmc::get(&ctx, &mm, id).await?;
+
+		Ok(task_comment)
+	})
+	.await
+}
+
+pub async fn delete_task_comment(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<TaskComment> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let task_comment = TaskCommentBmc::get(&ctx, &mm, id).await?;
+		TaskCommentBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(task_comment)
+	})
+	.await
+}
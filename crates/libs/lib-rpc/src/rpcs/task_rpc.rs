@@ -1,20 +1,62 @@
-use crate::router::RpcRouter;
+use crate::router::{IntoParams, RpcRouter};
 use crate::rpc_router;
+use crate::ListResult;
 use crate::Result;
-use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use crate::{
+	ParamsForCreate, ParamsForCreateMany, ParamsForMove, ParamsForUpdate,
+	ParamsForUpdateMany, ParamsIded, ParamsIdsOrFilters, ParamsList,
+};
 use lib_core::ctx::Ctx;
 use lib_core::model::task::{
-	Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate,
+	Task, TaskBmc, TaskFilter, TaskForCreate, TaskForUpdate, TaskOccurrence,
+	TaskPosition, TaskTree,
 };
 use lib_core::model::ModelManager;
+use serde::Deserialize;
+
+/// Params structure for the task blocker rpcs
+/// (i.e., `task_id` is blocked by `blocker_id`).
+#[derive(Deserialize)]
+pub struct ParamsTaskBlocker {
+	pub task_id: i64,
+	pub blocker_id: i64,
+}
+
+impl IntoParams for ParamsTaskBlocker {}
+
+/// Params structure for the `list_occurrences` rpc.
+#[derive(Deserialize)]
+pub struct ParamsTaskOccurrences {
+	pub id: i64,
+	/// The max number of occurrences (default 10).
+	pub limit: Option<i64>,
+}
+
+impl IntoParams for ParamsTaskOccurrences {}
+
+/// The default `list_occurrences` limit.
+const OCCURRENCES_LIMIT_DEFAULT: i64 = 10;
 
 pub fn rpc_router() -> RpcRouter {
 	rpc_router!(
 		// Same as RpcRouter::new().add...
 		create_task,
+		create_tasks,
 		list_tasks,
+		get_task_tree,
 		update_task,
+		update_tasks,
+		move_task,
+		add_task_blocker,
+		remove_task_blocker,
+		list_blocked_tasks,
+		list_ready_tasks,
+		list_occurrences,
 		delete_task,
+		delete_tasks,
+		restore_task,
+		list_trash_tasks,
+		purge_task,
 	)
 }
 
@@ -25,21 +67,70 @@ pub async fn create_task(
 ) -> Result<Task> {
 	let ParamsForCreate { data } = params;
 
-	let id = TaskBmc::create(&ctx, &mm, data).await?;
-	let task = TaskBmc::get(&ctx, &mm, id).await?;
+	mm.in_txn(|mm| async move {
+		let id = TaskBmc::create(&ctx, &mm, data).await?;
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+pub async fn create_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreateMany<TaskForCreate>,
+) -> Result<Vec<Task>> {
+	let ParamsForCreateMany { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let ids = TaskBmc::create_many(&ctx, &mm, data).await?;
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
 
-	Ok(task)
+		Ok(tasks)
+	})
+	.await
 }
 
 pub async fn list_tasks(
 	ctx: Ctx,
 	mm: ModelManager,
 	params: ParamsList<TaskFilter>,
-) -> Result<Vec<Task>> {
-	let tasks =
-		TaskBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+) -> Result<ListResult<Task>> {
+	let list_result = if params.is_paged() {
+		let page = TaskBmc::list_page(
+			&ctx,
+			&mm,
+			params.filters,
+			params.list_options,
+			params.cursor,
+		)
+		.await?;
+		ListResult::Page(page)
+	} else if params.with_meta {
+		let list_with_meta =
+			TaskBmc::list_with_meta(&ctx, &mm, params.filters, params.list_options)
+				.await?;
+		ListResult::WithMeta(list_with_meta)
+	} else {
+		let tasks =
+			TaskBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+		ListResult::Items(tasks)
+	};
 
-	Ok(tasks)
+	Ok(list_result)
+}
+
+pub async fn get_task_tree(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<TaskTree> {
+	let ParamsIded { id } = params;
+
+	let task_tree = TaskBmc::get_tree(&ctx, &mm, id).await?;
+
+	Ok(task_tree)
 }
 
 pub async fn update_task(
@@ -47,13 +138,129 @@ pub async fn update_task(
 	mm: ModelManager,
 	params: ParamsForUpdate<TaskForUpdate>,
 ) -> Result<Task> {
-	let ParamsForUpdate { id, data } = params;
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
 
-	TaskBmc::update(&ctx, &mm, id, data).await?;
+	mm.in_txn(|mm| async move {
+		TaskBmc::update_versioned(&ctx, &mm, id, data, expected_mtime).await?;
 
-	let task = TaskBmc::get(&ctx, &mm, id).await?;
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
 
-	Ok(task)
+		Ok(task)
+	})
+	.await
+}
+
+pub async fn update_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdateMany<TaskForUpdate, Vec<TaskFilter>>,
+) -> Result<Vec<Task>> {
+	let ParamsForUpdateMany { target, data } = params;
+
+	mm.in_txn(|mm| async move {
+		let ids = TaskBmc::update_many(&ctx, &mm, target, data).await?;
+		let tasks = TaskBmc::get_many(&ctx, &mm, &ids).await?;
+
+		Ok(tasks)
+	})
+	.await
+}
+
+pub async fn move_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForMove<TaskPosition>,
+) -> Result<Task> {
+	let ParamsForMove { id, position } = params;
+
+	mm.in_txn(|mm| async move {
+		TaskBmc::move_task(&ctx, &mm, id, position).await?;
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+/// Returns the blocked task.
+pub async fn add_task_blocker(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskBlocker,
+) -> Result<Task> {
+	let ParamsTaskBlocker {
+		task_id,
+		blocker_id,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		TaskBmc::add_blocker(&ctx, &mm, task_id, blocker_id).await?;
+		let task = TaskBmc::get(&ctx, &mm, task_id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+/// Returns the (formerly) blocked task.
+pub async fn remove_task_blocker(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskBlocker,
+) -> Result<Task> {
+	let ParamsTaskBlocker {
+		task_id,
+		blocker_id,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		TaskBmc::remove_blocker(&ctx, &mm, task_id, blocker_id).await?;
+		let task = TaskBmc::get(&ctx, &mm, task_id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+pub async fn list_blocked_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TaskFilter>,
+) -> Result<Vec<Task>> {
+	let tasks =
+		TaskBmc::list_blocked(&ctx, &mm, params.filters, params.list_options)
+			.await?;
+
+	Ok(tasks)
+}
+
+pub async fn list_ready_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TaskFilter>,
+) -> Result<Vec<Task>> {
+	let tasks =
+		TaskBmc::list_ready(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(tasks)
+}
+
+/// Preview the upcoming occurrences of a recurring task.
+pub async fn list_occurrences(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsTaskOccurrences,
+) -> Result<Vec<TaskOccurrence>> {
+	let ParamsTaskOccurrences { id, limit } = params;
+	let limit = limit.unwrap_or(OCCURRENCES_LIMIT_DEFAULT);
+
+	let occurrences = TaskBmc::list_occurrences(&ctx, &mm, id, limit).await?;
+
+	Ok(occurrences)
 }
 
 pub async fn delete_task(
@@ -63,8 +270,73 @@ pub async fn delete_task(
 ) -> Result<Task> {
 	let ParamsIded { id } = params;
 
-	let task = TaskBmc::get(&ctx, &mm, id).await?;
-	TaskBmc::delete(&ctx, &mm, id).await?;
+	mm.in_txn(|mm| async move {
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+		TaskBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+/// Returns the deleted tasks (i.e., now in the trash).
+pub async fn delete_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIdsOrFilters<Vec<TaskFilter>>,
+) -> Result<Vec<Task>> {
+	let ParamsIdsOrFilters { target } = params;
+
+	mm.in_txn(|mm| async move {
+		let ids = TaskBmc::delete_many(&ctx, &mm, target).await?;
+		let tasks = TaskBmc::get_many_trashed(&ctx, &mm, &ids).await?;
+
+		Ok(tasks)
+	})
+	.await
+}
+
+pub async fn restore_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Task> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		TaskBmc::restore(&ctx, &mm, id).await?;
+
+		let task = TaskBmc::get(&ctx, &mm, id).await?;
+
+		Ok(task)
+	})
+	.await
+}
+
+pub async fn list_trash_tasks(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<TaskFilter>,
+) -> Result<Vec<Task>> {
+	let tasks =
+		TaskBmc::list_trashed(&ctx, &mm, params.filters, params.list_options)
+			.await?;
+
+	Ok(tasks)
+}
+
+pub async fn purge_task(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Task> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let task = TaskBmc::get_trashed(&ctx, &mm, id).await?;
+		TaskBmc::purge(&ctx, &mm, id).await?;
 
-	Ok(task)
+		Ok(task)
+	})
+	.await
 }
@@ -0,0 +1,47 @@
+use crate::router::{IntoParams, RpcRouter};
+use crate::rpc_router;
+use crate::Result;
+use lib_core::ctx::Ctx;
+use lib_core::model::user::{User, UserBmc};
+use lib_core::model::ModelManager;
+use serde::Deserialize;
+
+/// Params structure for the own password change rpc.
+#[derive(Deserialize)]
+pub struct ParamsChangePwd {
+	pub pwd_old: String,
+	pub pwd_new: String,
+}
+
+impl IntoParams for ParamsChangePwd {}
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		get_own_user,
+		change_own_pwd,
+	)
+}
+
+/// Returns the ctx user profile.
+pub async fn get_own_user(ctx: Ctx, mm: ModelManager) -> Result<User> {
+	let user = UserBmc::get(&ctx, &mm, ctx.user_id()).await?;
+
+	Ok(user)
+}
+
+/// Change the ctx user password, after validating its current one.
+pub async fn change_own_pwd(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsChangePwd,
+) -> Result<User> {
+	let ParamsChangePwd { pwd_old, pwd_new } = params;
+	let user_id = ctx.user_id();
+
+	UserBmc::validate_pwd(&ctx, &mm, user_id, &pwd_old).await?;
+	UserBmc::update_pwd(&ctx, &mm, user_id, &pwd_new).await?;
+	let user = UserBmc::get(&ctx, &mm, user_id).await?;
+
+	Ok(user)
+}
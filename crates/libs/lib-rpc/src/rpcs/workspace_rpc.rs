@@ -0,0 +1,164 @@
+use crate::router::RpcRouter;
+use crate::rpc_router;
+use crate::Result;
+use crate::{ParamsForCreate, ParamsForUpdate, ParamsIded, ParamsList};
+use lib_core::ctx::Ctx;
+use lib_core::model::workspace::{
+	Workspace, WorkspaceBmc, WorkspaceFilter, WorkspaceForCreate, WorkspaceForUpdate,
+};
+use lib_core::model::workspace_member::{
+	WorkspaceMember, WorkspaceMemberBmc, WorkspaceMemberFilter,
+	WorkspaceMemberForCreate, WorkspaceMemberForUpdate,
+};
+use lib_core::model::ModelManager;
+
+pub fn rpc_router() -> RpcRouter {
+	rpc_router!(
+		// Same as RpcRouter::new().add...
+		create_workspace,
+		list_workspaces,
+		update_workspace,
+		delete_workspace,
+		add_workspace_member,
+		list_workspace_members,
+		change_workspace_member_role,
+		remove_workspace_member,
+	)
+}
+
+// region:    --- Workspace
+
+pub async fn create_workspace(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<WorkspaceForCreate>,
+) -> Result<Workspace> {
+	let ParamsForCreate { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let id = WorkspaceBmc::create(&ctx, &mm, data).await?;
+		let workspace = WorkspaceBmc::get(&ctx, &mm, id).await?;
+
+		Ok(workspace)
+	})
+	.await
+}
+
+/// List the workspaces of the ctx user (not only the active one).
+pub async fn list_workspaces(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<WorkspaceFilter>,
+) -> Result<Vec<Workspace>> {
+	let workspaces =
+		WorkspaceBmc::list(&ctx, &mm, params.filters, params.list_options).await?;
+
+	Ok(workspaces)
+}
+
+pub async fn update_workspace(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<WorkspaceForUpdate>,
+) -> Result<Workspace> {
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		WorkspaceBmc::update_versioned(&ctx, &mm, id, data, expected_mtime).await?;
+		let workspace = WorkspaceBmc::get(&ctx, &mm, id).await?;
+
+		Ok(workspace)
+	})
+	.await
+}
+
+pub async fn delete_workspace(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<Workspace> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let workspace = WorkspaceBmc::get(&ctx, &mm, id).await?;
+		WorkspaceBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(workspace)
+	})
+	.await
+}
+
+// endregion: --- Workspace
+
+// region:    --- WorkspaceMember
+
+pub async fn add_workspace_member(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForCreate<WorkspaceMemberForCreate>,
+) -> Result<WorkspaceMember> {
+	let ParamsForCreate { data } = params;
+
+	mm.in_txn(|mm| async move {
+		let id = WorkspaceMemberBmc::create(&ctx, &mm, data).await?;
+		let workspace_member = WorkspaceMemberBmc::get(&ctx, &mm, id).await?;
+
+		Ok(workspace_member)
+	})
+	.await
+}
+
+pub async fn list_workspace_members(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsList<WorkspaceMemberFilter>,
+) -> Result<Vec<WorkspaceMember>> {
+	let workspace_members =
+		WorkspaceMemberBmc::list(&ctx, &mm, params.filters, params.list_options)
+			.await?;
+
+	Ok(workspace_members)
+}
+
+pub async fn change_workspace_member_role(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsForUpdate<WorkspaceMemberForUpdate>,
+) -> Result<WorkspaceMember> {
+	let ParamsForUpdate {
+		id,
+		data,
+		expected_mtime,
+	} = params;
+
+	mm.in_txn(|mm| async move {
+		WorkspaceMemberBmc::update_versioned(&ctx, &mm, id, data, expected_mtime)
+			.await?;
+		let workspace_member = WorkspaceMemberBmc::get(&ctx, &mm, id).await?;
+
+		Ok(workspace_member)
+	})
+	.await
+}
+
+pub async fn remove_workspace_member(
+	ctx: Ctx,
+	mm: ModelManager,
+	params: ParamsIded,
+) -> Result<WorkspaceMember> {
+	let ParamsIded { id } = params;
+
+	mm.in_txn(|mm| async move {
+		let workspace_member = WorkspaceMemberBmc::get(&ctx, &mm, id).await?;
+		WorkspaceMemberBmc::delete(&ctx, &mm, id).await?;
+
+		Ok(workspace_member)
+	})
+	.await
+}
+
+// endregion: --- WorkspaceMember
@@ -11,11 +11,14 @@ use config::web_config;
 use crate::web::mw_auth::{mw_ctx_require, mw_ctx_resolve};
 use crate::web::mw_res_map::mw_reponse_map;
 use crate::web::mw_stamp::mw_req_stamp;
+use crate::web::routes_changes::ChangesState;
 use crate::web::routes_rpc::RpcState;
-use crate::web::{routes_login, routes_static};
+use crate::web::{routes_attachment, routes_changes, routes_login, routes_static};
 use axum::{middleware, Router};
 use lib_core::_dev_utils;
-use lib_core::model::ModelManager;
+use lib_core::model::change_feed::ChangeFeed;
+use lib_core::model::outbox::{LogSink, OutboxRelay};
+use lib_core::model::{migration, ModelManager};
 use tokio::net::TcpListener;
 use tower_cookies::CookieManagerLayer;
 use tracing::info;
@@ -37,14 +40,35 @@ async fn main() -> Result<()> {
 	// Initialize ModelManager.
 	let mm = ModelManager::new().await?;
 
+	// Apply the pending db migrations.
+	migration::migrate(&mm).await?;
+
+	// Relay the outbox domain events (to the log for now).
+	let outbox_relay = OutboxRelay::new(mm.clone(), vec![LogSink.into()]);
+	tokio::spawn(outbox_relay.run());
+
+	// Listen to the db change notifications (of all the service instances).
+	let change_feed = ChangeFeed::start().await?;
+
 	// -- Define Routes
 	let rpc_state = RpcState { mm: mm.clone() };
 	let routes_rpc = web::routes_rpc::routes(rpc_state)
 		.route_layer(middleware::from_fn(mw_ctx_require));
+	let routes_attachment = routes_attachment::routes(mm.clone())
+		.route_layer(middleware::from_fn(mw_ctx_require));
+	let changes_state = ChangesState {
+		mm: mm.clone(),
+		change_feed,
+	};
+	let routes_changes = routes_changes::routes(changes_state)
+		.route_layer(middleware::from_fn(mw_ctx_require));
 
 	let routes_all = Router::new()
 		.merge(routes_login::routes(mm.clone()))
-		.nest("/api", routes_rpc)
+		.nest(
+			"/api",
+			routes_rpc.merge(routes_attachment).merge(routes_changes),
+		)
 		.layer(middleware::map_response(mw_reponse_map))
 		.layer(middleware::from_fn_with_state(mm.clone(), mw_ctx_resolve))
 		.layer(middleware::from_fn(mw_req_stamp))
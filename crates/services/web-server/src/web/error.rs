@@ -1,9 +1,11 @@
 use crate::web;
+use axum::extract::multipart::MultipartError;
 use axum::http::StatusCode;
 use axum::response::{IntoResponse, Response};
 use derive_more::From;
 use lib_auth::{pwd, token};
-use lib_core::model;
+use lib_core::{ctx, model};
+use lib_utils::time::format_time;
 use serde::Serialize;
 use serde_with::{serde_as, DisplayFromStr};
 use std::sync::Arc;
@@ -31,8 +33,14 @@ pub enum Error {
 	// -- ReqStamp
 	ReqStampNotInResponseExt,
 
+	// -- Attachment
+	/// The upload multipart has no `file` field.
+	AttachmentUploadNoFile,
+
 	// -- Modules
 	#[from]
+	Ctx(ctx::Error),
+	#[from]
 	Model(model::Error),
 	#[from]
 	Pwd(pwd::Error),
@@ -44,6 +52,8 @@ pub enum Error {
 	// -- External Modules
 	#[from]
 	SerdeJson(#[serde_as(as = "DisplayFromStr")] serde_json::Error),
+	#[from]
+	Multipart(#[serde_as(as = "DisplayFromStr")] MultipartError),
 }
 
 // region:    --- Axum IntoResponse
@@ -93,12 +103,174 @@ impl Error {
 			// -- Auth
 			CtxExt(_) => (StatusCode::FORBIDDEN, ClientError::NO_AUTH),
 
-			// -- Model
-			Model(model::Error::EntityNotFound { entity, id }) => (
+			// -- Attachment
+			AttachmentUploadNoFile | Multipart(_) => (
 				StatusCode::BAD_REQUEST,
-				ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+				ClientError::ATTACHMENT_UPLOAD_INVALID,
 			),
 
+			// -- Model
+			// Note: Model errors can come directly or through the rpc layer.
+			Model(model_error) | Rpc(lib_rpc::Error::Model(model_error)) => {
+				match model_error {
+					model::Error::EntityNotFound { entity, id } => (
+						StatusCode::BAD_REQUEST,
+						ClientError::ENTITY_NOT_FOUND { entity, id: *id },
+					),
+					model::Error::AccessDenied { entity, id } => (
+						StatusCode::FORBIDDEN,
+						ClientError::ACCESS_DENIED { entity, id: *id },
+					),
+					model::Error::ConcurrentModification {
+						entity,
+						id,
+						current_mtime,
+					} => (
+						StatusCode::CONFLICT,
+						ClientError::CONCURRENT_MODIFICATION {
+							entity,
+							id: *id,
+							current_mtime: format_time(*current_mtime),
+						},
+					),
+					model::Error::ListCursorInvalid => {
+						(StatusCode::BAD_REQUEST, ClientError::LIST_CURSOR_INVALID)
+					}
+					model::Error::CtxNoWorkspace => {
+						(StatusCode::BAD_REQUEST, ClientError::CTX_NO_WORKSPACE)
+					}
+					model::Error::WorkspaceNotEmpty { id } => (
+						StatusCode::CONFLICT,
+						ClientError::WORKSPACE_NOT_EMPTY { id: *id },
+					),
+					model::Error::WorkspaceLastOwner { workspace_id } => (
+						StatusCode::CONFLICT,
+						ClientError::WORKSPACE_LAST_OWNER {
+							workspace_id: *workspace_id,
+						},
+					),
+					model::Error::UsernameEmpty => {
+						(StatusCode::BAD_REQUEST, ClientError::USERNAME_EMPTY)
+					}
+					model::Error::UsernameTooLong { max } => (
+						StatusCode::BAD_REQUEST,
+						ClientError::USERNAME_TOO_LONG { max: *max },
+					),
+					model::Error::UsernameAlreadyExists { username } => (
+						StatusCode::CONFLICT,
+						ClientError::USERNAME_ALREADY_EXISTS {
+							username: username.to_string(),
+						},
+					),
+					model::Error::UserPwdEmpty => {
+						(StatusCode::BAD_REQUEST, ClientError::USER_PWD_EMPTY)
+					}
+					model::Error::UserPwdNotMatching { .. } => {
+						(StatusCode::FORBIDDEN, ClientError::USER_PWD_NOT_MATCHING)
+					}
+					model::Error::ProjectMemberAlreadyExists {
+						project_id,
+						user_id,
+					} => (
+						StatusCode::CONFLICT,
+						ClientError::PROJECT_MEMBER_ALREADY_EXISTS {
+							project_id: *project_id,
+							user_id: *user_id,
+						},
+					),
+					model::Error::ProjectMemberNotInWorkspace {
+						project_id,
+						user_id,
+					} => (
+						StatusCode::BAD_REQUEST,
+						ClientError::PROJECT_MEMBER_NOT_IN_WORKSPACE {
+							project_id: *project_id,
+							user_id: *user_id,
+						},
+					),
+					model::Error::TaskMoveSiblingInvalid { id, sibling_id } => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_MOVE_SIBLING_INVALID {
+							id: *id,
+							sibling_id: *sibling_id,
+						},
+					),
+					model::Error::TaskParentNotInProject {
+						parent_id,
+						project_id,
+					} => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_PARENT_NOT_IN_PROJECT {
+							parent_id: *parent_id,
+							project_id: *project_id,
+						},
+					),
+					model::Error::TaskParentCycle { id, parent_id } => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_PARENT_CYCLE {
+							id: *id,
+							parent_id: *parent_id,
+						},
+					),
+					model::Error::TaskDependencyCycle {
+						task_id,
+						blocker_id,
+					} => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_DEPENDENCY_CYCLE {
+							task_id: *task_id,
+							blocker_id: *blocker_id,
+						},
+					),
+					model::Error::TaskBlocked { id, blocker_id } => (
+						StatusCode::CONFLICT,
+						ClientError::TASK_BLOCKED {
+							id: *id,
+							blocker_id: *blocker_id,
+						},
+					),
+					model::Error::TaskOpenSubtask { id, subtask_id } => (
+						StatusCode::CONFLICT,
+						ClientError::TASK_OPEN_SUBTASK {
+							id: *id,
+							subtask_id: *subtask_id,
+						},
+					),
+					model::Error::TaskRecurrenceInvalid { recurrence, cause } => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_RECURRENCE_INVALID {
+							recurrence: recurrence.to_string(),
+							cause,
+						},
+					),
+					model::Error::TaskRecurrenceNoDueDate => (
+						StatusCode::BAD_REQUEST,
+						ClientError::TASK_RECURRENCE_NO_DUE_DATE,
+					),
+					model::Error::LabelNameAlreadyExists { project_id, name } => (
+						StatusCode::CONFLICT,
+						ClientError::LABEL_NAME_ALREADY_EXISTS {
+							project_id: *project_id,
+							name: name.to_string(),
+						},
+					),
+					model::Error::LabelNotInProject {
+						label_id,
+						project_id,
+					} => (
+						StatusCode::BAD_REQUEST,
+						ClientError::LABEL_NOT_IN_PROJECT {
+							label_id: *label_id,
+							project_id: *project_id,
+						},
+					),
+					_ => (
+						StatusCode::INTERNAL_SERVER_ERROR,
+						ClientError::SERVICE_ERROR,
+					),
+				}
+			}
+
 			// -- Fallback.
 			_ => (
 				StatusCode::INTERNAL_SERVER_ERROR,
@@ -114,7 +286,82 @@ impl Error {
 pub enum ClientError {
 	LOGIN_FAIL,
 	NO_AUTH,
-	ENTITY_NOT_FOUND { entity: &'static str, id: i64 },
+	ENTITY_NOT_FOUND {
+		entity: &'static str,
+		id: i64,
+	},
+	ACCESS_DENIED {
+		entity: &'static str,
+		id: i64,
+	},
+	CONCURRENT_MODIFICATION {
+		entity: &'static str,
+		id: i64,
+		current_mtime: String,
+	},
+	LIST_CURSOR_INVALID,
+	ATTACHMENT_UPLOAD_INVALID,
+	CTX_NO_WORKSPACE,
+	WORKSPACE_NOT_EMPTY {
+		id: i64,
+	},
+	WORKSPACE_LAST_OWNER {
+		workspace_id: i64,
+	},
+	USERNAME_EMPTY,
+	USERNAME_TOO_LONG {
+		max: usize,
+	},
+	USERNAME_ALREADY_EXISTS {
+		username: String,
+	},
+	USER_PWD_EMPTY,
+	USER_PWD_NOT_MATCHING,
+	PROJECT_MEMBER_ALREADY_EXISTS {
+		project_id: i64,
+		user_id: i64,
+	},
+	PROJECT_MEMBER_NOT_IN_WORKSPACE {
+		project_id: i64,
+		user_id: i64,
+	},
+	TASK_MOVE_SIBLING_INVALID {
+		id: i64,
+		sibling_id: i64,
+	},
+	TASK_PARENT_NOT_IN_PROJECT {
+		parent_id: i64,
+		project_id: i64,
+	},
+	TASK_PARENT_CYCLE {
+		id: i64,
+		parent_id: i64,
+	},
+	TASK_DEPENDENCY_CYCLE {
+		task_id: i64,
+		blocker_id: i64,
+	},
+	TASK_BLOCKED {
+		id: i64,
+		blocker_id: i64,
+	},
+	TASK_OPEN_SUBTASK {
+		id: i64,
+		subtask_id: i64,
+	},
+	TASK_RECURRENCE_INVALID {
+		recurrence: String,
+		cause: &'static str,
+	},
+	TASK_RECURRENCE_NO_DUE_DATE,
+	LABEL_NAME_ALREADY_EXISTS {
+		project_id: i64,
+		name: String,
+	},
+	LABEL_NOT_IN_PROJECT {
+		label_id: i64,
+		project_id: i64,
+	},
 
 	SERVICE_ERROR,
 }
@@ -4,6 +4,8 @@ mod error;
 pub mod mw_auth;
 pub mod mw_res_map;
 pub mod mw_stamp;
+pub mod routes_attachment;
+pub mod routes_changes;
 pub mod routes_login;
 pub mod routes_rpc;
 pub mod routes_static;
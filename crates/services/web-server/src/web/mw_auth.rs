@@ -4,17 +4,22 @@ use async_trait::async_trait;
 use axum::body::Body;
 use axum::extract::{FromRequestParts, State};
 use axum::http::request::Parts;
-use axum::http::Request;
+use axum::http::{HeaderMap, Request};
 use axum::middleware::Next;
 use axum::response::Response;
 use lib_auth::token::{validate_web_token, Token};
 use lib_core::ctx::Ctx;
 use lib_core::model::user::{UserBmc, UserForAuth};
+use lib_core::model::workspace::WorkspaceBmc;
 use lib_core::model::ModelManager;
 use serde::Serialize;
 use tower_cookies::{Cookie, Cookies};
 use tracing::debug;
 
+/// The request header of the active workspace id
+/// (the user default workspace when absent).
+pub const WORKSPACE_ID_HEADER: &str = "x-workspace-id";
+
 pub async fn mw_ctx_require(
 	ctx: Result<CtxW>,
 	req: Request<Body>,
@@ -35,11 +40,17 @@ pub async fn mw_ctx_resolve(
 ) -> Result<Response> {
 	debug!("{:<12} - mw_ctx_resolve", "MIDDLEWARE");
 
-	let ctx_ext_result = _ctx_resolve(mm, &cookies).await;
+	let ctx_ext_result = _ctx_resolve(mm, &cookies, req.headers()).await;
 
+	// Note: The token cookie stays for the workspace errors
+	//       (the user is authenticated, but asked for a wrong workspace).
 	if ctx_ext_result.is_err()
-		&& !matches!(ctx_ext_result, Err(CtxExtError::TokenNotInCookie))
-	{
+		&& !matches!(
+			ctx_ext_result,
+			Err(CtxExtError::TokenNotInCookie
+				| CtxExtError::WorkspaceIdWrongFormat
+				| CtxExtError::WorkspaceNotAccessible(_))
+		) {
 		cookies.remove(Cookie::from(AUTH_TOKEN))
 	}
 
@@ -50,7 +61,11 @@ pub async fn mw_ctx_resolve(
 	Ok(next.run(req).await)
 }
 
-async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResult {
+async fn _ctx_resolve(
+	mm: State<ModelManager>,
+	cookies: &Cookies,
+	headers: &HeaderMap,
+) -> CtxExtResult {
 	// -- Get Token String
 	let token = cookies
 		.get(AUTH_TOKEN)
@@ -75,10 +90,35 @@ async fn _ctx_resolve(mm: State<ModelManager>, cookies: &Cookies) -> CtxExtResul
 	set_token_cookie(cookies, &user.username, user.token_salt)
 		.map_err(|_| CtxExtError::CannotSetTokenCookie)?;
 
+	// -- Create Ctx
+	let ctx = Ctx::new(user.id)
+		.map_err(|ex| CtxExtError::CtxCreateFail(ex.to_string()))?;
+
+	// -- Resolve the active workspace
+	let workspace_id = match headers.get(WORKSPACE_ID_HEADER) {
+		Some(workspace_id) => {
+			let workspace_id: i64 = workspace_id
+				.to_str()
+				.ok()
+				.and_then(|id| id.parse().ok())
+				.ok_or(CtxExtError::WorkspaceIdWrongFormat)?;
+			WorkspaceBmc::get(&ctx, &mm, workspace_id)
+				.await
+				.map_err(|ex| CtxExtError::WorkspaceNotAccessible(ex.to_string()))?;
+			Some(workspace_id)
+		}
+		None => WorkspaceBmc::default_id(&ctx, &mm)
+			.await
+			.map_err(|ex| CtxExtError::ModelAccessError(ex.to_string()))?,
+	};
+
 	// -- Create CtxExtResult
-	Ctx::new(user.id)
-		.map(CtxW)
-		.map_err(|ex| CtxExtError::CtxCreateFail(ex.to_string()))
+	let ctx = match workspace_id {
+		Some(workspace_id) => ctx.add_workspace_id(workspace_id),
+		None => ctx,
+	};
+
+	Ok(CtxW(ctx))
 }
 
 // region:    --- Ctx Extractor
@@ -117,5 +157,8 @@ pub enum CtxExtError {
 
 	CtxNotInRequestExt,
 	CtxCreateFail(String),
+
+	WorkspaceIdWrongFormat,
+	WorkspaceNotAccessible(String),
 }
 // endregion: --- Ctx Extractor Result/Error
@@ -0,0 +1,112 @@
+use crate::web::mw_auth::CtxW;
+use crate::web::{Error, Result};
+use axum::body::Body;
+use axum::extract::{DefaultBodyLimit, Multipart, Path, State};
+use axum::http::header::{CONTENT_DISPOSITION, CONTENT_LENGTH, CONTENT_TYPE};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use futures::TryStreamExt;
+use lib_core::model::attachment::{AttachmentBmc, AttachmentForCreate};
+use lib_core::model::ModelManager;
+use serde_json::{json, Value};
+use tokio_util::io::{ReaderStream, StreamReader};
+use tracing::debug;
+
+/// The multipart field name of the uploaded file.
+const FILE_FIELD: &str = "file";
+
+/// The content type when the upload does not give one.
+const DEFAULT_CONTENT_TYPE: &str = "application/octet-stream";
+
+/// The max upload body size (i.e., the attachment size, plus the multipart overhead).
+const UPLOAD_MAX_SIZE: usize = 50 * 1024 * 1024;
+
+// Axum router for '/api' attachment routes (requiring the ctx).
+pub fn routes(mm: ModelManager) -> Router {
+	Router::new()
+		.route("/tasks/:task_id/attachments", post(upload_handler))
+		.route("/attachments/:id/content", get(download_handler))
+		.layer(DefaultBodyLimit::max(UPLOAD_MAX_SIZE))
+		.with_state(mm)
+}
+
+/// Upload the multipart `file` field as an attachment of the task
+/// (streamed to the blob store).
+async fn upload_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	Path(task_id): Path<i64>,
+	mut multipart: Multipart,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - upload_handler", "HANDLER");
+	let mm = mm.new_for_request();
+
+	let ctx = ctx.0;
+
+	while let Some(field) = multipart.next_field().await? {
+		if field.name() != Some(FILE_FIELD) {
+			continue;
+		}
+
+		let attachment_c = AttachmentForCreate {
+			task_id,
+			file_name: field.file_name().unwrap_or(FILE_FIELD).to_string(),
+			content_type: field
+				.content_type()
+				.unwrap_or(DEFAULT_CONTENT_TYPE)
+				.to_string(),
+		};
+		let content = StreamReader::new(field.map_err(std::io::Error::other));
+
+		// Note: Not in a transaction, as the upload can be long
+		//       (see `AttachmentBmc::create`).
+		let id = AttachmentBmc::create(&ctx, &mm, attachment_c, content).await?;
+		let attachment = AttachmentBmc::get(&ctx, &mm, id).await?;
+
+		return Ok(Json(json!({
+			"result": attachment
+		})));
+	}
+
+	Err(Error::AttachmentUploadNoFile)
+}
+
+/// Stream the attachment content, as a file download.
+async fn download_handler(
+	State(mm): State<ModelManager>,
+	ctx: CtxW,
+	Path(id): Path<i64>,
+) -> Result<Response> {
+	debug!("{:<12} - download_handler", "HANDLER");
+	let mm = mm.new_for_request();
+
+	let (attachment, content) = AttachmentBmc::open(&ctx.0, &mm, id).await?;
+
+	let headers = [
+		(CONTENT_TYPE, attachment.content_type),
+		(CONTENT_LENGTH, attachment.size.to_string()),
+		(
+			CONTENT_DISPOSITION,
+			format!(
+				"attachment; filename=\"{}\"",
+				header_safe_file_name(&attachment.file_name)
+			),
+		),
+	];
+
+	Ok((headers, Body::from_stream(ReaderStream::new(content))).into_response())
+}
+
+/// Returns the file name with only the header-safe characters
+/// (i.e., printable ascii, without the quotes and backslashes).
+fn header_safe_file_name(file_name: &str) -> String {
+	file_name
+		.chars()
+		.map(|c| match c {
+			'"' | '\\' => '_',
+			c if c.is_ascii_graphic() || c == ' ' => c,
+			_ => '_',
+		})
+		.collect()
+}
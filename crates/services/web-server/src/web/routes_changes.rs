@@ -0,0 +1,82 @@
+use crate::web::mw_auth::CtxW;
+use axum::extract::State;
+use axum::response::sse::{Event, KeepAlive, Sse};
+use axum::routing::get;
+use axum::Router;
+use futures::{stream, Stream};
+use lib_core::ctx::Ctx;
+use lib_core::model::change_feed::{ChangeFeed, EntityChange};
+use lib_core::model::ModelManager;
+use tokio::sync::broadcast::error::RecvError;
+use tokio::sync::broadcast::Receiver;
+use tracing::{debug, error};
+
+/// The SSE event name of an `EntityChange` (as json data).
+const CHANGE_EVENT: &str = "change";
+/// The SSE event name of dropped changes (with their count as data),
+/// after which the client should refetch its data.
+const LAGGED_EVENT: &str = "lagged";
+
+#[derive(Clone)]
+pub struct ChangesState {
+	pub mm: ModelManager,
+	pub change_feed: ChangeFeed,
+}
+
+// Axum router for '/api/changes' (requiring the ctx).
+pub fn routes(changes_state: ChangesState) -> Router {
+	Router::new()
+		.route("/changes", get(changes_handler))
+		.with_state(changes_state)
+}
+
+/// Stream (as Server-Sent Events) the changes of the projects, and their tasks,
+/// readable by the ctx user, from the connection on.
+async fn changes_handler(
+	State(changes_state): State<ChangesState>,
+	ctx: CtxW,
+) -> Sse<impl Stream<Item = Result<Event, axum::Error>>> {
+	debug!("{:<12} - changes_handler", "HANDLER");
+
+	let ChangesState { mm, change_feed } = changes_state;
+	let mm = mm.new_for_request();
+	let change_rx = change_feed.subscribe();
+
+	let events = stream::unfold(
+		(change_rx, ctx.0, mm),
+		|(mut change_rx, ctx, mm)| async move {
+			let event = next_event(&mut change_rx, &ctx, &mm).await?;
+			Some((event, (change_rx, ctx, mm)))
+		},
+	);
+
+	Sse::new(events).keep_alive(KeepAlive::default())
+}
+
+/// Returns the next event for the ctx user,
+/// or `None` when the change feed is closed (i.e., ending the stream).
+async fn next_event(
+	change_rx: &mut Receiver<EntityChange>,
+	ctx: &Ctx,
+	mm: &ModelManager,
+) -> Option<Result<Event, axum::Error>> {
+	loop {
+		let change = match change_rx.recv().await {
+			Ok(change) => change,
+			Err(RecvError::Lagged(count)) => {
+				let event =
+					Event::default().event(LAGGED_EVENT).data(count.to_string());
+				return Some(Ok(event));
+			}
+			Err(RecvError::Closed) => return None,
+		};
+
+		match change.is_visible(ctx, mm).await {
+			Ok(true) => {
+				return Some(Event::default().event(CHANGE_EVENT).json_data(change))
+			}
+			Ok(false) => (),
+			Err(ex) => error!("{:<12} - visible check fail - {ex:?}", "CHANGES"),
+		}
+	}
+}
@@ -4,7 +4,8 @@ use axum::routing::post;
 use axum::{Json, Router};
 use lib_auth::pwd::{self, ContentToHash, SchemeStatus};
 use lib_core::ctx::Ctx;
-use lib_core::model::user::{UserBmc, UserForLogin};
+use lib_core::model::user::{User, UserBmc, UserForCreate, UserForLogin};
+use lib_core::model::workspace::{WorkspaceBmc, WorkspaceForCreate};
 use lib_core::model::ModelManager;
 use serde::Deserialize;
 use serde_json::{json, Value};
@@ -13,11 +14,68 @@ use tracing::debug;
 
 pub fn routes(mm: ModelManager) -> Router {
 	Router::new()
+		.route("/api/signup", post(api_signup_handler))
 		.route("/api/login", post(api_login_handler))
 		.route("/api/logoff", post(api_logoff_handler))
 		.with_state(mm)
 }
 
+// region:    --- Signup
+/// Create the user, with its personal workspace,
+/// and log it in (i.e., set its web token).
+async fn api_signup_handler(
+	State(mm): State<ModelManager>,
+	cookies: Cookies,
+	Json(payload): Json<SignupPayload>,
+) -> Result<Json<Value>> {
+	debug!("{:<12} - api_signup_handler", "HANDLER");
+	let mm = mm.new_for_request();
+
+	let SignupPayload {
+		username,
+		pwd: pwd_clear,
+	} = payload;
+	let root_ctx = Ctx::root_ctx();
+
+	// -- Create the user, with its personal workspace.
+	let workspace_c = WorkspaceForCreate {
+		name: username.to_string(),
+	};
+	let user_c = UserForCreate {
+		username,
+		pwd_clear,
+	};
+	let user_id = mm
+		.in_txn(|mm| async move {
+			let user_id = UserBmc::create(&Ctx::root_ctx(), &mm, user_c).await?;
+			WorkspaceBmc::create(&Ctx::new(user_id)?, &mm, workspace_c).await?;
+			Ok::<_, Error>(user_id)
+		})
+		.await?;
+	let user: UserForLogin = UserBmc::get(&root_ctx, &mm, user_id).await?;
+
+	// -- Set web token.
+	web::set_token_cookie(&cookies, &user.username, user.token_salt)?;
+
+	// Create the success body.
+	let user = User {
+		id: user.id,
+		username: user.username,
+	};
+	let body = Json(json!({
+		"result": user
+	}));
+
+	Ok(body)
+}
+
+#[derive(Debug, Deserialize)]
+struct SignupPayload {
+	username: String,
+	pwd: String,
+}
+// endregion: --- Signup
+
 // region:    --- Login
 async fn api_login_handler(
 	State(mm): State<ModelManager>,
@@ -25,6 +83,7 @@ async fn api_login_handler(
 	Json(payload): Json<LoginPayload>,
 ) -> Result<Json<Value>> {
 	debug!("{:<12} - api_login_handler", "HANDLER");
+	let mm = mm.new_for_request();
 
 	let LoginPayload {
 		username,
@@ -5,7 +5,10 @@ use axum::routing::post;
 use axum::{Json, Router};
 use lib_core::model::ModelManager;
 use lib_rpc::router::RpcRouter;
-use lib_rpc::{project_rpc, task_rpc, RpcRequest, RpcResources};
+use lib_rpc::{
+	entity_history_rpc, label_rpc, project_member_rpc, project_rpc, search_rpc,
+	task_comment_rpc, task_rpc, user_rpc, workspace_rpc, RpcRequest, RpcResources,
+};
 use serde_json::{json, Value};
 use std::sync::Arc;
 
@@ -31,7 +34,14 @@ pub fn routes(rpc_state: RpcState) -> Router {
 	// Build the combined RpcRouter.
 	let rpc_router = RpcRouter::new()
 		.extend(task_rpc::rpc_router())
-		.extend(project_rpc::rpc_router());
+		.extend(task_comment_rpc::rpc_router())
+		.extend(project_rpc::rpc_router())
+		.extend(project_member_rpc::rpc_router())
+		.extend(label_rpc::rpc_router())
+		.extend(entity_history_rpc::rpc_router())
+		.extend(search_rpc::rpc_router())
+		.extend(user_rpc::rpc_router())
+		.extend(workspace_rpc::rpc_router());
 
 	// Build the Axum Router for '/rpc'
 	Router::new()
@@ -54,9 +64,10 @@ async fn rpc_axum_handler(
 	};
 	let rpc_method = &rpc_info.method;
 	let rpc_params = rpc_req.params;
+	// Note: A request ModelManager, to read its own writes (see read replicas).
 	let rpc_resources = RpcResources {
 		ctx: Some(ctx),
-		mm: rpc_state.mm,
+		mm: rpc_state.mm.new_for_request(),
 	};
 
 	// -- Exec Rpc Route
@@ -0,0 +1,28 @@
+use anyhow::Result;
+use lib_core::model::{migration, ModelManager};
+use tracing_subscriber::EnvFilter;
+
+/// Apply the pending db migrations of the `SERVICE_MIGRATIONS_DIR` dir
+/// to the `SERVICE_DB_URL` db.
+#[tokio::main]
+async fn main() -> Result<()> {
+	tracing_subscriber::fmt()
+		.without_time()
+		.with_target(false)
+		.with_env_filter(EnvFilter::from_default_env())
+		.init();
+
+	let mm = ModelManager::new().await?;
+	let applied = migration::migrate(&mm).await?;
+
+	if applied.is_empty() {
+		println!("\nNo pending migration.");
+	} else {
+		println!("\nApplied migrations:");
+		for migration in applied {
+			println!("  {:>4} - {}", migration.version, migration.description);
+		}
+	}
+
+	Ok(())
+}
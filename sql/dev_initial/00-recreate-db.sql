@@ -1,9 +1,21 @@
 -- DEV ONLY - Brute Force DROP DB (for local dev and unit test)
 SELECT pg_terminate_backend(pid) FROM pg_stat_activity WHERE
- usename = 'app_user' OR datname = 'app_db';
+ usename = 'app_user' OR datname IN ('app_db', 'app_db_replica');
 DROP DATABASE IF EXISTS app_db;
+DROP DATABASE IF EXISTS app_db_replica;
 DROP USER IF EXISTS app_user;
+DROP ROLE IF EXISTS app_rls;
+DROP ROLE IF EXISTS app_rls_bypass;
+
+-- The row-level security roles, which app_user switches to per transaction
+-- (see `SERVICE_DB_RLS` and `sql/migrations/0003_row_level_security.sql`).
+CREATE ROLE app_rls NOLOGIN;
+CREATE ROLE app_rls_bypass NOLOGIN BYPASSRLS;
 
 -- DEV ONLY - Dev only password (for local dev and unit test).
 CREATE USER app_user PASSWORD 'dev_only_pwd';
-CREATE DATABASE app_db owner app_user ENCODING = 'UTF-8';
\ No newline at end of file
+GRANT app_rls, app_rls_bypass TO app_user;
+CREATE DATABASE app_db owner app_user ENCODING = 'UTF-8';
+
+-- DEV ONLY - The read replica stand-in (for the read replica routing unit test).
+CREATE DATABASE app_db_replica owner app_user ENCODING = 'UTF-8';
@@ -6,4 +6,21 @@ INSERT INTO "user"
 -- User demo1
 INSERT INTO "user" 
     (username, cid, ctime, mid, mtime) VALUES 
-    ('demo1',  0,   now(), 0,   now());
\ No newline at end of file
+    ('demo1',  0,   now(), 0,   now());
+
+-- User demo2
+INSERT INTO "user" 
+    (username, cid, ctime, mid, mtime) VALUES 
+    ('demo2',  0,   now(), 0,   now());
+
+-- Workspace Demo (owned by demo1, with demo2 as member)
+INSERT INTO workspace 
+    (name,   cid, ctime, mid, mtime) VALUES 
+    ('Demo', 0,   now(), 0,   now());
+
+INSERT INTO workspace_member 
+    (workspace_id, user_id, role, cid, ctime, mid, mtime)
+    SELECT w.id, u.id, CASE u.username WHEN 'demo1' THEN 'Owner' ELSE 'Member' END,
+        0, now(), 0, now()
+    FROM workspace w, "user" u
+    WHERE w.name = 'Demo' AND u.username IN ('demo1', 'demo2');
@@ -0,0 +1,283 @@
+---- Base app schema
+
+-- User
+CREATE TABLE "user" (
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  username varchar(128) NOT NULL UNIQUE,
+
+  -- Auth
+  pwd varchar(256),
+  pwd_salt uuid NOT NULL DEFAULT gen_random_uuid(),
+  token_salt uuid NOT NULL DEFAULT gen_random_uuid(),
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL  
+);
+
+-- Project
+CREATE TABLE project (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  owner_id BIGINT NOT NULL,
+  name varchar(256) NOT NULL,
+
+  -- Full-text search (see `model::search`)
+  search_tsv tsvector GENERATED ALWAYS AS (to_tsvector('english', name)) STORED,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  -- Soft delete (deleter user_id/time, when in the trash)
+  did bigint,
+  dtime timestamp with time zone
+);
+
+CREATE INDEX idx_project_search_tsv ON project USING GIN (search_tsv);
+
+-- Task
+CREATE TABLE task (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  project_id BIGINT NOT NULL,
+  parent_id BIGINT,
+  
+  -- Properties
+  title varchar(256) NOT NULL,
+  description text,
+  due_date timestamp with time zone,
+  priority varchar(32) NOT NULL DEFAULT 'Medium',
+  status varchar(32) NOT NULL DEFAULT 'Todo',
+  -- Derived from the status (for the clients predating it)
+  done bool GENERATED ALWAYS AS (status = 'Done') STORED,
+  -- Position in the project (see `TaskBmc::move_task`)
+  rank double precision NOT NULL,
+  -- RRULE-style recurrence (see `model::recurrence`)
+  recurrence varchar(256),
+  -- Generated when the recurring task is done
+  next_occurrence_id BIGINT,
+
+  -- Full-text search (see `model::search`)
+  search_tsv tsvector GENERATED ALWAYS AS (
+    to_tsvector('english', title || ' ' || coalesce(description, ''))
+  ) STORED,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  -- Soft delete (deleter user_id/time, when in the trash)
+  did bigint,
+  dtime timestamp with time zone
+);
+
+ALTER TABLE task ADD CONSTRAINT fk_project
+  FOREIGN KEY (project_id) REFERENCES project(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE task ADD CONSTRAINT fk_parent
+  FOREIGN KEY (parent_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE task ADD CONSTRAINT fk_next_occurrence
+  FOREIGN KEY (next_occurrence_id) REFERENCES task(id)
+  ON DELETE SET NULL;
+
+CREATE INDEX idx_task_search_tsv ON task USING GIN (search_tsv);
+CREATE INDEX idx_task_project_rank ON task (project_id, rank);
+CREATE INDEX idx_task_parent_id ON task (parent_id);
+
+-- ProjectMember
+CREATE TABLE project_member (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  project_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL,
+
+  -- Properties
+  role varchar(32) NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (project_id, user_id)
+);
+
+ALTER TABLE project_member ADD CONSTRAINT fk_project
+  FOREIGN KEY (project_id) REFERENCES project(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE project_member ADD CONSTRAINT fk_user
+  FOREIGN KEY (user_id) REFERENCES "user"(id)
+  ON DELETE CASCADE;
+
+-- TaskDependency
+--   (the `task_id` task is blocked by the `blocker_id` task)
+CREATE TABLE task_dependency (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  task_id BIGINT NOT NULL,
+  blocker_id BIGINT NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (task_id, blocker_id)
+);
+
+CREATE INDEX idx_task_dependency_blocker_id ON task_dependency (blocker_id);
+
+ALTER TABLE task_dependency ADD CONSTRAINT fk_task
+  FOREIGN KEY (task_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE task_dependency ADD CONSTRAINT fk_blocker
+  FOREIGN KEY (blocker_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+-- TaskComment
+--   (the author is the creator, i.e., the `cid`)
+CREATE TABLE task_comment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  task_id BIGINT NOT NULL,
+
+  -- Properties
+  content text NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_task_comment_task_id ON task_comment (task_id);
+
+ALTER TABLE task_comment ADD CONSTRAINT fk_task
+  FOREIGN KEY (task_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+-- Attachment
+--   (the file content is in the blob store, by `blob_key`)
+CREATE TABLE attachment (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  task_id BIGINT NOT NULL,
+
+  -- Properties
+  file_name varchar(256) NOT NULL,
+  content_type varchar(256) NOT NULL,
+  size bigint NOT NULL,
+  blob_key varchar(128) NOT NULL UNIQUE,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_attachment_task_id ON attachment (task_id);
+
+ALTER TABLE attachment ADD CONSTRAINT fk_task
+  FOREIGN KEY (task_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+-- Label
+--   (per project, attached to the tasks through the task_label table)
+CREATE TABLE label (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FK
+  project_id BIGINT NOT NULL,
+
+  -- Properties
+  name varchar(128) NOT NULL,
+  color varchar(32),
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (project_id, name)
+);
+
+ALTER TABLE label ADD CONSTRAINT fk_project
+  FOREIGN KEY (project_id) REFERENCES project(id)
+  ON DELETE CASCADE;
+
+CREATE TABLE task_label (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  task_id BIGINT NOT NULL,
+  label_id BIGINT NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (task_id, label_id)
+);
+
+CREATE INDEX idx_task_label_label_id ON task_label (label_id);
+
+ALTER TABLE task_label ADD CONSTRAINT fk_task
+  FOREIGN KEY (task_id) REFERENCES task(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE task_label ADD CONSTRAINT fk_label
+  FOREIGN KEY (label_id) REFERENCES label(id)
+  ON DELETE CASCADE;
+
+-- EntityHistory
+--   (audit trail of the model writes, immutable rows)
+CREATE TABLE entity_history (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  entity varchar(128) NOT NULL,
+  entity_id BIGINT NOT NULL,
+  action varchar(32) NOT NULL,
+  diff jsonb NOT NULL,
+
+  -- Timestamps (acting user_id/time)
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL
+);
+
+CREATE INDEX idx_entity_history_entity ON entity_history (entity, entity_id);
@@ -0,0 +1,71 @@
+---- Workspaces (the organizations owning the projects)
+
+-- Workspace
+CREATE TABLE workspace (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- Properties
+  name varchar(256) NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL
+);
+
+-- WorkspaceMember
+CREATE TABLE workspace_member (
+  -- PK
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY (START WITH 1000) PRIMARY KEY,
+
+  -- FKs
+  workspace_id BIGINT NOT NULL,
+  user_id BIGINT NOT NULL,
+
+  -- Properties
+  role varchar(32) NOT NULL,
+
+  -- Timestamps
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  mid bigint NOT NULL,
+  mtime timestamp with time zone NOT NULL,
+
+  UNIQUE (workspace_id, user_id)
+);
+
+CREATE INDEX idx_workspace_member_user_id ON workspace_member (user_id);
+
+ALTER TABLE workspace_member ADD CONSTRAINT fk_workspace
+  FOREIGN KEY (workspace_id) REFERENCES workspace(id)
+  ON DELETE CASCADE;
+
+ALTER TABLE workspace_member ADD CONSTRAINT fk_user
+  FOREIGN KEY (user_id) REFERENCES "user"(id)
+  ON DELETE CASCADE;
+
+-- Project workspace
+ALTER TABLE project ADD COLUMN workspace_id BIGINT;
+
+-- The existing projects go in a "Default" workspace, with all the users
+-- as members (i.e., as in the previous global namespace).
+INSERT INTO workspace (name, cid, ctime, mid, mtime)
+  SELECT 'Default', 0, now(), 0, now()
+  WHERE EXISTS (SELECT 1 FROM project);
+
+INSERT INTO workspace_member (workspace_id, user_id, role, cid, ctime, mid, mtime)
+  SELECT w.id, u.id, 'Member', 0, now(), 0, now()
+  FROM workspace w, "user" u
+  WHERE w.name = 'Default' AND u.id <> 0;
+
+UPDATE project SET workspace_id = (SELECT id FROM workspace WHERE name = 'Default');
+
+ALTER TABLE project ALTER COLUMN workspace_id SET NOT NULL;
+
+CREATE INDEX idx_project_workspace_id ON project (workspace_id);
+
+ALTER TABLE project ADD CONSTRAINT fk_workspace
+  FOREIGN KEY (workspace_id) REFERENCES workspace(id)
+  ON DELETE CASCADE;
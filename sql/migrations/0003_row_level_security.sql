@@ -0,0 +1,50 @@
+---- Row-level security on the project and task tables
+
+-- The policies read the ctx of the current transaction from the
+-- `app.user_id` and `app.workspace_id` settings (see `Dbx` RLS session).
+--
+-- Note: They apply to the `app_rls` role only, since the table owner
+--       (i.e., app_user, running the migrations and the dev seed)
+--       and the `app_rls_bypass` role (i.e., the root ctx) bypass them.
+
+ALTER TABLE project ENABLE ROW LEVEL SECURITY;
+
+-- The projects of the ctx workspace, owned by the ctx user or of which
+-- the ctx user is a member (any role, the roles are checked by the model layer).
+CREATE POLICY project_ctx ON project
+  USING (
+    workspace_id = NULLIF(current_setting('app.workspace_id', true), '')::bigint
+    AND (
+      owner_id = NULLIF(current_setting('app.user_id', true), '')::bigint
+      OR id IN (
+        SELECT project_id FROM project_member
+        WHERE user_id = NULLIF(current_setting('app.user_id', true), '')::bigint
+      )
+    )
+  );
+
+ALTER TABLE task ENABLE ROW LEVEL SECURITY;
+
+-- The tasks of the projects visible to the ctx (i.e., by the project policy).
+CREATE POLICY task_ctx ON task
+  USING (project_id IN (SELECT id FROM project));
+
+-- The grants of the RLS roles (when provisioned, see `00-recreate-db.sql`),
+-- for the current and future tables.
+DO $$
+BEGIN
+  IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'app_rls')
+     AND EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'app_rls_bypass') THEN
+    GRANT SELECT, INSERT, UPDATE, DELETE ON ALL TABLES IN SCHEMA public
+      TO app_rls, app_rls_bypass;
+    GRANT USAGE, SELECT ON ALL SEQUENCES IN SCHEMA public
+      TO app_rls, app_rls_bypass;
+    ALTER DEFAULT PRIVILEGES IN SCHEMA public
+      GRANT SELECT, INSERT, UPDATE, DELETE ON TABLES TO app_rls, app_rls_bypass;
+    ALTER DEFAULT PRIVILEGES IN SCHEMA public
+      GRANT USAGE, SELECT ON SEQUENCES TO app_rls, app_rls_bypass;
+  ELSE
+    RAISE NOTICE 'app_rls roles not provisioned, SERVICE_DB_RLS cannot be enabled';
+  END IF;
+END
+$$;
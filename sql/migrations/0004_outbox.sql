@@ -0,0 +1,16 @@
+---- Transactional outbox of the domain events (see `lib_core::model::outbox`)
+
+CREATE TABLE outbox (
+  -- PK (i.e., the event order)
+  id BIGINT GENERATED BY DEFAULT AS IDENTITY PRIMARY KEY,
+
+  -- Properties
+  event_type varchar(64) NOT NULL,
+  -- The event, as `{"type": .., "data": {..}}`
+  payload jsonb NOT NULL,
+
+  -- Timestamps
+  --   (acting user_id/time)
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL
+);
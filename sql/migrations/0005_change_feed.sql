@@ -0,0 +1,43 @@
+---- Change feed notifications of the task and project writes (see `lib_core::model::change_feed`)
+
+-- Notifies the `entity_change` channel of the row change
+-- (i.e., `{"entity": "task", "id": .., "project_id": .., "action": "Update"}`,
+--  with the `EntityAction` names, the trash moves being the `dtime` updates).
+-- Note: The notifications are sent on the transaction commit.
+CREATE FUNCTION notify_entity_change() RETURNS trigger AS $$
+DECLARE
+  r RECORD;
+BEGIN
+  IF TG_OP = 'DELETE' THEN
+    r := OLD;
+  ELSE
+    r := NEW;
+  END IF;
+
+  PERFORM pg_notify('entity_change', json_build_object(
+    'entity', TG_TABLE_NAME,
+    'id', r.id,
+    'project_id', CASE TG_TABLE_NAME
+      WHEN 'project' THEN r.id
+      ELSE (to_jsonb(r) ->> 'project_id')::bigint
+    END,
+    'action', CASE
+      WHEN TG_OP = 'INSERT' THEN 'Create'
+      WHEN TG_OP = 'DELETE' THEN 'Purge'
+      WHEN OLD.dtime IS NULL AND NEW.dtime IS NOT NULL THEN 'Delete'
+      WHEN OLD.dtime IS NOT NULL AND NEW.dtime IS NULL THEN 'Restore'
+      ELSE 'Update'
+    END
+  )::text);
+
+  RETURN NULL;
+END;
+$$ LANGUAGE plpgsql;
+
+CREATE TRIGGER project_notify_entity_change
+  AFTER INSERT OR UPDATE OR DELETE ON project
+  FOR EACH ROW EXECUTE FUNCTION notify_entity_change();
+
+CREATE TRIGGER task_notify_entity_change
+  AFTER INSERT OR UPDATE OR DELETE ON task
+  FOR EACH ROW EXECUTE FUNCTION notify_entity_change();
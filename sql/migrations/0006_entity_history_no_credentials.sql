@@ -0,0 +1,7 @@
+---- Remove the credential columns from the recorded `user` history diffs
+---- (no longer recorded, see `lib_core::model::base::audit`)
+
+UPDATE entity_history
+SET diff = diff - 'pwd' - 'pwd_salt' - 'token_salt'
+WHERE entity = 'user'
+  AND diff ?| array['pwd', 'pwd_salt', 'token_salt'];
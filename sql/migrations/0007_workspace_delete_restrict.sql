@@ -0,0 +1,8 @@
+---- Keep the projects (and so their tasks) from being deleted with their workspace
+---- (i.e., bypassing the trash), see `lib_core::model::workspace`
+
+ALTER TABLE project DROP CONSTRAINT fk_workspace;
+
+ALTER TABLE project ADD CONSTRAINT fk_workspace
+  FOREIGN KEY (workspace_id) REFERENCES workspace(id)
+  ON DELETE RESTRICT;
@@ -0,0 +1,38 @@
+---- Narrow the RLS roles grants (see `0003_row_level_security`) to the tables,
+---- and the privileges, used by the model layer queries of a ctx (see `Dbx` RLS session)
+
+-- Note: The new tables are not granted by default anymore,
+--       so their migration must grant them to the RLS roles as needed.
+DO $$
+BEGIN
+  IF EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'app_rls')
+     AND EXISTS (SELECT 1 FROM pg_roles WHERE rolname = 'app_rls_bypass') THEN
+    ALTER DEFAULT PRIVILEGES IN SCHEMA public
+      REVOKE ALL ON TABLES FROM app_rls, app_rls_bypass;
+    ALTER DEFAULT PRIVILEGES IN SCHEMA public
+      REVOKE ALL ON SEQUENCES FROM app_rls, app_rls_bypass;
+    REVOKE ALL ON ALL TABLES IN SCHEMA public FROM app_rls, app_rls_bypass;
+    REVOKE ALL ON ALL SEQUENCES IN SCHEMA public FROM app_rls, app_rls_bypass;
+
+    -- The users are created by the root ctx only (e.g., at signup),
+    -- and updated by their own ctx (i.e., their password).
+    GRANT SELECT, UPDATE ON "user" TO app_rls;
+    GRANT SELECT, INSERT, UPDATE, DELETE ON "user" TO app_rls_bypass;
+
+    GRANT SELECT, INSERT, UPDATE, DELETE
+      ON workspace, workspace_member, project, project_member, task,
+         task_comment, label
+      TO app_rls, app_rls_bypass;
+    GRANT SELECT, INSERT, DELETE
+      ON task_dependency, task_label, attachment
+      TO app_rls, app_rls_bypass;
+
+    -- The history rows are immutable.
+    GRANT SELECT, INSERT ON entity_history TO app_rls, app_rls_bypass;
+    -- The events are only recorded (i.e., relayed with the connection role).
+    GRANT INSERT ON outbox TO app_rls, app_rls_bypass;
+  ELSE
+    RAISE NOTICE 'app_rls roles not provisioned, SERVICE_DB_RLS cannot be enabled';
+  END IF;
+END
+$$;
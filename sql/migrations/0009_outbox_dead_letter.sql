@@ -0,0 +1,20 @@
+---- Dead letters of the outbox relay (see `lib_core::model::outbox`)
+
+-- The outbox events which cannot be delivered (e.g., not a valid `DomainEvent`),
+-- moved out of the outbox so they do not block the next ones.
+CREATE TABLE outbox_dead_letter (
+  -- PK (i.e., the outbox event id)
+  id BIGINT PRIMARY KEY,
+
+  -- Properties
+  event_type varchar(64) NOT NULL,
+  payload jsonb NOT NULL,
+  -- Why the event cannot be delivered
+  cause text NOT NULL,
+
+  -- Timestamps
+  --   (acting user_id/time of the event, and its dead letter time)
+  cid bigint NOT NULL,
+  ctime timestamp with time zone NOT NULL,
+  dead_letter_time timestamp with time zone NOT NULL
+);
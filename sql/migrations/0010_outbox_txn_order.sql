@@ -0,0 +1,7 @@
+---- The outbox events transaction id, for the relay to deliver them in transaction
+---- order, without skipping the events of the still running ones
+---- (see `lib_core::model::outbox`)
+
+ALTER TABLE outbox ADD COLUMN txn_id xid8 NOT NULL DEFAULT pg_current_xact_id();
+
+CREATE INDEX idx_outbox_txn_id ON outbox (txn_id, id);
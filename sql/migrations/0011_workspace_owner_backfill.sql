@@ -0,0 +1,22 @@
+---- The owners of the workspaces left without one by the `0002_workspace` backfill
+---- (e.g., the "Default" workspace, with all its users as 'Member')
+
+-- The owners of the workspace projects.
+UPDATE workspace_member m SET role = 'Owner', mid = 0, mtime = now()
+  FROM project p
+  WHERE p.workspace_id = m.workspace_id AND p.owner_id = m.user_id
+    AND NOT EXISTS (
+      SELECT 1 FROM workspace_member o
+      WHERE o.workspace_id = m.workspace_id AND o.role = 'Owner'
+    );
+
+-- Otherwise (e.g., no project owner member), the earliest user of the workspace.
+UPDATE workspace_member SET role = 'Owner', mid = 0, mtime = now()
+  WHERE id IN (
+    SELECT DISTINCT ON (m.workspace_id) m.id FROM workspace_member m
+    WHERE NOT EXISTS (
+      SELECT 1 FROM workspace_member o
+      WHERE o.workspace_id = m.workspace_id AND o.role = 'Owner'
+    )
+    ORDER BY m.workspace_id, m.user_id
+  );
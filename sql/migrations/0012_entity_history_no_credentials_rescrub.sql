@@ -0,0 +1,7 @@
+---- Remove again the credential columns from the recorded `user` history diffs
+---- (e.g., the ones recorded after `0006` by the instances not upgraded yet)
+
+UPDATE entity_history
+SET diff = diff - 'pwd' - 'pwd_salt' - 'token_salt'
+WHERE entity = 'user'
+  AND diff ?| array['pwd', 'pwd_salt', 'token_salt'];